@@ -45,8 +45,8 @@ pub enum PercolatorError {
     #[msg("No position found")]
     NoPosition,
 
-    /// Question exceeds maximum length (256 bytes).
-    #[msg("Question too long (max 256 bytes)")]
+    /// Question exceeds `config.max_question_len` (at most 256 bytes).
+    #[msg("Question too long")]
     QuestionTooLong,
 
     /// Deadline must be in the future.
@@ -72,5 +72,354 @@ pub enum PercolatorError {
     /// Invalid outcome value.
     #[msg("Invalid outcome")]
     InvalidOutcome,
+
+    /// Amount must be greater than zero.
+    #[msg("Amount must be > 0")]
+    ZeroAmount,
+
+    /// The creator has no liquidity left to reclaim.
+    #[msg("No creator liquidity to reclaim")]
+    NoCreatorLiquidity,
+
+    /// Basis-point value exceeds 10000.
+    #[msg("Basis points must be <= 10000")]
+    InvalidBasisPoints,
+
+    /// Only the protocol authority can perform this action.
+    #[msg("Unauthorized: not the protocol authority")]
+    UnauthorizedAuthority,
+
+    /// A deadline extension must move the deadline later.
+    #[msg("New deadline must be later than the current deadline")]
+    DeadlineNotExtended,
+
+    /// The extension exceeds `config.max_extension_secs`.
+    #[msg("Deadline extension too long")]
+    ExtensionTooLong,
+
+    /// The market has used all of its deadline extensions.
+    #[msg("Deadline extension limit reached")]
+    ExtensionLimitReached,
+
+    /// The market is frozen by the protocol authority.
+    #[msg("Market is frozen")]
+    MarketFrozen,
+
+    /// Withdrawal would take the treasury below its rent-exempt minimum.
+    #[msg("Insufficient treasury balance")]
+    InsufficientTreasuryBalance,
+
+    /// The oracle reading predates the market deadline, or was published
+    /// more than `config.max_reading_staleness_secs` after it.
+    #[msg("Oracle reading is outside the window after the market deadline")]
+    StaleOracleReading,
+
+    /// The instruction does not apply to this market's rule.
+    #[msg("Instruction not supported for this market rule")]
+    InvalidMarketRule,
+
+    /// The creator has been banned by the protocol authority.
+    #[msg("Creator is banned from creating markets")]
+    CreatorBanned,
+
+    /// The commit deadline must fall between now and the market deadline.
+    #[msg("Invalid commit deadline")]
+    InvalidCommitDeadline,
+
+    /// Commit-reveal markets only accept bets through `commit_bet`.
+    #[msg("Market requires commit-reveal betting")]
+    CommitRevealRequired,
+
+    /// The market does not use commit-reveal betting.
+    #[msg("Market does not use commit-reveal betting")]
+    CommitRevealDisabled,
+
+    /// The commit phase has ended.
+    #[msg("Commit phase has closed")]
+    CommitPhaseClosed,
+
+    /// Reveals are only accepted between the commit deadline and the market deadline.
+    #[msg("Reveal phase is not open")]
+    RevealPhaseNotOpen,
+
+    /// The revealed side, amount and nonce do not hash to the commitment.
+    #[msg("Reveal does not match commitment")]
+    CommitmentMismatch,
+
+    /// The commitment can still be revealed.
+    #[msg("Commitment is still revealable")]
+    CommitmentStillRevealable,
+
+    /// Leaf count is zero or above `MerkleDistribution::MAX_LEAVES`.
+    #[msg("Invalid Merkle leaf count")]
+    InvalidLeafCount,
+
+    /// The posted total exceeds what the vault holds.
+    #[msg("Settlement root total exceeds vault balance")]
+    SettlementRootExceedsVault,
+
+    /// The Merkle proof does not match the settlement root.
+    #[msg("Invalid Merkle proof")]
+    InvalidMerkleProof,
+
+    /// The market settles through a Merkle root, not per-position `settle`.
+    #[msg("Market uses Merkle settlement")]
+    MerkleSettlementActive,
+
+    /// The creator already has `config.max_markets_per_creator` open markets.
+    #[msg("Creator has reached the open market limit")]
+    CreatorMarketLimit,
+
+    /// Every winning position has already been settled.
+    #[msg("Settlement count would exceed winning positions")]
+    SettlementOverflow,
+
+    /// The oracle's resolution window has not ended yet.
+    #[msg("Resolution window is still open")]
+    ResolutionWindowOpen,
+
+    /// Resolution window length must be positive, or resolve_default would
+    /// be callable the moment the deadline passes.
+    #[msg("Invalid resolution window")]
+    InvalidResolutionWindow,
+
+    /// Protocol-wide volume for the current window would exceed the cap.
+    #[msg("Protocol volume circuit breaker tripped")]
+    VolumeCircuitBreaker,
+
+    /// Winning positions are settled, not closed.
+    #[msg("Position is on the winning side")]
+    WinningPosition,
+
+    /// The market was cancelled; only refunds are available.
+    #[msg("Market has been cancelled")]
+    MarketCancelled,
+
+    /// Schedule entries must be in the future and after every existing entry.
+    #[msg("Invalid oracle schedule entry")]
+    InvalidScheduleEntry,
+
+    /// The oracle schedule has no room for more entries.
+    #[msg("Oracle schedule is full")]
+    OracleScheduleFull,
+
+    /// The market resolves via an oracle schedule that was not provided.
+    #[msg("Oracle schedule account required")]
+    MissingOracleSchedule,
+
+    /// The market creator may not bet on this market.
+    #[msg("Creator cannot bet on this market")]
+    CreatorCannotBet,
+
+    /// The market oracle may not bet on this market.
+    #[msg("Oracle cannot bet on this market")]
+    OracleCannotBet,
+
+    /// The market is younger than `config.min_resolution_delay_secs`.
+    #[msg("Market cannot be resolved this soon after creation")]
+    ResolvedTooSoon,
+
+    /// Market terms cannot change after the first bet. A commit-reveal
+    /// question cannot change at all, since sealed bets are not in the pools.
+    #[msg("Market already has bets")]
+    MarketHasBets,
+
+    /// The oracle already used its resolution-window extension.
+    #[msg("Resolution window already extended")]
+    ResolutionAlreadyExtended,
+
+    /// The resolution window has already ended.
+    #[msg("Resolution window has ended")]
+    ResolutionWindowEnded,
+
+    /// Cancelling after the deadline needs the protocol authority's co-signature.
+    #[msg("Cancellation after the deadline requires authority co-sign")]
+    CancelAfterDeadline,
+
+    /// A scalar rule needs `lower < upper`.
+    #[msg("Invalid scalar range")]
+    InvalidScalarRange,
+
+    /// Another settlement landed since the caller read `settlements_count`.
+    #[msg("Settle order is stale")]
+    StaleSettleOrder,
+
+    /// The config cannot close while any market is still active.
+    #[msg("Markets are still active")]
+    MarketsStillActive,
+
+    /// `question_uri` exceeds 128 bytes.
+    #[msg("Question URI too long (max 128 bytes)")]
+    QuestionUriTooLong,
+
+    /// A batch must hold 1..=`MAX_BATCH_MARKETS` entries, with one account
+    /// group per entry.
+    #[msg("Invalid batch size")]
+    InvalidBatchSize,
+
+    /// The parent market account is missing or does not match the parent
+    /// market named at creation.
+    #[msg("Invalid parent market")]
+    InvalidParentMarket,
+
+    /// A conditional market cannot resolve before its parent.
+    #[msg("Parent market not resolved")]
+    ParentNotResolved,
+
+    /// An early-bird bonus is paid from the creator subsidy, so needs one.
+    #[msg("Early-bird bonus requires a creator subsidy")]
+    EarlyBirdWithoutSubsidy,
+
+    /// The vault cannot pay winners at the market's h-ratio floor.
+    #[msg("h-ratio below the market floor; vault top-up required")]
+    HRatioBelowFloor,
+
+    /// Time-weighted profit shares and an early-bird bonus are exclusive.
+    #[msg("Time weighting cannot be combined with an early-bird bonus")]
+    InvalidScoringMode,
+
+    /// `token_mint` is not an initialized SPL Token mint.
+    #[msg("Token mint is not an initialized SPL mint")]
+    InvalidTokenMint,
+
+    /// The oracle tried to resolve in the same slot as the latest bet.
+    #[msg("Cannot resolve in the same slot as a bet")]
+    ResolveSameSlotAsBet,
+
+    /// The oracle missed its heartbeat; automated resolution is refused.
+    #[msg("Oracle heartbeat is stale; use resolve_default")]
+    OracleHeartbeatStale,
+
+    /// Cash-out is only offered while the market is frozen.
+    #[msg("Market is not frozen")]
+    MarketNotFrozen,
+
+    /// A no-loss market needs a prize to distribute.
+    #[msg("No-loss market requires a creator prize")]
+    NoLossWithoutPrize,
+
+    /// Only the proposed fee collector can accept the handover.
+    #[msg("Signer is not the pending fee collector")]
+    NotPendingFeeCollector,
+
+    /// The config requires the oracle to differ from the creator.
+    #[msg("Oracle must differ from the market creator")]
+    OracleSameAsCreator,
+
+    /// The bet would push one side past the market's imbalance limit.
+    #[msg("Bet would make the market too one-sided")]
+    MarketTooImbalanced,
+
+    /// The position's tokens are still held, by the user or anyone they went
+    /// to; they must be redeemed through `claim_refund`.
+    #[msg("Position tokens still held; use claim_refund")]
+    PositionTokensHeld,
+
+    /// The secondary oracle must differ from the primary oracle.
+    #[msg("Secondary oracle must differ from the primary oracle")]
+    InvalidSecondaryOracle,
+
+    /// The market has a secondary oracle but its reading was not supplied.
+    #[msg("Secondary oracle reading required")]
+    MissingSecondaryReading,
+
+    /// `creator_subsidy` is below `expected_max_pool × min_seed_ratio_bps`.
+    #[msg("Creator subsidy does not meet the seed commitment")]
+    SeedTooSmall,
+
+    /// A seeded market's h-ratio would resolve below its floor.
+    #[msg("Seeded market would resolve below its h-ratio floor; add liquidity first")]
+    SeededMarketBelowFloor,
+
+    /// The market has already paid out Merkle claims, which leave positions
+    /// unsettled.
+    #[msg("Market has already paid out Merkle claims; cannot force-cancel")]
+    PayoutsAlreadyMade,
+
+    /// The claim deadline must fall after the resolution window.
+    #[msg("Claim deadline must be after the resolution window")]
+    InvalidClaimDeadline,
+
+    /// The market has no claim deadline, or it has not passed yet.
+    #[msg("Claim deadline not reached")]
+    ClaimDeadlineNotReached,
+
+    /// Price exponent outside the supported range.
+    #[msg("Price exponent must be between -18 and 0")]
+    InvalidPriceExponent,
+
+    /// The position is on the other side.
+    #[msg("Position is on the other side")]
+    PositionSideMismatch,
+
+    /// The market refunds by token balance; use `claim_refund`.
+    #[msg("Market refunds by token balance; use claim_refund")]
+    RefundByTokenBalance,
+
+    /// A side's pool is below `min_pool_per_side`; cancel the market instead.
+    #[msg("One side's pool is below the market minimum; cancel instead")]
+    SideTooThin,
+
+    /// A late-settlement penalty is due but no treasury account was passed.
+    #[msg("Late-settlement penalty due; pass the treasury account")]
+    TreasuryRequired,
+
+    /// The market cannot move from its current status to the requested one.
+    #[msg("Invalid market status transition")]
+    InvalidStatusTransition,
+
+    /// A side's position-token supply does not match its pool.
+    #[msg("Position token supply does not match the pool")]
+    InvariantViolation,
+
+    /// A settlement receipt was missing in a market that issues them, or
+    /// passed in one that does not.
+    #[msg("Settlement receipt must be passed exactly when the market issues them")]
+    SettlementReceiptMismatch,
+
+    /// A new or extended deadline is further out than
+    /// `config.max_market_duration_secs` from now.
+    #[msg("Deadline exceeds the maximum market duration")]
+    DeadlineTooFar,
+
+    /// `max_market_duration_secs` must not be negative.
+    #[msg("Invalid maximum market duration")]
+    InvalidMarketDuration,
+
+    /// The market was created with creator stats; pass its stats account.
+    #[msg("Creator stats account required for this market")]
+    CreatorStatsRequired,
+
+    /// The account is already at the current layout.
+    #[msg("Account already migrated")]
+    AlreadyMigrated,
+
+    /// The market predates versioning; run `migrate_market` first.
+    #[msg("Market must be migrated first")]
+    MarketNotMigrated,
+
+    /// More terminal markets than markets ever created.
+    #[msg("Terminal market count exceeds total markets")]
+    InvalidTerminalMarkets,
+
+    /// Position NFT accounts must be passed exactly for `position_nfts` markets.
+    #[msg("Position NFT accounts do not match the market")]
+    PositionNftMismatch,
+
+    /// A position NFT records a single bet; the position already has one.
+    #[msg("Position NFT already minted")]
+    PositionNftAlreadyMinted,
+
+    /// The instruction works on fungible position tokens only.
+    #[msg("Not available for position-NFT markets")]
+    PositionNftsUnsupported,
+
+    /// `max_protocol_volume` is set without a positive `volume_window_secs`.
+    #[msg("Volume window must be positive while a volume cap is set")]
+    InvalidVolumeWindow,
+
+    /// A duration setting in `update_config` was negative.
+    #[msg("Durations must not be negative")]
+    NegativeDuration,
 }
 
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{BetSide, MarketStatus, Outcome, ResolutionSource};
+
+/// Emitted when a creator pushes a market's deadline later.
+#[event]
+pub struct DeadlineExtended {
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub old_deadline: i64,
+    pub new_deadline: i64,
+    pub extensions_count: u8,
+}
+
+/// Emitted when a market is cancelled; bettors can now claim refunds.
+#[event]
+pub struct MarketCancelled {
+    pub market: Pubkey,
+    pub market_id: u64,
+    /// `yes_pool + no_pool` at cancellation.
+    pub refundable_total: u64,
+    pub cancelled_by: Pubkey,
+    pub cancelled_at: i64,
+}
+
+/// Emitted on every `place_bet`.
+#[event]
+pub struct BetPlaced {
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub bettor: Pubkey,
+    pub side: BetSide,
+    /// Net stake after fees.
+    pub stake: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    /// The position's memo, as set on its first bet.
+    pub memo: [u8; 16],
+    /// Pools just after the position's first bet (its entry odds).
+    pub entry_yes_pool: u64,
+    pub entry_no_pool: u64,
+}
+
+/// Emitted when a market resolves, by any path.
+#[event]
+pub struct MarketResolved {
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub outcome: Outcome,
+    pub h_ratio_bps: u16,
+    /// YES share of the pool for a scalar or blended outcome.
+    pub yes_share_bps: u16,
+    pub resolved_via: ResolutionSource,
+}
+
+/// Emitted when the protocol authority force-cancels a wedged market.
+/// Always accompanied by `MarketCancelled`.
+#[event]
+pub struct MarketForceCancelled {
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub previous_status: MarketStatus,
+    pub previous_outcome: Outcome,
+    pub authority: Pubkey,
+    pub cancelled_at: i64,
+    /// Stake and lamports settled before the cancel; those payouts stand.
+    pub settled_stake: u64,
+    pub settled_amount: u64,
+}
+
+/// Emitted whenever a market's h-ratio is (re)computed.
+#[event]
+pub struct HRatioUpdated {
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub old_h_ratio_bps: u16,
+    pub new_h_ratio_bps: u16,
+}
+
+/// Emitted when an oracle pushes a market's resolution window back.
+#[event]
+pub struct ResolutionExtended {
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub oracle: Pubkey,
+    pub old_window_end: i64,
+    pub new_window_end: i64,
+}
+
+/// Emitted on every winning settlement, including the one that moves the
+/// market to Settled.
+#[event]
+pub struct SettlementProgress {
+    pub market: Pubkey,
+    pub market_id: u64,
+    /// Winning positions paid so far.
+    pub settlements_count: u64,
+    pub winning_positions: u32,
+    /// Lamports paid out so far, crank fees and early-settlement bonuses
+    /// included.
+    pub settled_amount: u64,
+    /// Vault balance after this payout.
+    pub vault_balance: u64,
+    /// Whether this settlement completed the market.
+    pub market_settled: bool,
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct AcceptFeeCollector<'info> {
+    /// The proposed fee collector, proving control of the wallet.
+    #[account(
+        constraint = config.pending_fee_collector == Some(new_fee_collector.key())
+            @ PercolatorError::NotPendingFeeCollector,
+    )]
+    pub new_fee_collector: Signer<'info>,
+
+    /// Global config PDA.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+pub fn handler(ctx: Context<AcceptFeeCollector>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let old_fee_collector = config.fee_collector;
+    config.fee_collector = ctx.accounts.new_fee_collector.key();
+    config.pending_fee_collector = None;
+
+    msg!(
+        "Fee collector changed: {} -> {}",
+        old_fee_collector,
+        config.fee_collector,
+    );
+
+    Ok(())
+}
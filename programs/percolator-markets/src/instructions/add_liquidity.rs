@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    /// Market creator — the only account allowed to subsidize the vault.
+    #[account(
+        mut,
+        constraint = creator.key() == market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: Signer<'info>,
+
+    /// The open market receiving liquidity.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Market vault — receives the liquidity.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddLiquidity>, amount: u64) -> Result<()> {
+    require!(amount > 0, PercolatorError::ZeroAmount);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.creator.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    // Liquidity raises the vault balance used for the h-ratio, but is not
+    // added to either pool — it is a subsidy, not a bet.
+    let market = &mut ctx.accounts.market;
+    market.creator_liquidity = market.creator_liquidity.checked_add(amount)
+        .ok_or(PercolatorError::Overflow)?;
+
+    msg!(
+        "Liquidity added: {} lamports to market #{} (total creator liquidity: {})",
+        amount,
+        market.market_id,
+        market.creator_liquidity,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::events::MarketForceCancelled;
+use crate::instructions::cancel::cancel;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct AdminForceCancel<'info> {
+    /// Protocol authority.
+    #[account(
+        constraint = authority.key() == config.authority @ PercolatorError::UnauthorizedAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global config — identifies the authority; counts terminal markets.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// The market to cancel — any status short of Settled/Cancelled.
+    #[account(
+        mut,
+        constraint = market.status != MarketStatus::Settled
+            && market.status != MarketStatus::Cancelled
+            @ PercolatorError::InvalidMarketStatus,
+        // Merkle claims leave positions unsettled, so they could refund again
+        constraint = !market.merkle_settlement || market.settlements_count == 0
+            @ PercolatorError::PayoutsAlreadyMade,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Creator stats — frees an active-market slot once the market closes.
+    /// Omitted only for markets created before creator stats existed.
+    #[account(
+        mut,
+        seeds = [b"creator_stats", market.creator.as_ref()],
+        bump = creator_stats.bump,
+    )]
+    pub creator_stats: Option<Account<'info, CreatorStats>>,
+}
+
+pub fn handler(ctx: Context<AdminForceCancel>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let previous_status = market.status;
+    let previous_outcome = market.outcome;
+
+    // Resolution folded the matched subsidy into the profit pool; hand the
+    // part settled winners haven't taken back to the creator's escrow so
+    // refunds don't pay it out
+    if previous_status == MarketStatus::Resolved {
+        let unpaid = market.unpaid_subsidy();
+        market.creator_subsidy = market.creator_subsidy.checked_add(unpaid)
+            .ok_or(PercolatorError::Overflow)?;
+        market.subsidy_applied = 0;
+    }
+
+    cancel(
+        market,
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        ctx.accounts.authority.key(),
+    )?;
+
+    emit!(MarketForceCancelled {
+        market: market.key(),
+        market_id: market.market_id,
+        previous_status,
+        previous_outcome,
+        authority: ctx.accounts.authority.key(),
+        cancelled_at: market.cancelled_at,
+        settled_stake: market.settled_stake,
+        settled_amount: market.settled_amount,
+    });
+
+    msg!(
+        "EMERGENCY: market #{} force-cancelled by authority {} ({} lamports already settled)",
+        market.market_id,
+        ctx.accounts.authority.key(),
+        market.settled_amount,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct AmendQuestion<'info> {
+    /// Market creator.
+    #[account(
+        constraint = creator.key() == market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: Signer<'info>,
+
+    /// Global config — question length limit.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// The market to amend.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+pub fn handler(ctx: Context<AmendQuestion>, question: String) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    // Terms are immutable once anyone has wagered on them
+    let total_pool = market.yes_pool.checked_add(market.no_pool)
+        .ok_or(PercolatorError::Overflow)?;
+    require!(total_pool == 0, PercolatorError::MarketHasBets);
+    // Sealed commitments stay out of the pools until revealed
+    require!(market.commit_deadline == 0, PercolatorError::MarketHasBets);
+    require!(
+        question.len() <= ctx.accounts.config.question_limit(),
+        PercolatorError::QuestionTooLong
+    );
+
+    market.question = question;
+
+    msg!("Market #{} question amended: {}", market.market_id, market.question);
+
+    Ok(())
+}
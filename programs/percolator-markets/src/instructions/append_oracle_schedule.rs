@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct AppendOracleSchedule<'info> {
+    /// Market creator — pays for the schedule on first use.
+    #[account(
+        mut,
+        constraint = creator.key() == market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: Signer<'info>,
+
+    /// The market whose oracle rotates.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open || market.status == MarketStatus::Closed
+            @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Oracle schedule PDA.
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = OracleSchedule::SIZE,
+        seeds = [b"oracle_schedule", market.key().as_ref()],
+        bump,
+    )]
+    pub oracle_schedule: Account<'info, OracleSchedule>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AppendOracleSchedule>, effective_at: i64, oracle: Pubkey) -> Result<()> {
+    let clock = Clock::get()?;
+    let schedule = &mut ctx.accounts.oracle_schedule;
+
+    // Past and active entries are immutable: only append strictly later ones
+    require!(effective_at > clock.unix_timestamp, PercolatorError::InvalidScheduleEntry);
+    if let Some(last) = schedule.entries.last() {
+        require!(effective_at > last.effective_at, PercolatorError::InvalidScheduleEntry);
+    }
+    require!(
+        schedule.entries.len() < OracleSchedule::MAX_ENTRIES,
+        PercolatorError::OracleScheduleFull
+    );
+
+    schedule.market = ctx.accounts.market.key();
+    schedule.bump = ctx.bumps.oracle_schedule;
+    schedule.entries.push(OracleScheduleEntry { effective_at, oracle });
+
+    let market = &mut ctx.accounts.market;
+    market.has_oracle_schedule = true;
+
+    msg!(
+        "Market #{} oracle {} scheduled from {}",
+        market.market_id,
+        oracle,
+        effective_at,
+    );
+
+    Ok(())
+}
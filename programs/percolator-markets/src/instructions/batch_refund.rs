@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+
+use crate::errors::PercolatorError;
+use crate::instructions::claim_refund::refund_position;
+use crate::state::*;
+
+/// Most positions one `batch_refund` call may refund (signatures and
+/// compute budget).
+pub const MAX_BATCH_REFUND: usize = 5;
+
+#[derive(Accounts)]
+pub struct BatchRefund<'info> {
+    /// Submits the batch and pays the transaction fee.
+    pub payer: Signer<'info>,
+
+    /// The cancelled market.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Cancelled @ PercolatorError::InvalidMarketStatus,
+        constraint = !market.position_nfts @ PercolatorError::PositionNftsUnsupported,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Market vault — source of the refunds.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// YES token mint.
+    #[account(
+        mut,
+        seeds = [b"yes_mint", market.key().as_ref()],
+        bump,
+    )]
+    pub yes_mint: Account<'info, Mint>,
+
+    /// NO token mint.
+    #[account(
+        mut,
+        seeds = [b"no_mint", market.key().as_ref()],
+        bump,
+    )]
+    pub no_mint: Account<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Refund the positions passed in `remaining_accounts` as
+/// `(position, user, token_account)` groups, all writable.
+///
+/// Each user must sign, since burning their position tokens needs the
+/// token owner's authority. Each refund is computed and paid as
+/// `claim_refund` would. Returns the total refunded.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, BatchRefund<'info>>) -> Result<u64> {
+    let accounts = ctx.remaining_accounts;
+    require!(
+        !accounts.is_empty()
+            && accounts.len().is_multiple_of(3)
+            && accounts.len() / 3 <= MAX_BATCH_REFUND,
+        PercolatorError::InvalidBatchSize
+    );
+
+    let market_key = ctx.accounts.market.key();
+    let vault = ctx.accounts.vault.to_account_info();
+    let mut total: u64 = 0;
+    for group in accounts.chunks(3) {
+        let mut position = Account::<UserPosition>::try_from(&group[0])?;
+        let user = &group[1];
+        let token_account = Account::<TokenAccount>::try_from(&group[2])?;
+
+        require_keys_eq!(position.market, market_key, PercolatorError::NoPosition);
+        require_keys_eq!(position.user, user.key(), PercolatorError::NoPosition);
+        require!(!position.settled, PercolatorError::AlreadySettled);
+        require!(user.is_signer, ErrorCode::AccountNotSigner);
+
+        let mint = match position.side {
+            BetSide::Yes => ctx.accounts.yes_mint.to_account_info(),
+            BetSide::No => ctx.accounts.no_mint.to_account_info(),
+        };
+        require_keys_eq!(token_account.mint, mint.key(), PercolatorError::NoPosition);
+
+        let stake = ctx.accounts.market.refund_stake(position.deposited, token_account.amount);
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint,
+                    from: group[2].clone(),
+                    authority: user.clone(),
+                },
+            ),
+            stake,
+        )?;
+
+        let refund = refund_position(&mut ctx.accounts.market, &mut position, stake, &vault, user)?;
+        position.exit(ctx.program_id)?;
+
+        total = total.checked_add(refund).ok_or(PercolatorError::Overflow)?;
+    }
+
+    msg!(
+        "Batch refund: {} lamports to {} positions in market #{}",
+        total,
+        accounts.len() / 3,
+        ctx.accounts.market.market_id,
+    );
+
+    Ok(total)
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
 use crate::errors::PercolatorError;
+use crate::events::MarketCancelled;
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -12,6 +13,20 @@ pub struct CancelMarket<'info> {
     )]
     pub authority: Signer<'info>,
 
+    /// Protocol authority co-signer — required once the deadline has passed.
+    #[account(
+        constraint = protocol_authority.key() == config.authority @ PercolatorError::UnauthorizedAuthority,
+    )]
+    pub protocol_authority: Option<Signer<'info>>,
+
+    /// Global config — identifies the protocol authority; counts terminal markets.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
     /// The market to cancel.
     #[account(
         mut,
@@ -19,11 +34,38 @@ pub struct CancelMarket<'info> {
             @ PercolatorError::CannotCancelResolved,
     )]
     pub market: Account<'info, Market>,
+
+    /// Creator stats — frees an active-market slot once the market closes.
+    /// Omitted only for markets created before creator stats existed.
+    #[account(
+        mut,
+        seeds = [b"creator_stats", market.creator.as_ref()],
+        bump = creator_stats.bump,
+    )]
+    pub creator_stats: Option<Account<'info, CreatorStats>>,
 }
 
 pub fn handler(ctx: Context<CancelMarket>) -> Result<()> {
+    // After the deadline the outcome may be known; cancelling then would let
+    // the creator dodge a losing resolution unless the protocol agrees
+    let clock = Clock::get()?;
+    let past_deadline = ctx.accounts.market.status == MarketStatus::Closed
+        || clock.unix_timestamp >= ctx.accounts.market.deadline;
+    // A market too thin to resolve has no outcome to dodge
+    require!(
+        !past_deadline
+            || ctx.accounts.protocol_authority.is_some()
+            || ctx.accounts.market.is_side_too_thin(),
+        PercolatorError::CancelAfterDeadline
+    );
+
     let market = &mut ctx.accounts.market;
-    market.status = MarketStatus::Cancelled;
+    cancel(
+        market,
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        ctx.accounts.authority.key(),
+    )?;
 
     msg!(
         "Market #{} cancelled by {}",
@@ -34,3 +76,33 @@ pub fn handler(ctx: Context<CancelMarket>) -> Result<()> {
     Ok(())
 }
 
+/// Mark `market` cancelled and emit `MarketCancelled`.
+///
+/// Shared by every path that voids a market.
+pub(crate) fn cancel(
+    market: &mut Account<Market>,
+    config: &mut GlobalConfig,
+    creator_stats: Option<&mut CreatorStats>,
+    cancelled_by: Pubkey,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    market.transition(MarketStatus::Cancelled)?;
+    market.cancelled_at = clock.unix_timestamp;
+    CreatorStats::close_market(creator_stats, market)?;
+    config.market_closed();
+
+    // Stake settled before a force-cancel is not refundable
+    let refundable_total = market.yes_pool.checked_add(market.no_pool)
+        .ok_or(PercolatorError::Overflow)?
+        .saturating_sub(market.settled_stake);
+
+    emit!(MarketCancelled {
+        market: market.key(),
+        market_id: market.market_id,
+        refundable_total,
+        cancelled_by,
+        cancelled_at: market.cancelled_at,
+    });
+
+    Ok(())
+}
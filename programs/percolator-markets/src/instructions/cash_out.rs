@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CashOut<'info> {
+    /// The bettor exiting their position.
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// A frozen, unresolved market.
+    #[account(
+        mut,
+        constraint = market.frozen @ PercolatorError::MarketNotFrozen,
+        constraint = market.status == MarketStatus::Open || market.status == MarketStatus::Closed
+            @ PercolatorError::AlreadyResolved,
+        constraint = !market.position_nfts @ PercolatorError::PositionNftsUnsupported,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// User position PDA — closed, rent returned to the user.
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+        constraint = !position.settled @ PercolatorError::AlreadySettled,
+        constraint = position.user == user.key() @ PercolatorError::NoPosition,
+    )]
+    pub position: Account<'info, UserPosition>,
+
+    /// Market vault — pays the deposit back.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// YES token mint.
+    #[account(
+        mut,
+        seeds = [b"yes_mint", market.key().as_ref()],
+        bump,
+    )]
+    pub yes_mint: Account<'info, Mint>,
+
+    /// NO token mint.
+    #[account(
+        mut,
+        seeds = [b"no_mint", market.key().as_ref()],
+        bump,
+    )]
+    pub no_mint: Account<'info, Mint>,
+
+    /// The user's token account for the position side.
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<CashOut>) -> Result<()> {
+    let position = &ctx.accounts.position;
+    let deposited = position.deposited;
+    let side = position.side;
+
+    // Burn the position tokens minted 1:1 with the stake
+    let mint = match side {
+        BetSide::Yes => ctx.accounts.yes_mint.to_account_info(),
+        BetSide::No => ctx.accounts.no_mint.to_account_info(),
+    };
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint,
+                from: ctx.accounts.user_token_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        deposited,
+    )?;
+
+    // Full deposit back; no exit fee while the market is frozen
+    **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= deposited;
+    assert_vault_solvent(&ctx.accounts.vault.to_account_info(), Rent::get()?.minimum_balance(0))?;
+    **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += deposited;
+
+    // Undo the position's contribution to the market
+    let market = &mut ctx.accounts.market;
+    match side {
+        BetSide::Yes => {
+            market.yes_pool = market.yes_pool.checked_sub(deposited)
+                .ok_or(PercolatorError::Overflow)?;
+            market.yes_bonus_stake = market.yes_bonus_stake.checked_sub(position.bonus_stake)
+                .ok_or(PercolatorError::Overflow)?;
+            market.yes_weighted_stake = market.yes_weighted_stake
+                .checked_sub(position.weighted_stake)
+                .ok_or(PercolatorError::Overflow)?;
+        }
+        BetSide::No => {
+            market.no_pool = market.no_pool.checked_sub(deposited)
+                .ok_or(PercolatorError::Overflow)?;
+            market.no_bonus_stake = market.no_bonus_stake.checked_sub(position.bonus_stake)
+                .ok_or(PercolatorError::Overflow)?;
+            market.no_weighted_stake = market.no_weighted_stake
+                .checked_sub(position.weighted_stake)
+                .ok_or(PercolatorError::Overflow)?;
+        }
+    }
+    market.remove_position(side)?;
+
+    msg!(
+        "Cash-out: {} lamports returned to {} from frozen market #{}",
+        deposited,
+        ctx.accounts.user.key(),
+        market.market_id,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ClaimMerkle<'info> {
+    /// The user named in the leaf.
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// The market being settled.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Resolved @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Distribution PDA — root and claim bitmap.
+    #[account(
+        mut,
+        seeds = [b"merkle_distribution", market.key().as_ref()],
+        bump = distribution.bump,
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+
+    /// Market vault — source of payout funds.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Creator stats — frees an active-market slot once the market closes.
+    /// Omitted only for markets created before creator stats existed.
+    #[account(
+        mut,
+        seeds = [b"creator_stats", market.creator.as_ref()],
+        bump = creator_stats.bump,
+    )]
+    pub creator_stats: Option<Account<'info, CreatorStats>>,
+
+    /// Global config — counts markets reaching a terminal state.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<ClaimMerkle>,
+    index: u32,
+    payout: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let distribution = &mut ctx.accounts.distribution;
+    require!(index < distribution.leaf_count, PercolatorError::InvalidMerkleProof);
+    require!(!distribution.is_claimed(index), PercolatorError::AlreadySettled);
+
+    let leaf = MerkleDistribution::leaf(index, &ctx.accounts.user.key(), payout);
+    require!(
+        MerkleDistribution::verify(&proof, &distribution.root, leaf),
+        PercolatorError::InvalidMerkleProof
+    );
+
+    // The root's total was checked against the vault; never pay beyond it
+    let claimed_amount = distribution.claimed_amount.checked_add(payout)
+        .ok_or(PercolatorError::Overflow)?;
+    require!(
+        claimed_amount <= distribution.total_payout,
+        PercolatorError::VaultInsolvency
+    );
+    require!(
+        payout <= ctx.accounts.vault.lamports(),
+        PercolatorError::VaultInsolvency
+    );
+
+    distribution.set_claimed(index);
+    distribution.claimed_amount = claimed_amount;
+    distribution.claimed_count += 1;
+
+    // Direct lamport transfer from PDA
+    **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= payout;
+    assert_vault_solvent(&ctx.accounts.vault.to_account_info(), Rent::get()?.minimum_balance(0))?;
+    **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += payout;
+
+    let market = &mut ctx.accounts.market;
+    market.settled_amount = market.settled_amount.checked_add(payout)
+        .ok_or(PercolatorError::Overflow)?;
+    market.settlements_count = market.settlements_count.checked_add(1)
+        .ok_or(PercolatorError::Overflow)?;
+
+    // Every leaf has been claimed.
+    if distribution.claimed_count == distribution.leaf_count {
+        market.transition(MarketStatus::Settled)?;
+        CreatorStats::close_market(ctx.accounts.creator_stats.as_deref_mut(), market)?;
+        ctx.accounts.config.market_closed();
+    }
+
+    msg!(
+        "Merkle claim: user={} leaf={} payout={}, market #{}",
+        ctx.accounts.user.key(),
+        index,
+        payout,
+        market.market_id,
+    );
+
+    Ok(())
+}
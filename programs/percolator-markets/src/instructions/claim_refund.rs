@@ -40,8 +40,13 @@ pub struct ClaimRefund<'info> {
     #[account(mut)]
     pub user_token_account: Account<'info, TokenAccount>,
 
-    /// YES or NO mint (depending on the position side).
-    #[account(mut)]
+    /// YES or NO mint (depending on the position side), or the position
+    /// NFT in a `position_nfts` market.
+    #[account(
+        mut,
+        constraint = position_mint.key() == market.position_mint(&position.key(), position.side)
+            @ PercolatorError::NoPosition,
+    )]
     pub position_mint: Account<'info, Mint>,
 
     pub system_program: Program<'info, System>,
@@ -49,10 +54,13 @@ pub struct ClaimRefund<'info> {
 }
 
 pub fn handler(ctx: Context<ClaimRefund>) -> Result<()> {
-    let position = &ctx.accounts.position;
-    let refund_amount = position.deposited;
+    // Received tokens entitle their holder when refunds follow the balance
+    let stake = ctx.accounts.market.refund_stake(
+        ctx.accounts.position.deposited,
+        ctx.accounts.user_token_account.amount,
+    );
 
-    // Burn the user's position tokens
+    // Burn the user's position tokens, or their single position NFT
     let market_key = ctx.accounts.market.key();
     let creator_key = ctx.accounts.market.creator;
     let market_id_bytes = ctx.accounts.market.market_id.to_le_bytes();
@@ -73,21 +81,21 @@ pub fn handler(ctx: Context<ClaimRefund>) -> Result<()> {
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
-        refund_amount,
+        if ctx.accounts.market.position_nfts { 1 } else { stake },
     )?;
 
-    // Transfer SOL back from vault to user
-    **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= refund_amount;
-    **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += refund_amount;
-
-    // Mark position as settled (refunded)
-    let position = &mut ctx.accounts.position;
-    position.settled = true;
-    position.payout = refund_amount;
+    let refund_amount = refund_position(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.position,
+        stake,
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.user.to_account_info(),
+    )?;
 
     msg!(
-        "Refund: {} lamports returned to {} for market #{}",
+        "Refund: {} lamports (of {} staked) returned to {} for market #{}",
         refund_amount,
+        stake,
         ctx.accounts.user.key(),
         ctx.accounts.market.market_id,
     );
@@ -95,3 +103,34 @@ pub fn handler(ctx: Context<ClaimRefund>) -> Result<()> {
     Ok(())
 }
 
+
+/// Pay `position` the refund on `stake` from `vault` to `user` and mark it
+/// refunded.
+///
+/// `stake` is `Market::refund_stake`: normally `deposited`. The refund is
+/// scaled down proportionally if the vault can't cover every deposit.
+/// Shared by every refund path; the caller validates the accounts and
+/// handles any token burn. Returns the amount paid.
+pub(crate) fn refund_position<'info>(
+    market: &mut Market,
+    position: &mut UserPosition,
+    stake: u64,
+    vault: &AccountInfo<'info>,
+    user: &AccountInfo<'info>,
+) -> Result<u64> {
+    let refund_amount = market.refund_amount(stake, vault.lamports());
+
+    // Transfer SOL back from vault to user
+    **vault.try_borrow_mut_lamports()? -= refund_amount;
+    assert_vault_solvent(vault, Rent::get()?.minimum_balance(0))?;
+    **user.try_borrow_mut_lamports()? += refund_amount;
+
+    // Mark position as settled (refunded)
+    position.settled = true;
+    position.payout = refund_amount;
+
+    market.refunded_stake = market.refunded_stake.checked_add(stake)
+        .ok_or(PercolatorError::Overflow)?;
+
+    Ok(refund_amount)
+}
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::errors::PercolatorError;
+use crate::instructions::claim_refund::refund_position;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ClaimRefundByPosition<'info> {
+    /// The user claiming their refund.
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// The cancelled market.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Cancelled @ PercolatorError::InvalidMarketStatus,
+        // Without the tokens there is no balance to refund against
+        constraint = !market.refund_by_token_balance @ PercolatorError::RefundByTokenBalance,
+        // A position NFT is refunded by burning it through claim_refund
+        constraint = !market.position_nfts @ PercolatorError::PositionNftsUnsupported,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// User position PDA.
+    #[account(
+        mut,
+        seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+        constraint = !position.settled @ PercolatorError::AlreadySettled,
+        constraint = position.user == user.key() @ PercolatorError::NoPosition,
+    )]
+    pub position: Account<'info, UserPosition>,
+
+    /// Market vault.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// The user's associated token account for the position mint.
+    /// CHECK: Address is checked against the position side's mint; it must
+    /// be closed or hold no tokens.
+    #[account(
+        address = get_associated_token_address(
+            &user.key(),
+            &match position.side {
+                BetSide::Yes => market.yes_mint,
+                BetSide::No => market.no_mint,
+            },
+        ),
+    )]
+    pub user_token_account: UncheckedAccount<'info>,
+
+    /// YES token mint — its supply bounds the stake still redeemable.
+    #[account(
+        seeds = [b"yes_mint", market.key().as_ref()],
+        bump,
+    )]
+    pub yes_mint: Account<'info, Mint>,
+
+    /// NO token mint.
+    #[account(
+        seeds = [b"no_mint", market.key().as_ref()],
+        bump,
+    )]
+    pub no_mint: Account<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ClaimRefundByPosition>) -> Result<()> {
+    // Only a closed or empty token account qualifies; anyone still holding
+    // position tokens goes through `claim_refund`, which burns them.
+    let token_info = ctx.accounts.user_token_account.to_account_info();
+    if !token_info.data_is_empty() {
+        let token_account = TokenAccount::try_deserialize(&mut &token_info.data.borrow()[..])?;
+        require!(token_account.amount == 0, PercolatorError::PositionTokensHeld);
+    }
+
+    // Tokens moved to another account stay redeemable through claim_refund,
+    // so this position's stake must not be among the tokens still out
+    let deposited = ctx.accounts.position.deposited;
+    let market = &ctx.accounts.market;
+    let unrefunded = market.yes_pool
+        .saturating_add(market.no_pool)
+        .saturating_sub(market.refunded_stake);
+    let supply = ctx.accounts.yes_mint.supply.saturating_add(ctx.accounts.no_mint.supply);
+    require!(
+        supply <= unrefunded.saturating_sub(deposited),
+        PercolatorError::PositionTokensHeld
+    );
+
+    let refund_amount = refund_position(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.position,
+        deposited,
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.user.to_account_info(),
+    )?;
+
+    msg!(
+        "Refund by position: {} lamports (of {} deposited) returned to {} for market #{}",
+        refund_amount,
+        deposited,
+        ctx.accounts.user.key(),
+        ctx.accounts.market.market_id,
+    );
+
+    Ok(())
+}
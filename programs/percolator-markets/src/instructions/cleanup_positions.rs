@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+/// Most positions one `cleanup_positions` call may inspect.
+pub const MAX_CLEANUP_POSITIONS: usize = 10;
+
+#[derive(Accounts)]
+pub struct CleanupPositions<'info> {
+    /// Position owner — receives the rent.
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+/// Close the user's finished positions passed in `remaining_accounts` as
+/// `(market, position)` pairs, positions writable.
+///
+/// A position is closed once it has been settled or refunded, or lost in a
+/// resolved market. Anything still claimable is left untouched. Returns the
+/// rent reclaimed.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CleanupPositions<'info>>,
+) -> Result<u64> {
+    let accounts = ctx.remaining_accounts;
+    require!(
+        !accounts.is_empty()
+            && accounts.len().is_multiple_of(2)
+            && accounts.len() / 2 <= MAX_CLEANUP_POSITIONS,
+        PercolatorError::InvalidBatchSize
+    );
+
+    let user = ctx.accounts.user.to_account_info();
+    let mut reclaimed: u64 = 0;
+    let mut closed = 0;
+    for pair in accounts.chunks(2) {
+        let market = Account::<Market>::try_from(&pair[0])?;
+        let position = Account::<UserPosition>::try_from(&pair[1])?;
+
+        require_keys_eq!(position.market, market.key(), PercolatorError::NoPosition);
+        require_keys_eq!(position.user, user.key(), PercolatorError::NoPosition);
+
+        // No-loss losers still have a deposit to take back
+        let lost = matches!(market.status, MarketStatus::Resolved | MarketStatus::Settled)
+            && !market.is_winning_side(position.side)
+            && market.kind != MarketKind::NoLoss;
+        if !position.settled && !lost {
+            continue;
+        }
+
+        reclaimed = reclaimed.checked_add(pair[1].lamports())
+            .ok_or(PercolatorError::Overflow)?;
+        position.close(user.clone())?;
+        closed += 1;
+    }
+
+    msg!("Cleanup: closed {} positions for {}, {} lamports reclaimed", closed, user.key(), reclaimed);
+
+    Ok(reclaimed)
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CloseConfig<'info> {
+    /// Protocol authority — receives the config account's rent.
+    #[account(
+        mut,
+        constraint = authority.key() == config.authority @ PercolatorError::UnauthorizedAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global config — closed for good.
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+pub fn handler(ctx: Context<CloseConfig>) -> Result<()> {
+    let config = &ctx.accounts.config;
+
+    // Resolution, settlement and cancellation all load the config
+    require!(
+        !config.has_active_markets(),
+        PercolatorError::MarketsStillActive
+    );
+
+    msg!(
+        "Config closed: {} markets, all terminal",
+        config.total_markets,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CloseLosingPosition<'info> {
+    /// Position owner — receives the rent.
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// The resolved market.
+    #[account(
+        constraint = market.status == MarketStatus::Resolved || market.status == MarketStatus::Settled
+            @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Losing position — closed to the user.
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+        constraint = position.user == user.key() @ PercolatorError::NoPosition,
+    )]
+    pub position: Account<'info, UserPosition>,
+
+    /// Market vault — returns the deposit in a no-loss market.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+}
+
+pub fn handler(ctx: Context<CloseLosingPosition>) -> Result<()> {
+    let market = &ctx.accounts.market;
+    let position = &ctx.accounts.position;
+
+    // Winners must go through settle to be paid
+    require!(
+        !market.is_winning_side(position.side),
+        PercolatorError::WinningPosition
+    );
+
+    // No-loss markets hand the losing deposit back in full
+    if market.kind == MarketKind::NoLoss {
+        let vault = ctx.accounts.vault.to_account_info();
+        **vault.try_borrow_mut_lamports()? -= position.deposited;
+        assert_vault_solvent(&vault, Rent::get()?.minimum_balance(0))?;
+        **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += position.deposited;
+    }
+
+    msg!(
+        "Losing position closed: user={} stake={}, market #{}",
+        ctx.accounts.user.key(),
+        position.deposited,
+        market.market_id,
+    );
+
+    Ok(())
+}
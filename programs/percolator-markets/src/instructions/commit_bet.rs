@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CommitBet<'info> {
+    /// The bettor committing a sealed bet.
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    /// The commit-reveal market.
+    #[account(
+        constraint = market.status == MarketStatus::Open @ PercolatorError::InvalidMarketStatus,
+        constraint = !market.frozen @ PercolatorError::MarketFrozen,
+        constraint = market.commit_deadline != 0 @ PercolatorError::CommitRevealDisabled,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Commitment PDA — escrows the bet amount until reveal or refund.
+    #[account(
+        init,
+        payer = bettor,
+        space = BetCommitment::SIZE,
+        seeds = [b"commitment", market.key().as_ref(), bettor.key().as_ref()],
+        bump,
+    )]
+    pub commitment: Account<'info, BetCommitment>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CommitBet>, commitment: [u8; 32], amount: u64) -> Result<()> {
+    require!(amount > 0, PercolatorError::ZeroBetAmount);
+    ctx.accounts.market.check_bettor(&ctx.accounts.bettor.key())?;
+
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp < ctx.accounts.market.commit_deadline,
+        PercolatorError::CommitPhaseClosed
+    );
+
+    // Escrow the bet amount in the commitment PDA
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bettor.to_account_info(),
+                to: ctx.accounts.commitment.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let entry = &mut ctx.accounts.commitment;
+    entry.market = ctx.accounts.market.key();
+    entry.user = ctx.accounts.bettor.key();
+    entry.commitment = commitment;
+    entry.amount = amount;
+    entry.committed_at = clock.unix_timestamp;
+    entry.bump = ctx.bumps.commitment;
+
+    msg!(
+        "Bet committed: {} lamports escrowed for market #{}",
+        amount,
+        ctx.accounts.market.market_id,
+    );
+
+    Ok(())
+}
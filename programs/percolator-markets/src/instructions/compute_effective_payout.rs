@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+/// Return data for `compute_effective_payout`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct EffectivePayout {
+    /// Capital plus profit after h, before fees.
+    pub gross_payout: u64,
+
+    /// h-ratio the settlement would apply (bps).
+    pub h_applied_bps: u16,
+
+    /// Crank fee taken from the profit (0 when settling yourself).
+    pub settlement_fee: u64,
+
+    /// Early-settlement bonus added to the profit.
+    pub incentive_bonus: u64,
+
+    /// Late-settlement penalty taken from the profit.
+    pub late_penalty: u64,
+
+    /// Lamports the position owner would receive.
+    pub net_payout: u64,
+}
+
+#[derive(Accounts)]
+pub struct ComputeEffectivePayout<'info> {
+    /// Global config — crank fee settings.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// The resolved market.
+    #[account(
+        constraint = market.status == MarketStatus::Resolved @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// The winning position to quote.
+    #[account(
+        constraint = position.market == market.key() @ PercolatorError::NoPosition,
+        constraint = !position.settled @ PercolatorError::AlreadySettled,
+        constraint = market.is_winning_side(position.side) @ PercolatorError::LosingSide,
+    )]
+    pub position: Account<'info, UserPosition>,
+
+    /// Market vault — its balance decides any haircut.
+    /// CHECK: Validated by seeds.
+    #[account(
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+}
+
+/// Quote the payout exactly as `settle` (or `crank_settle` when `via_crank`)
+/// would compute it now. Fails where settlement would.
+pub fn handler(ctx: Context<ComputeEffectivePayout>, via_crank: bool) -> Result<EffectivePayout> {
+    let market = &ctx.accounts.market;
+    require!(!market.merkle_settlement, PercolatorError::MerkleSettlementActive);
+    require!(market.floor_shortfall == 0, PercolatorError::HRatioBelowFloor);
+
+    let vault_balance = ctx.accounts.vault.lamports();
+    let (breakdown, h_applied_bps) =
+        market.settlement_breakdown(&ctx.accounts.position, vault_balance)?;
+
+    let config = &ctx.accounts.config;
+    let elapsed = Clock::get()?.unix_timestamp.saturating_sub(market.resolved_at);
+    let (bonus, late_penalty) = config.settlement_incentive(breakdown.profit, elapsed);
+    let incentive_bonus = bonus.min(
+        market.incentive_surplus(vault_balance, Rent::get()?.minimum_balance(0))?,
+    );
+    let profit = breakdown.profit + incentive_bonus - late_penalty;
+
+    let settlement_fee = if via_crank {
+        config.crank_fee(market.crank_fees_paid).min(profit)
+    } else {
+        0
+    };
+
+    Ok(EffectivePayout {
+        gross_payout: breakdown.total,
+        h_applied_bps,
+        settlement_fee,
+        incentive_bonus,
+        late_penalty,
+        net_payout: breakdown.total + incentive_bonus - late_penalty - settlement_fee,
+    })
+}
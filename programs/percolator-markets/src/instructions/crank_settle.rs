@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::instructions::settle::{issue_receipt, settle_position};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CrankSettle<'info> {
+    /// Anyone settling a position on its owner's behalf — receives the crank fee.
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    /// Global config — crank fee settings; counts terminal markets.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// The resolved market.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Resolved @ PercolatorError::InvalidMarketStatus,
+        // Only the holder can burn a position NFT
+        constraint = !market.position_nfts @ PercolatorError::PositionNftsUnsupported,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Position being settled.
+    #[account(
+        mut,
+        seeds = [b"position", market.key().as_ref(), position.user.as_ref()],
+        bump = position.bump,
+        constraint = !position.settled @ PercolatorError::AlreadySettled,
+    )]
+    pub position: Account<'info, UserPosition>,
+
+    /// Position owner — receives the payout.
+    /// CHECK: Must match position.user.
+    #[account(
+        mut,
+        address = position.user @ PercolatorError::NoPosition,
+    )]
+    pub user: UncheckedAccount<'info>,
+
+    /// Market vault — source of payout funds.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Creator stats — frees an active-market slot once the market closes.
+    /// Omitted only for markets created before creator stats existed.
+    #[account(
+        mut,
+        seeds = [b"creator_stats", market.creator.as_ref()],
+        bump = creator_stats.bump,
+    )]
+    pub creator_stats: Option<Account<'info, CreatorStats>>,
+
+    /// Protocol treasury — required once a late-settlement penalty is due.
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    /// Settlement receipt — required exactly when `market.settlement_receipts`.
+    #[account(
+        init,
+        payer = cranker,
+        space = SettlementReceipt::SIZE,
+        seeds = [b"settlement_receipt", market.key().as_ref(), position.user.as_ref()],
+        bump,
+    )]
+    pub receipt: Option<Account<'info, SettlementReceipt>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CrankSettle>, settle_order: Option<u64>) -> Result<()> {
+    let cranker = ctx.accounts.cranker.to_account_info();
+    let user = ctx.accounts.user.to_account_info();
+    let vault = ctx.accounts.vault.to_account_info();
+
+    // No tip for settling your own position.
+    let fee = if cranker.key() == user.key() {
+        0
+    } else {
+        ctx.accounts.config.crank_fee(ctx.accounts.market.crank_fees_paid)
+    };
+
+    let breakdown = settle_position(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        &mut ctx.accounts.position,
+        &vault,
+        &user,
+        Some((&cranker, fee)),
+        ctx.accounts.treasury.as_mut(),
+        settle_order,
+    )?;
+
+    issue_receipt(
+        &ctx.accounts.market,
+        ctx.accounts.receipt.as_mut(),
+        ctx.bumps.receipt,
+        user.key(),
+        breakdown.total,
+    )
+}
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{self, AssociatedToken};
+
+use crate::errors::PercolatorError;
+use crate::instructions::create_market::*;
+use crate::instructions::place_bet::{mint_position_tokens, net_stake, record_bet, transfer_from_bettor};
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(params: CreateMarketParams)]
+pub struct CreateAndBet<'info> {
+    /// Every account `create_market` needs; the creator is also the bettor.
+    pub create: CreateMarket<'info>,
+
+    /// Creator's position in the new market.
+    #[account(
+        init,
+        payer = create.creator,
+        space = UserPosition::SIZE,
+        seeds = [b"position", create.market.key().as_ref(), create.creator.key().as_ref()],
+        bump,
+    )]
+    pub position: Account<'info, UserPosition>,
+
+    /// Creator's associated token account for the chosen side's mint —
+    /// created here, since the mints are born in this instruction.
+    /// CHECK: Address is validated by the associated token program.
+    #[account(mut)]
+    pub creator_token_account: UncheckedAccount<'info>,
+
+    /// Protocol treasury — receives the protocol share of the fee.
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreateAndBet>,
+    params: CreateMarketParams,
+    side: BetSide,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, PercolatorError::ZeroBetAmount);
+    require!(params.commit_deadline == 0, PercolatorError::CommitRevealRequired);
+    require!(!params.position_nfts, PercolatorError::PositionNftsUnsupported);
+
+    create(&mut ctx.accounts.create, &ctx.bumps.create, params)?;
+
+    let position_bump = ctx.bumps.position;
+    let accounts = ctx.accounts;
+    let creator_key = accounts.create.creator.key();
+    accounts.create.market.check_bettor(&creator_key)?;
+
+    // Fee comes off the top; the creator share would be paid to the creator
+    // themselves, so it simply stays in their wallet
+    let (protocol_fee, creator_fee) = accounts.create.config.split_fee(amount);
+    let bounty = accounts.create.market.bounty_share(amount);
+    let stake = net_stake(amount, protocol_fee, creator_fee, bounty)?;
+
+    let creator = accounts.create.creator.to_account_info();
+    let system_program = accounts.system_program.to_account_info();
+    transfer_from_bettor(&system_program, &creator, &accounts.create.vault.to_account_info(), stake + bounty)?;
+    transfer_from_bettor(&system_program, &creator, &accounts.treasury.to_account_info(), protocol_fee)?;
+
+    let mint = match side {
+        BetSide::Yes => accounts.create.yes_mint.to_account_info(),
+        BetSide::No => accounts.create.no_mint.to_account_info(),
+    };
+
+    associated_token::create(CpiContext::new(
+        accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: creator.clone(),
+            associated_token: accounts.creator_token_account.to_account_info(),
+            authority: creator,
+            mint: mint.clone(),
+            system_program,
+            token_program: accounts.create.token_program.to_account_info(),
+        },
+    ))?;
+
+    mint_position_tokens(
+        &accounts.create.token_program.to_account_info(),
+        &accounts.create.market,
+        mint,
+        accounts.creator_token_account.to_account_info(),
+        stake,
+    )?;
+
+    record_bet(
+        &mut accounts.create.market,
+        &mut accounts.position,
+        position_bump,
+        creator_key,
+        side,
+        stake,
+        creator_fee,
+    )?;
+
+    let treasury = &mut accounts.treasury;
+    treasury.total_collected = treasury.total_collected.checked_add(protocol_fee)
+        .ok_or(PercolatorError::Overflow)?;
+    let market = &mut accounts.create.market;
+    market.resolution_bounty = market.resolution_bounty.checked_add(bounty)
+        .ok_or(PercolatorError::Overflow)?;
+
+    let clock = Clock::get()?;
+    accounts.create.config.record_volume(amount, clock.unix_timestamp)?;
+
+    msg!(
+        "Seed bet: {} lamports on {:?} for new market #{}",
+        stake,
+        side as u8,
+        accounts.create.market.market_id,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::instructions::create_market::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CreateFromTemplate<'info> {
+    /// Every account `create_market` needs.
+    pub create: CreateMarket<'info>,
+
+    /// Template supplying everything but the question and token mint.
+    #[account(
+        constraint = template.creator == create.creator.key() @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub template: Account<'info, MarketTemplate>,
+}
+
+pub fn handler(ctx: Context<CreateFromTemplate>, question: String, token_mint: Pubkey) -> Result<()> {
+    let template = &ctx.accounts.template;
+    let clock = Clock::get()?;
+
+    let deadline = clock.unix_timestamp
+        .checked_add(template.duration_secs)
+        .ok_or(PercolatorError::Overflow)?;
+
+    let params = CreateMarketParams {
+        question,
+        rule: template.rule,
+        target_value: template.target_value,
+        token_mint,
+        oracle: template.oracle,
+        deadline,
+        subsidy_match_bps: 0,
+        creator_subsidy: 0,
+        commit_deadline: 0,
+        insolvency_mode: template.insolvency_mode,
+        default_outcome: template.default_outcome,
+        resolution_window_secs: template.resolution_window_secs,
+        creator_betting_allowed: template.creator_betting_allowed,
+        oracle_betting_allowed: template.oracle_betting_allowed,
+        question_hash: [0; 32],
+        question_uri: String::new(),
+        parent_market: None,
+        parent_required_outcome: Outcome::Unresolved,
+        early_bird_bonus_bps: 0,
+        early_bird_window_secs: 0,
+        h_ratio_floor_bps: 0,
+        time_weighted: false,
+        kind: MarketKind::Standard,
+        max_imbalance_bps: 10_000,
+        secondary_oracle: None,
+        resolution_tolerance_bps: 0,
+        min_seed_ratio_bps: 0,
+        expected_max_pool: 0,
+        claim_deadline: 0,
+        price_exponent: DEFAULT_PRICE_EXPONENT,
+        refund_by_token_balance: false,
+        min_pool_per_side: 0,
+        resolution_bounty_bps: 0,
+        resolve_at_deadline: false,
+        settlement_receipts: false,
+        position_nfts: false,
+    };
+
+    msg!("Creating market from template #{}", template.template_id);
+
+    create(&mut ctx.accounts.create, &ctx.bumps.create, params)
+}
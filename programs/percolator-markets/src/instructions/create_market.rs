@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token};
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Mint, Token};
 
 use crate::errors::PercolatorError;
 use crate::state::*;
@@ -7,7 +8,8 @@ use crate::state::*;
 /// Parameters for creating a new prediction market.
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct CreateMarketParams {
-    /// Human-readable prediction question (max 256 bytes).
+    /// Human-readable prediction question (max `config.max_question_len`
+    /// bytes, never more than 256).
     pub question: String,
 
     /// Resolution rule.
@@ -24,10 +26,117 @@ pub struct CreateMarketParams {
 
     /// Unix timestamp deadline.
     pub deadline: i64,
+
+    /// Fraction of the loser pool the creator matches at resolution (bps).
+    pub subsidy_match_bps: u16,
+
+    /// Lamports escrowed into the vault to fund the subsidy match.
+    pub creator_subsidy: u64,
+
+    /// End of the commit phase; 0 disables commit-reveal betting.
+    pub commit_deadline: i64,
+
+    /// Settlement behavior when the vault is short.
+    pub insolvency_mode: InsolvencyMode,
+
+    /// Fallback outcome if the oracle never resolves (`Unresolved` = void).
+    pub default_outcome: Outcome,
+
+    /// Seconds after the deadline the oracle has to resolve. Must be positive.
+    pub resolution_window_secs: i64,
+
+    /// Allow the creator to bet (true for ordinary markets).
+    pub creator_betting_allowed: bool,
+
+    /// Allow the oracle to bet (true for ordinary markets).
+    pub oracle_betting_allowed: bool,
+
+    /// sha256 of the full question text kept off-chain (all zeros = none).
+    pub question_hash: [u8; 32],
+
+    /// Off-chain location of the full text, e.g. an IPFS or Arweave URI
+    /// (max 128 bytes; empty = none).
+    pub question_uri: String,
+
+    /// Make this a conditional market on another market (`None` = ordinary).
+    pub parent_market: Option<Pubkey>,
+
+    /// Outcome (YES or NO) the parent must resolve to; otherwise this market
+    /// is voided. Ignored without a parent.
+    pub parent_required_outcome: Outcome,
+
+    /// Extra subsidy-share weight for early bets, in bps of stake
+    /// (0 = none). Requires a creator subsidy, which funds the bonus.
+    pub early_bird_bonus_bps: u16,
+
+    /// Seconds after creation during which bets earn the bonus.
+    pub early_bird_window_secs: i64,
+
+    /// Lowest h-ratio winners may be paid at (bps, 0 = no floor). Below it,
+    /// settlement waits for `restore_solvency` instead of paying less.
+    pub h_ratio_floor_bps: u16,
+
+    /// Weight profit shares by how long each stake was committed before the
+    /// deadline. Cannot be combined with an early-bird bonus.
+    pub time_weighted: bool,
+
+    /// `NoLoss` returns every deposit and pays winners only the
+    /// `creator_subsidy` prize, which must be non-zero.
+    pub kind: MarketKind,
+
+    /// Largest share of the pool one side may reach through a bet, in bps
+    /// (5000–10000; 10000 = no limit).
+    pub max_imbalance_bps: u16,
+
+    /// Second oracle for `resolve_with_rule`; its reading must agree with
+    /// the primary oracle's before the market resolves.
+    pub secondary_oracle: Option<Pubkey>,
+
+    /// Allowed gap between the two readings, in bps (0–10000).
+    pub resolution_tolerance_bps: u16,
+
+    /// Seed commitment, in bps of `expected_max_pool` (0–10000, 0 = none).
+    /// `creator_subsidy` must cover it.
+    pub min_seed_ratio_bps: u16,
+
+    /// Total pool the seed is sized for.
+    pub expected_max_pool: u64,
+
+    /// After this time the creator may sweep unclaimed winnings
+    /// (0 = never). Must fall after the resolution window.
+    pub claim_deadline: i64,
+
+    /// `PriceTarget` only: decimal exponent of `target_value` in USD
+    /// (-18–0; -9 = USD × 10^9).
+    pub price_exponent: i32,
+
+    /// Refund cancelled-market bettors by token balance rather than
+    /// `position.deposited` (for transferable position tokens).
+    pub refund_by_token_balance: bool,
+
+    /// Smallest pool each side needs for the market to resolve (0 = none);
+    /// below it the market can only be cancelled.
+    pub min_pool_per_side: u64,
+
+    /// Share of each bet (bps, at most 500) paid to whoever resolves the
+    /// market; 0 = no bounty.
+    pub resolution_bounty_bps: u16,
+
+    /// Let anyone resolve the market from the oracle's posted reading once
+    /// the deadline passes. Only for rules the program can evaluate.
+    pub resolve_at_deadline: bool,
+
+    /// Issue a `SettlementReceipt` for every settled position. Such markets
+    /// settle one position at a time and never through a Merkle root.
+    pub settlement_receipts: bool,
+
+    /// Mint each position as a single NFT with Token Metadata instead of
+    /// YES/NO tokens. Not combinable with commit-reveal betting or
+    /// token-balance refunds.
+    pub position_nfts: bool,
 }
 
 #[derive(Accounts)]
-#[instruction(params: CreateMarketParams)]
 pub struct CreateMarket<'info> {
     /// Market creator — pays for account allocation.
     #[account(mut)]
@@ -55,6 +164,24 @@ pub struct CreateMarket<'info> {
     )]
     pub market: Account<'info, Market>,
 
+    /// Ban entry for the creator — must be absent or inactive.
+    /// CHECK: May not exist; inspected in the handler.
+    #[account(
+        seeds = [b"banned", creator.key().as_ref()],
+        bump,
+    )]
+    pub banned_creator: UncheckedAccount<'info>,
+
+    /// Creator stats — created on the creator's first market.
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = CreatorStats::SIZE,
+        seeds = [b"creator_stats", creator.key().as_ref()],
+        bump,
+    )]
+    pub creator_stats: Account<'info, CreatorStats>,
+
     /// Vault PDA — holds all SOL deposits for this market.
     /// CHECK: Initialized as a PDA; no data, just lamports.
     #[account(
@@ -64,6 +191,16 @@ pub struct CreateMarket<'info> {
     )]
     pub vault: SystemAccount<'info>,
 
+    /// Creator revenue PDA — funded to rent exemption here, so fee shares
+    /// below the rent minimum can land in it.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"creator_revenue", market.key().as_ref()],
+        bump,
+    )]
+    pub creator_revenue: SystemAccount<'info>,
+
     /// YES position token mint.
     #[account(
         init,
@@ -86,30 +223,205 @@ pub struct CreateMarket<'info> {
     )]
     pub no_mint: Account<'info, Mint>,
 
+    /// Token the market is about — must be `params.token_mint`.
+    /// CHECK: Validated as an initialized SPL mint in the handler.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// Parent market — required when `params.parent_market` is set.
+    pub parent_market: Option<Box<Account<'info, Market>>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 pub fn handler(ctx: Context<CreateMarket>, params: CreateMarketParams) -> Result<()> {
+    create(ctx.accounts, &ctx.bumps, params)
+}
+
+/// Validate `params` and initialize the market, its vault and stats.
+///
+/// Shared by every instruction that creates a market.
+pub(crate) fn create(
+    accounts: &mut CreateMarket,
+    bumps: &CreateMarketBumps,
+    params: CreateMarketParams,
+) -> Result<()> {
+    require!(
+        !BannedCreator::is_active(&accounts.banned_creator.to_account_info())?,
+        PercolatorError::CreatorBanned
+    );
+
+    let max_markets = accounts.config.max_markets_per_creator;
+    require!(
+        max_markets == 0 || accounts.creator_stats.active_markets < max_markets,
+        PercolatorError::CreatorMarketLimit
+    );
+
     // Validate inputs
     require!(
-        params.question.len() <= 256,
+        !accounts.config.require_distinct_oracle || params.oracle != accounts.creator.key(),
+        PercolatorError::OracleSameAsCreator
+    );
+    require_keys_eq!(
+        accounts.token_mint.key(),
+        params.token_mint,
+        PercolatorError::InvalidTokenMint
+    );
+    require!(
+        is_initialized_mint(&accounts.token_mint.to_account_info()),
+        PercolatorError::InvalidTokenMint
+    );
+    require!(
+        params.question.len() <= accounts.config.question_limit(),
         PercolatorError::QuestionTooLong
     );
+    require!(
+        params.question_uri.len() <= Market::MAX_QUESTION_URI_LEN,
+        PercolatorError::QuestionUriTooLong
+    );
 
     let clock = Clock::get()?;
     require!(
         params.deadline > clock.unix_timestamp,
         PercolatorError::DeadlineInPast
     );
+    accounts.config.check_deadline_distance(params.deadline, clock.unix_timestamp)?;
+
+    require!(
+        params.subsidy_match_bps <= 10_000 && params.h_ratio_floor_bps <= 10_000,
+        PercolatorError::InvalidBasisPoints
+    );
+    require!(
+        (5_000..=10_000).contains(&params.max_imbalance_bps),
+        PercolatorError::InvalidBasisPoints
+    );
+    require!(
+        params.resolution_tolerance_bps <= 10_000,
+        PercolatorError::InvalidBasisPoints
+    );
+    require!(
+        params.resolution_bounty_bps <= Market::MAX_RESOLUTION_BOUNTY_BPS,
+        PercolatorError::InvalidBasisPoints
+    );
+    // Oracle-decided rules have nothing for the program to read
+    require!(
+        !params.resolve_at_deadline
+            || !matches!(params.rule, MarketRule::OracleCustom | MarketRule::Scalar { .. }),
+        PercolatorError::InvalidMarketRule
+    );
+    require!(
+        params.secondary_oracle != Some(params.oracle),
+        PercolatorError::InvalidSecondaryOracle
+    );
+
+    require!(
+        params.resolution_window_secs > 0,
+        PercolatorError::InvalidResolutionWindow
+    );
+
+    require!(
+        params.early_bird_bonus_bps <= 10_000 && params.early_bird_window_secs >= 0,
+        PercolatorError::InvalidBasisPoints
+    );
+    require!(
+        params.early_bird_bonus_bps == 0 || params.creator_subsidy > 0,
+        PercolatorError::EarlyBirdWithoutSubsidy
+    );
+    require!(
+        !(params.time_weighted && params.early_bird_bonus_bps > 0),
+        PercolatorError::InvalidScoringMode
+    );
+    require!(
+        params.kind != MarketKind::NoLoss || params.creator_subsidy > 0,
+        PercolatorError::NoLossWithoutPrize
+    );
+    require!(
+        params.min_seed_ratio_bps <= 10_000,
+        PercolatorError::InvalidBasisPoints
+    );
+    require!(
+        params.creator_subsidy
+            >= Market::required_seed(params.expected_max_pool, params.min_seed_ratio_bps)?,
+        PercolatorError::SeedTooSmall
+    );
+    let resolution_window_end = params.deadline
+        .checked_add(params.resolution_window_secs)
+        .ok_or(PercolatorError::Overflow)?;
+    require!(
+        params.claim_deadline == 0 || params.claim_deadline > resolution_window_end,
+        PercolatorError::InvalidClaimDeadline
+    );
+
+    require!(
+        PRICE_EXPONENT_RANGE.contains(&params.price_exponent),
+        PercolatorError::InvalidPriceExponent
+    );
+
+    if let MarketRule::Scalar { lower, upper } = params.rule {
+        require!(lower < upper, PercolatorError::InvalidScalarRange);
+    }
+    require!(
+        params.default_outcome != Outcome::Scalar,
+        PercolatorError::InvalidOutcome
+    );
+
+    if let Some(parent) = params.parent_market {
+        require_keys_neq!(parent, accounts.market.key(), PercolatorError::InvalidParentMarket);
+        // Loading it as a `Market` proves the parent exists and is ours
+        require!(
+            accounts.parent_market.as_ref().map(|market| market.key()) == Some(parent),
+            PercolatorError::InvalidParentMarket
+        );
+        require!(
+            params.parent_required_outcome == Outcome::Yes
+                || params.parent_required_outcome == Outcome::No,
+            PercolatorError::InvalidOutcome
+        );
+    }
+
+    require!(
+        !params.position_nfts
+            || (params.commit_deadline == 0 && !params.refund_by_token_balance),
+        PercolatorError::PositionNftsUnsupported
+    );
+
+    if params.commit_deadline != 0 {
+        require!(
+            params.commit_deadline > clock.unix_timestamp
+                && params.commit_deadline < params.deadline,
+            PercolatorError::InvalidCommitDeadline
+        );
+    }
+
+    // Escrow the creator subsidy in the vault
+    if params.creator_subsidy > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: accounts.creator.to_account_info(),
+                    to: accounts.vault.to_account_info(),
+                },
+            ),
+            params.creator_subsidy,
+        )?;
+    }
+
+    // Creator fee shares can be smaller than the rent minimum
+    fund_rent(
+        &accounts.creator_revenue.to_account_info(),
+        &accounts.creator.to_account_info(),
+        &accounts.system_program.to_account_info(),
+        accounts.rent.minimum_balance(0),
+    )?;
 
     // Populate market account
-    let market = &mut ctx.accounts.market;
-    let config = &mut ctx.accounts.config;
+    let market = &mut accounts.market;
+    let config = &mut accounts.config;
 
     market.market_id = config.next_market_id;
-    market.creator = ctx.accounts.creator.key();
+    market.creator = accounts.creator.key();
     market.oracle = params.oracle;
     market.question = params.question;
     market.rule = params.rule;
@@ -120,27 +432,129 @@ pub fn handler(ctx: Context<CreateMarket>, params: CreateMarketParams) -> Result
     market.outcome = Outcome::Unresolved;
     market.yes_pool = 0;
     market.no_pool = 0;
-    market.yes_mint = ctx.accounts.yes_mint.key();
-    market.no_mint = ctx.accounts.no_mint.key();
-    market.vault = ctx.accounts.vault.key();
-    market.vault_bump = ctx.bumps.vault;
-    market.bump = ctx.bumps.market;
+    market.yes_mint = accounts.yes_mint.key();
+    market.no_mint = accounts.no_mint.key();
+    market.vault = accounts.vault.key();
+    market.vault_bump = bumps.vault;
+    market.bump = bumps.market;
     market.h_ratio_bps = 10_000; // 100% until resolution
     market.settled_amount = 0;
     market.settlements_count = 0;
+    market.settled_stake = 0;
+    market.creator_liquidity = 0;
+    market.subsidy_match_bps = params.subsidy_match_bps;
+    market.creator_subsidy = params.creator_subsidy;
+    market.subsidy_applied = 0;
+    market.extensions_count = 0;
+    market.frozen = false;
+    market.creator_fees_earned = 0;
+    market.refunded_stake = 0;
+    market.floor_breached = false;
+    market.crank_fees_paid = 0;
+    market.commit_deadline = params.commit_deadline;
+    market.insolvency_mode = params.insolvency_mode;
+    market.merkle_settlement = false;
+    market.cancelled_at = 0;
+    market.yes_positions = 0;
+    market.no_positions = 0;
+    market.default_outcome = params.default_outcome;
+    market.scalar_value = 0;
+    market.yes_share_bps = 0;
+    market.resolution_window_end = resolution_window_end;
+    market.h_ratio_history = [0; 8];
+    market.h_ratio_cursor = 0;
+    market.has_oracle_schedule = false;
+    market.creator_betting_disabled = !params.creator_betting_allowed;
+    market.oracle_betting_disabled = !params.oracle_betting_allowed;
+    market.created_at = clock.unix_timestamp;
+    market.resolution_extended = false;
+    market.question_hash = params.question_hash;
+    market.question_uri = params.question_uri;
+    market.parent_market = params.parent_market;
+    market.parent_required_outcome = match params.parent_market {
+        Some(_) => params.parent_required_outcome,
+        None => Outcome::Unresolved,
+    };
+    market.early_bird_bonus_bps = params.early_bird_bonus_bps;
+    market.early_bird_window_secs = params.early_bird_window_secs;
+    market.yes_bonus_stake = 0;
+    market.no_bonus_stake = 0;
+    market.settled_bonus_stake = 0;
+    market.h_ratio_floor_bps = params.h_ratio_floor_bps;
+    market.floor_shortfall = 0;
+    market.time_weighted = params.time_weighted;
+    market.yes_weighted_stake = 0;
+    market.no_weighted_stake = 0;
+    market.settled_weighted_stake = 0;
+    market.last_bet_slot = 0;
+    market.rounding_mode = config.rounding_mode;
+    market.settled_profit_claim = 0;
+    market.last_heartbeat = clock.unix_timestamp;
+    market.hedge_of = None;
+    market.kind = params.kind;
+    market.max_imbalance_bps = params.max_imbalance_bps;
+    market.secondary_oracle = params.secondary_oracle;
+    market.resolution_tolerance_bps = params.resolution_tolerance_bps;
+    market.oracle_disagreement = false;
+    market.min_seed_ratio_bps = params.min_seed_ratio_bps;
+    market.expected_max_pool = params.expected_max_pool;
+    market.claim_deadline = params.claim_deadline;
+    market.swept_unclaimed = 0;
+    market.price_exponent = params.price_exponent;
+    market.resolved_via = ResolutionSource::Unresolved;
+    market.yes_reconcile_slack = 0;
+    market.no_reconcile_slack = 0;
+    market.refund_by_token_balance = params.refund_by_token_balance;
+    market.min_pool_per_side = params.min_pool_per_side;
+    market.resolved_at = 0;
+    market.creator_revenue = 0;
+    market.resolution_bounty_bps = params.resolution_bounty_bps;
+    market.resolution_bounty = 0;
+    market.resolve_at_deadline = params.resolve_at_deadline;
+    market.resolution_feed = if params.resolve_at_deadline {
+        Pubkey::find_program_address(
+            &[b"oracle_reading", params.oracle.as_ref(), params.token_mint.as_ref()],
+            &crate::ID,
+        ).0
+    } else {
+        Pubkey::default()
+    };
+    market.settlement_receipts = params.settlement_receipts;
+    market.position_nfts = params.position_nfts;
+    market.settlement_bonuses = 0;
+    market.version = Market::VERSION;
 
     // Increment global counter
     config.next_market_id = config.next_market_id.checked_add(1).unwrap();
     config.total_markets = config.total_markets.checked_add(1).unwrap();
 
+    let stats = &mut accounts.creator_stats;
+    stats.creator = market.creator;
+    stats.active_markets = stats.active_markets.checked_add(1)
+        .ok_or(PercolatorError::Overflow)?;
+    stats.total_markets = stats.total_markets.checked_add(1)
+        .ok_or(PercolatorError::Overflow)?;
+    stats.bump = bumps.creator_stats;
+
     msg!(
         "Market #{} created: {} | deadline: {} | rule: {:?}",
         market.market_id,
         market.question,
         market.deadline,
-        market.rule as u8,
+        market.rule,
     );
 
     Ok(())
 }
 
+
+/// Whether `info` is an initialized mint owned by the SPL Token program.
+fn is_initialized_mint(info: &AccountInfo) -> bool {
+    if info.owner != &token::ID {
+        return false;
+    }
+    let Ok(data) = info.try_borrow_data() else {
+        return false;
+    };
+    Mint::try_deserialize(&mut &data[..]).is_ok()
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::create_market::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(params: CreateMarketParams)]
+pub struct CreateMarketDeduped<'info> {
+    /// Every account `create_market` needs.
+    pub create: CreateMarket<'info>,
+
+    /// Dedupe marker — `init` fails if this creator already opened a
+    /// market with the same question, token mint, rule and target.
+    #[account(
+        init,
+        payer = create.creator,
+        space = MarketDedupe::SIZE,
+        seeds = [
+            b"market_dedupe",
+            create.creator.key().as_ref(),
+            MarketDedupe::key(&params.question, &params.token_mint, &params.rule, params.target_value).as_ref(),
+        ],
+        bump,
+    )]
+    pub dedupe: Account<'info, MarketDedupe>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateMarketDeduped>, params: CreateMarketParams) -> Result<()> {
+    create(&mut ctx.accounts.create, &ctx.bumps.create, params)?;
+
+    let dedupe = &mut ctx.accounts.dedupe;
+    dedupe.market = ctx.accounts.create.market.key();
+    dedupe.bump = ctx.bumps.dedupe;
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::instructions::create_market::*;
+
+/// Most markets a single `create_markets_batch` call may create; keeps the
+/// transaction within the default compute budget.
+pub const MAX_BATCH_MARKETS: usize = 4;
+
+#[derive(Accounts)]
+pub struct CreateMarketsBatch<'info> {
+    /// Market creator — must be the creator in every account group.
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+/// Create `params.len()` markets in one transaction.
+///
+/// `remaining_accounts` holds one `CreateMarket` account group per entry, in
+/// the same order and layout as `create_market`. Each group is validated and
+/// initialized exactly as `create_market` would, so ids are assigned
+/// sequentially and the per-creator market limit applies to every market.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateMarketsBatch<'info>>,
+    params: Vec<CreateMarketParams>,
+) -> Result<()> {
+    require!(
+        !params.is_empty() && params.len() <= MAX_BATCH_MARKETS,
+        PercolatorError::InvalidBatchSize
+    );
+
+    let count = params.len();
+    let mut accounts = ctx.remaining_accounts;
+    for params in params {
+        let mut bumps = CreateMarketBumps::default();
+        let mut group = CreateMarket::try_accounts(
+            ctx.program_id,
+            &mut accounts,
+            &[],
+            &mut bumps,
+            &mut BTreeSet::new(),
+        )?;
+        require_keys_eq!(
+            group.creator.key(),
+            ctx.accounts.creator.key(),
+            PercolatorError::UnauthorizedCreator
+        );
+
+        create(&mut group, &bumps, params)?;
+
+        // Persist now so the next group reads the bumped id and creator stats
+        group.exit(ctx.program_id)?;
+    }
+    require!(accounts.is_empty(), PercolatorError::InvalidBatchSize);
+
+    msg!("Batch created {} markets", count);
+
+    Ok(())
+}
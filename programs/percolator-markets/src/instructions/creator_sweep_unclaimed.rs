@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CreatorSweepUnclaimed<'info> {
+    /// Market creator — receives the unclaimed winnings.
+    #[account(
+        mut,
+        constraint = creator.key() == market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: Signer<'info>,
+
+    /// Global config — counts terminal markets.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// A resolved market past its claim deadline.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Resolved @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Market vault — source of the swept winnings.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Creator stats — frees an active-market slot once the market closes.
+    /// Omitted only for markets created before creator stats existed.
+    #[account(
+        mut,
+        seeds = [b"creator_stats", market.creator.as_ref()],
+        bump = creator_stats.bump,
+    )]
+    pub creator_stats: Option<Account<'info, CreatorStats>>,
+}
+
+pub fn handler(ctx: Context<CreatorSweepUnclaimed>) -> Result<()> {
+    let clock = Clock::get()?;
+    let market = &ctx.accounts.market;
+    require!(
+        market.claim_deadline != 0 && clock.unix_timestamp >= market.claim_deadline,
+        PercolatorError::ClaimDeadlineNotReached
+    );
+
+    // Only what unsettled winners are owed; creator liquidity stays for
+    // `reclaim_liquidity`, and the vault keeps its rent
+    let rent = Rent::get()?.minimum_balance(0);
+    let amount = market.owed_at_h()?
+        .min(ctx.accounts.vault.lamports().saturating_sub(rent));
+
+    **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+    assert_vault_solvent(&ctx.accounts.vault.to_account_info(), rent)?;
+    **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    // The remaining winners forfeit their claims
+    let market = &mut ctx.accounts.market;
+    market.swept_unclaimed = amount;
+    market.transition(MarketStatus::Settled)?;
+    CreatorStats::close_market(ctx.accounts.creator_stats.as_deref_mut(), market)?;
+    ctx.accounts.config.market_closed();
+
+    msg!(
+        "Unclaimed winnings swept: {} lamports to creator of market #{}",
+        amount,
+        market.market_id,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::events::DeadlineExtended;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ExtendDeadline<'info> {
+    /// Market creator.
+    #[account(
+        constraint = creator.key() == market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: Signer<'info>,
+
+    /// The open market to extend.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Global config — provides the extension limits.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+pub fn handler(ctx: Context<ExtendDeadline>, new_deadline: i64) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let market = &mut ctx.accounts.market;
+
+    // Once the deadline passes the outcome may already be known
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp < market.deadline,
+        PercolatorError::MarketExpired
+    );
+
+    require!(
+        new_deadline > market.deadline,
+        PercolatorError::DeadlineNotExtended
+    );
+    require!(
+        new_deadline - market.deadline <= config.max_extension_secs,
+        PercolatorError::ExtensionTooLong
+    );
+    require!(
+        market.extensions_count < config.max_extensions,
+        PercolatorError::ExtensionLimitReached
+    );
+    // Extensions are held to the same horizon as a new market
+    config.check_deadline_distance(new_deadline, clock.unix_timestamp)?;
+
+    // The oracle keeps the same resolution window after the new deadline
+    let old_deadline = market.deadline;
+    market.resolution_window_end = market.resolution_window_end
+        .checked_add(new_deadline - old_deadline)
+        .ok_or(PercolatorError::Overflow)?;
+    market.deadline = new_deadline;
+    market.extensions_count += 1;
+
+    emit!(DeadlineExtended {
+        market: market.key(),
+        market_id: market.market_id,
+        old_deadline,
+        new_deadline,
+        extensions_count: market.extensions_count,
+    });
+
+    msg!(
+        "Market #{} deadline extended: {} -> {} ({}/{})",
+        market.market_id,
+        old_deadline,
+        new_deadline,
+        market.extensions_count,
+        config.max_extensions,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+/// Return data for `get_global_stats`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct GlobalStats {
+    pub total_markets: u64,
+    pub total_volume: u64,
+    /// Lifetime protocol fees received by the treasury.
+    pub total_fees: u64,
+    /// Markets not yet Settled or Cancelled.
+    pub active_markets: u64,
+    pub next_market_id: u64,
+}
+
+#[derive(Accounts)]
+pub struct GetGlobalStats<'info> {
+    /// Global config.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// Protocol treasury — lifetime fee total.
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+}
+
+pub fn handler(ctx: Context<GetGlobalStats>) -> Result<GlobalStats> {
+    let config = &ctx.accounts.config;
+
+    Ok(GlobalStats {
+        total_markets: config.total_markets,
+        total_volume: config.total_volume,
+        total_fees: ctx.accounts.treasury.total_collected,
+        active_markets: config.active_markets(),
+        next_market_id: config.next_market_id,
+    })
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct Heartbeat<'info> {
+    /// Oracle authority.
+    #[account(
+        constraint = oracle.key() == market.oracle @ PercolatorError::UnauthorizedOracle,
+    )]
+    pub oracle: Signer<'info>,
+
+    /// An unresolved market.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open || market.status == MarketStatus::Closed
+            @ PercolatorError::AlreadyResolved,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+pub fn handler(ctx: Context<Heartbeat>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    market.last_heartbeat = Clock::get()?.unix_timestamp;
+
+    msg!("Heartbeat: market #{} at {}", market.market_id, market.last_heartbeat);
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+/// Parameters for initializing the global config.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct InitializeConfigParams {
+    /// Protocol fee in basis points.
+    pub fee_bps: u16,
+
+    /// Fee collector wallet.
+    pub fee_collector: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    /// Protocol authority — pays for the config account.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Global config PDA.
+    #[account(
+        init,
+        payer = authority,
+        space = GlobalConfig::SIZE,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeConfig>, params: InitializeConfigParams) -> Result<()> {
+    require!(params.fee_bps <= 10_000, PercolatorError::InvalidBasisPoints);
+
+    let config = &mut ctx.accounts.config;
+    config.authority = ctx.accounts.authority.key();
+    config.fee_bps = params.fee_bps;
+    config.fee_collector = params.fee_collector;
+    config.next_market_id = 0;
+    config.total_markets = 0;
+    config.total_volume = 0;
+    config.bump = ctx.bumps.config;
+    config.max_extension_secs = 0;
+    config.max_extensions = 0;
+    config.creator_fee_share_bps = 0;
+    config.crank_fee_lamports = 0;
+    config.max_crank_fees_per_market = 0;
+    config.max_markets_per_creator = 0;
+    config.max_protocol_volume = 0;
+    config.volume_window_secs = 0;
+    config.volume_window_start = 0;
+    config.window_volume = 0;
+    config.min_resolution_delay_secs = 0;
+    config.max_resolution_extension_secs = 0;
+    config.terminal_markets = 0;
+    config.max_question_len = 0;
+    config.rounding_mode = RoundingMode::Floor;
+    config.heartbeat_timeout_secs = 0;
+    config.pending_fee_collector = None;
+    config.require_distinct_oracle = false;
+    config.settlement_incentive = SettlementIncentive::None;
+    config.settlement_incentive_bps = 0;
+    config.settlement_incentive_window_secs = 0;
+    config.max_market_duration_secs = 0;
+    config.max_reading_staleness_secs = 0;
+
+    msg!(
+        "Config initialized: authority={} fee_bps={}",
+        config.authority,
+        config.fee_bps,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    /// Protocol authority — pays for the treasury account.
+    #[account(
+        mut,
+        constraint = authority.key() == config.authority @ PercolatorError::UnauthorizedAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global config — identifies the protocol authority.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// Treasury PDA.
+    #[account(
+        init,
+        payer = authority,
+        space = Treasury::SIZE,
+        seeds = [b"treasury"],
+        bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeTreasury>) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.total_collected = 0;
+    treasury.total_withdrawn = 0;
+    treasury.bump = ctx.bumps.treasury;
+
+    msg!("Treasury initialized: {}", treasury.key());
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// Protocol authority — pays the rent for the extra space.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Global config created under an older, shorter layout.
+    /// CHECK: Validated by seeds and owner; authority read from its raw bytes.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow the config to `GlobalConfig::SIZE`.
+///
+/// The old layout cannot be loaded as a `GlobalConfig`, so the authority is
+/// read straight from its bytes: it sits right after the discriminator in
+/// every layout. Every field added since reads as zero, which leaves its
+/// feature off until `update_config` sets it.
+pub fn handler(ctx: Context<MigrateConfig>) -> Result<()> {
+    let config_info = ctx.accounts.config.to_account_info();
+    require!(
+        config_info.data_len() < GlobalConfig::SIZE,
+        PercolatorError::AlreadyMigrated
+    );
+    {
+        let data = config_info.try_borrow_data()?;
+        require!(
+            data.len() >= 40 && data[..8] == GlobalConfig::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        require_keys_eq!(
+            Pubkey::try_from(&data[8..40]).unwrap(),
+            ctx.accounts.authority.key(),
+            PercolatorError::UnauthorizedAuthority
+        );
+    }
+
+    grow_account(
+        &config_info,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        GlobalConfig::SIZE,
+    )?;
+
+    // Fails loudly if the grown bytes do not load as the current layout
+    GlobalConfig::try_deserialize(&mut &config_info.try_borrow_data()?[..])?;
+
+    msg!("Config migrated to {} bytes", GlobalConfig::SIZE);
+
+    Ok(())
+}
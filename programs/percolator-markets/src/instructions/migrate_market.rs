@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct MigrateMarket<'info> {
+    /// Anyone — pays the rent for the extra space.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// A market created under an older, shorter layout.
+    /// CHECK: Owner checked here; discriminator checked on deserialization.
+    #[account(mut, owner = crate::ID)]
+    pub market: UncheckedAccount<'info>,
+
+    /// Market vault — read to recover refunded stake.
+    /// CHECK: Validated by seeds.
+    #[account(
+        seeds = [b"vault", market.key().as_ref()],
+        bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Creator revenue PDA — topped up to rent exemption.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"creator_revenue", market.key().as_ref()],
+        bump,
+    )]
+    pub creator_revenue: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow the market to `Market::SIZE` and fund its creator revenue PDA's
+/// rent, which markets created before that was done at creation lack.
+pub fn handler(ctx: Context<MigrateMarket>) -> Result<()> {
+    let market_info = ctx.accounts.market.to_account_info();
+    let payer = ctx.accounts.payer.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let grown = market_info.data_len() < Market::SIZE;
+    if grown {
+        grow_account(&market_info, &payer, &system_program, Market::SIZE)?;
+    }
+    let rent_funded = fund_rent(
+        &ctx.accounts.creator_revenue.to_account_info(),
+        &payer,
+        &system_program,
+        Rent::get()?.minimum_balance(0),
+    )? > 0;
+
+    let mut market = Market::try_deserialize(&mut &market_info.try_borrow_data()?[..])?;
+    require!(
+        grown || rent_funded || market.version == 0,
+        PercolatorError::AlreadyMigrated
+    );
+
+    // Only markets from before versioning need their new fields filled in;
+    // later ones grow with zeroes, which every newer field treats as unset
+    if market.version == 0 {
+        market.migrate_legacy(ctx.accounts.vault.lamports(), Clock::get()?.unix_timestamp)?;
+    }
+    market.try_serialize(&mut &mut market_info.try_borrow_mut_data()?[..])?;
+
+    msg!(
+        "Market #{} migrated: {} bytes, version {}",
+        market.market_id,
+        Market::SIZE,
+        market.version,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct MigratePosition<'info> {
+    /// Anyone — pays the rent for the extra space.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// A position created under an older, shorter layout.
+    /// CHECK: Owner checked here; discriminator checked in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub position: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow a position to `UserPosition::SIZE`.
+///
+/// Every field added since reads as zero: no memo, and entry pools of 0,
+/// which means the entry odds were never recorded.
+pub fn handler(ctx: Context<MigratePosition>) -> Result<()> {
+    let position_info = ctx.accounts.position.to_account_info();
+    require!(
+        position_info.data_len() < UserPosition::SIZE,
+        PercolatorError::AlreadyMigrated
+    );
+    {
+        let data = position_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == UserPosition::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+    }
+
+    grow_account(
+        &position_info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        UserPosition::SIZE,
+    )?;
+
+    // Fails loudly if the grown bytes do not load as the current layout
+    let position = UserPosition::try_deserialize(&mut &position_info.try_borrow_data()?[..])?;
+
+    msg!(
+        "Position migrated to {} bytes: user={}, market={}",
+        UserPosition::SIZE,
+        position.user,
+        position.market,
+    );
+
+    Ok(())
+}
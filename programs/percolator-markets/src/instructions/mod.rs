@@ -1,14 +1,128 @@
+pub mod initialize_config;
+pub mod update_config;
+pub mod initialize_treasury;
+pub mod withdraw_treasury;
+pub mod rescue_stray_lamports;
+pub mod set_creator_ban;
 pub mod create_market;
+pub mod create_market_deduped;
+pub mod update_target;
+pub mod amend_question;
+pub mod create_and_bet;
+pub mod resolve_scalar;
+pub mod resolve_blended;
+pub mod close_config;
+pub mod migrate_config;
+pub mod create_markets_batch;
+pub mod upsert_template;
+pub mod create_from_template;
+pub mod settle_many;
+pub mod outstanding_claims;
+pub mod verify_invariants;
+pub mod heartbeat;
+pub mod cash_out;
+pub mod spawn_hedge_market;
+pub mod cleanup_positions;
+pub mod propose_fee_collector;
+pub mod accept_fee_collector;
+pub mod compute_effective_payout;
+pub mod get_global_stats;
 pub mod place_bet;
+pub mod commit_bet;
+pub mod reveal_bet;
+pub mod refund_commitment;
 pub mod resolve;
+pub mod post_oracle_reading;
+pub mod resolve_with_rule;
+pub mod trigger_resolution;
+pub mod preview_resolution;
+pub mod record_checkpoint;
+pub mod resolve_default;
+pub mod request_resolution_extension;
 pub mod settle;
+pub mod crank_settle;
+pub mod post_settlement_root;
+pub mod claim_merkle;
+pub mod close_losing_position;
+pub mod preview_settle;
+pub mod total_claimable;
 pub mod cancel;
+pub mod admin_force_cancel;
 pub mod claim_refund;
+pub mod claim_refund_by_position;
+pub mod batch_refund;
+pub mod reconcile_position;
+pub mod add_liquidity;
+pub mod reclaim_liquidity;
+pub mod withdraw_creator_revenue;
+pub mod creator_sweep_unclaimed;
+pub mod restore_solvency;
+pub mod extend_deadline;
+pub mod set_market_frozen;
+pub mod append_oracle_schedule;
+pub mod migrate_market;
+pub mod migrate_position;
 
+pub use initialize_config::*;
+pub use update_config::*;
+pub use initialize_treasury::*;
+pub use withdraw_treasury::*;
+pub use rescue_stray_lamports::*;
+pub use set_creator_ban::*;
 pub use create_market::*;
+pub use create_market_deduped::*;
+pub use update_target::*;
+pub use amend_question::*;
+pub use create_and_bet::*;
+pub use close_config::*;
+pub use migrate_config::*;
+pub use create_markets_batch::*;
+pub use upsert_template::*;
+pub use create_from_template::*;
+pub use settle_many::*;
+pub use outstanding_claims::*;
+pub use verify_invariants::*;
+pub use heartbeat::*;
+pub use cash_out::*;
+pub use spawn_hedge_market::*;
+pub use cleanup_positions::*;
+pub use propose_fee_collector::*;
+pub use accept_fee_collector::*;
+pub use compute_effective_payout::*;
+pub use get_global_stats::*;
 pub use place_bet::*;
+pub use commit_bet::*;
+pub use reveal_bet::*;
+pub use refund_commitment::*;
 pub use resolve::*;
+pub use post_oracle_reading::*;
+pub use resolve_with_rule::*;
+pub use trigger_resolution::*;
+pub use preview_resolution::*;
+pub use record_checkpoint::*;
+pub use resolve_default::*;
+pub use request_resolution_extension::*;
 pub use settle::*;
+pub use crank_settle::*;
+pub use post_settlement_root::*;
+pub use claim_merkle::*;
+pub use close_losing_position::*;
+pub use preview_settle::*;
+pub use total_claimable::*;
 pub use cancel::*;
+pub use admin_force_cancel::*;
 pub use claim_refund::*;
+pub use claim_refund_by_position::*;
+pub use batch_refund::*;
+pub use reconcile_position::*;
+pub use add_liquidity::*;
+pub use reclaim_liquidity::*;
+pub use withdraw_creator_revenue::*;
+pub use creator_sweep_unclaimed::*;
+pub use restore_solvency::*;
+pub use extend_deadline::*;
+pub use set_market_frozen::*;
+pub use append_oracle_schedule::*;
+pub use migrate_market::*;
+pub use migrate_position::*;
 
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+/// Return data for `outstanding_claims`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ClaimsReport {
+    /// Still owed to unsettled winners at the current h-ratio.
+    pub outstanding: u64,
+
+    /// Lamports currently in the vault.
+    pub vault_balance: u64,
+}
+
+#[derive(Accounts)]
+pub struct OutstandingClaims<'info> {
+    /// The resolved (or fully settled) market.
+    #[account(
+        constraint = matches!(market.status, MarketStatus::Resolved | MarketStatus::Settled)
+            @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Market vault.
+    /// CHECK: Validated by seeds.
+    #[account(
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+}
+
+pub fn handler(ctx: Context<OutstandingClaims>) -> Result<ClaimsReport> {
+    Ok(ClaimsReport {
+        outstanding: ctx.accounts.market.owed_at_h()?,
+        vault_balance: ctx.accounts.vault.lamports(),
+    })
+}
@@ -1,8 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata::types::DataV2;
+use anchor_spl::metadata::{self, CreateMetadataAccountsV3, Metadata};
 use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
 
 use crate::errors::PercolatorError;
+use crate::events::BetPlaced;
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -14,7 +18,8 @@ pub struct PlaceBet<'info> {
     /// The prediction market.
     #[account(
         mut,
-        constraint = market.status == MarketStatus::Open @ PercolatorError::InvalidMarketStatus,
+        constraint = !market.frozen @ PercolatorError::MarketFrozen,
+        constraint = market.commit_deadline == 0 @ PercolatorError::CommitRevealRequired,
     )]
     pub market: Account<'info, Market>,
 
@@ -53,11 +58,12 @@ pub struct PlaceBet<'info> {
     )]
     pub no_mint: Account<'info, Mint>,
 
-    /// Bettor's token account for the chosen side.
+    /// Bettor's token account for the chosen side. Omitted in
+    /// `position_nfts` markets.
     #[account(mut)]
-    pub bettor_token_account: Account<'info, TokenAccount>,
+    pub bettor_token_account: Option<Box<Account<'info, TokenAccount>>>,
 
-    /// Global config for volume tracking.
+    /// Global config for volume tracking and fee settings.
     #[account(
         mut,
         seeds = [b"config"],
@@ -65,101 +71,384 @@ pub struct PlaceBet<'info> {
     )]
     pub config: Account<'info, GlobalConfig>,
 
+    /// Protocol treasury — receives the protocol share of the fee.
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Creator revenue PDA — accrues the creator share of the fee until
+    /// `withdraw_creator_revenue`.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"creator_revenue", market.key().as_ref()],
+        bump,
+    )]
+    pub creator_revenue: SystemAccount<'info>,
+
+    /// Position NFT mint — created by the bet, `position_nfts` markets only.
+    #[account(
+        init,
+        payer = bettor,
+        mint::decimals = 0,
+        mint::authority = market,
+        seeds = [b"position_nft", position.key().as_ref()],
+        bump,
+    )]
+    pub position_nft: Option<Box<Account<'info, Mint>>>,
+
+    /// Bettor's associated token account for the position NFT.
+    #[account(
+        init,
+        payer = bettor,
+        associated_token::mint = position_nft,
+        associated_token::authority = bettor,
+    )]
+    pub position_nft_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Token Metadata account of the position NFT.
+    /// CHECK: Derived and created by the Token Metadata program.
+    #[account(mut)]
+    pub position_nft_metadata: Option<UncheckedAccount<'info>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
 }
 
-pub fn handler(ctx: Context<PlaceBet>, side: BetSide, amount: u64) -> Result<()> {
+pub fn handler(
+    ctx: Context<PlaceBet>,
+    side: BetSide,
+    amount: u64,
+    memo: Option<[u8; 16]>,
+) -> Result<()> {
+    // Status-specific errors so clients know why betting is closed
+    match ctx.accounts.market.status {
+        MarketStatus::Open => {}
+        MarketStatus::Closed => return err!(PercolatorError::MarketExpired),
+        MarketStatus::Cancelled => return err!(PercolatorError::MarketCancelled),
+        _ => return err!(PercolatorError::InvalidMarketStatus),
+    }
+
     require!(amount > 0, PercolatorError::ZeroBetAmount);
 
     let clock = Clock::get()?;
     let market = &ctx.accounts.market;
+    market.check_bettor(&ctx.accounts.bettor.key())?;
     require!(
         clock.unix_timestamp < market.deadline,
         PercolatorError::MarketExpired
     );
 
-    // Transfer SOL from bettor to vault
+    // Fee and resolution bounty come off the top; only the net stake
+    // enters the pool
+    let (protocol_fee, creator_fee) = ctx.accounts.config.split_fee(amount);
+    let bounty = market.bounty_share(amount);
+    let stake = net_stake(amount, protocol_fee, creator_fee, bounty)?;
+
+    // Transfer SOL from bettor to vault, treasury and creator revenue
+    let bettor = ctx.accounts.bettor.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    transfer_from_bettor(&system_program, &bettor, &ctx.accounts.vault.to_account_info(), stake + bounty)?;
+    transfer_from_bettor(&system_program, &bettor, &ctx.accounts.treasury.to_account_info(), protocol_fee)?;
+    transfer_from_bettor(&system_program, &bettor, &ctx.accounts.creator_revenue.to_account_info(), creator_fee)?;
+
+    // The memo is fixed by the first deposit; later ones can't rewrite it
+    let first_bet = ctx.accounts.position.user == Pubkey::default();
+
+    if ctx.accounts.market.position_nfts {
+        require!(
+            ctx.accounts.position.user == Pubkey::default(),
+            PercolatorError::PositionNftAlreadyMinted
+        );
+        mint_position_nft(ctx.accounts, side, stake)?;
+    } else {
+        let to = ctx.accounts.bettor_token_account.as_ref()
+            .ok_or(PercolatorError::PositionNftMismatch)?;
+        let mint = match side {
+            BetSide::Yes => ctx.accounts.yes_mint.to_account_info(),
+            BetSide::No => ctx.accounts.no_mint.to_account_info(),
+        };
+
+        mint_position_tokens(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.market,
+            mint,
+            to.to_account_info(),
+            stake,
+        )?;
+    }
+
+    record_bet(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.position,
+        ctx.bumps.position,
+        ctx.accounts.bettor.key(),
+        side,
+        stake,
+        creator_fee,
+    )?;
+    if first_bet {
+        ctx.accounts.position.memo = memo.unwrap_or_default();
+    }
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.total_collected = treasury.total_collected.checked_add(protocol_fee)
+        .ok_or(PercolatorError::Overflow)?;
+    let market = &mut ctx.accounts.market;
+    market.creator_revenue = market.creator_revenue.checked_add(creator_fee)
+        .ok_or(PercolatorError::Overflow)?;
+    market.resolution_bounty = market.resolution_bounty.checked_add(bounty)
+        .ok_or(PercolatorError::Overflow)?;
+
+    // Track global volume; trips the circuit breaker past the cap
+    ctx.accounts.config.record_volume(amount, clock.unix_timestamp)?;
+
+    emit!(BetPlaced {
+        market: ctx.accounts.market.key(),
+        market_id: ctx.accounts.market.market_id,
+        bettor: ctx.accounts.bettor.key(),
+        side,
+        stake,
+        protocol_fee,
+        creator_fee,
+        memo: ctx.accounts.position.memo,
+        entry_yes_pool: ctx.accounts.position.entry_yes_pool,
+        entry_no_pool: ctx.accounts.position.entry_no_pool,
+    });
+
+    msg!(
+        "Bet placed: {} lamports on {:?} for market #{} (fee: {} protocol + {} creator)",
+        stake,
+        side as u8,
+        ctx.accounts.market.market_id,
+        protocol_fee,
+        creator_fee,
+    );
+
+    Ok(())
+}
+
+/// System transfer from the bettor; no-op for zero amounts.
+pub(crate) fn transfer_from_bettor<'info>(
+    system_program: &AccountInfo<'info>,
+    bettor: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
     system_program::transfer(
         CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
+            system_program.clone(),
             system_program::Transfer {
-                from: ctx.accounts.bettor.to_account_info(),
-                to: ctx.accounts.vault.to_account_info(),
+                from: bettor.clone(),
+                to: to.clone(),
             },
         ),
         amount,
-    )?;
-
-    // Determine which mint to use
-    let mint = match side {
-        BetSide::Yes => ctx.accounts.yes_mint.to_account_info(),
-        BetSide::No => ctx.accounts.no_mint.to_account_info(),
-    };
+    )
+}
 
-    // Mint position tokens to bettor
+/// Mint `amount` position tokens for `mint` to `to`, signed by the market PDA.
+pub(crate) fn mint_position_tokens<'info>(
+    token_program: &AccountInfo<'info>,
+    market: &Account<'info, Market>,
+    mint: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
     // Market PDA is the mint authority
-    let market_key = ctx.accounts.market.key();
-    let creator_key = ctx.accounts.market.creator;
-    let market_id_bytes = ctx.accounts.market.market_id.to_le_bytes();
-    let bump = ctx.accounts.market.bump;
+    let market_id_bytes = market.market_id.to_le_bytes();
     let seeds: &[&[u8]] = &[
         b"market",
-        creator_key.as_ref(),
+        market.creator.as_ref(),
         market_id_bytes.as_ref(),
-        &[bump],
+        &[market.bump],
     ];
 
     token::mint_to(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
+            token_program.clone(),
             MintTo {
                 mint,
-                to: ctx.accounts.bettor_token_account.to_account_info(),
-                authority: ctx.accounts.market.to_account_info(),
+                to,
+                authority: market.to_account_info(),
             },
             &[seeds],
         ),
-        amount, // 1:1 — each lamport = 1 position token
+        amount, // 1:1 — each staked lamport = 1 position token
+    )
+}
+
+/// Mint the bettor's position NFT and create its Token Metadata, both signed
+/// by the market PDA. The name records the side and stake in lamports; the
+/// URI is the market's `question_uri`.
+fn mint_position_nft(accounts: &PlaceBet, side: BetSide, stake: u64) -> Result<()> {
+    let (Some(mint), Some(to), Some(metadata_account), Some(metadata_program)) = (
+        accounts.position_nft.as_ref(),
+        accounts.position_nft_account.as_ref(),
+        accounts.position_nft_metadata.as_ref(),
+        accounts.token_metadata_program.as_ref(),
+    ) else {
+        return err!(PercolatorError::PositionNftMismatch);
+    };
+
+    let market = &accounts.market;
+    mint_position_tokens(
+        &accounts.token_program.to_account_info(),
+        market,
+        mint.to_account_info(),
+        to.to_account_info(),
+        1,
     )?;
 
+    let market_id_bytes = market.market_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[
+        b"market",
+        market.creator.as_ref(),
+        market_id_bytes.as_ref(),
+        &[market.bump],
+    ];
+    let side_label = match side {
+        BetSide::Yes => "YES",
+        BetSide::No => "NO",
+    };
+
+    metadata::create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: metadata_account.to_account_info(),
+                mint: mint.to_account_info(),
+                mint_authority: market.to_account_info(),
+                payer: accounts.bettor.to_account_info(),
+                update_authority: market.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+                // V3 reads rent itself; the slot is not part of the instruction
+                rent: accounts.system_program.to_account_info(),
+            },
+            &[seeds],
+        ),
+        DataV2 {
+            name: format!("{} {}", side_label, stake),
+            symbol: Market::POSITION_NFT_SYMBOL.to_string(),
+            uri: market.question_uri.clone(),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        },
+        false, // the receipt is immutable
+        true,
+        None,
+    )
+}
+
+/// What is left of a bet's gross `amount` once the fees and bounty come
+/// off. Fails with `ZeroBetAmount` if nothing is.
+pub(crate) fn net_stake(amount: u64, protocol_fee: u64, creator_fee: u64, bounty: u64) -> Result<u64> {
+    let stake = amount
+        .checked_sub(protocol_fee)
+        .and_then(|rest| rest.checked_sub(creator_fee))
+        .and_then(|rest| rest.checked_sub(bounty))
+        .unwrap_or(0);
+    require!(stake > 0, PercolatorError::ZeroBetAmount);
+    Ok(stake)
+}
+
+/// Add a net `stake` on `side` to the market pools and the user's position.
+pub(crate) fn record_bet(
+    market: &mut Account<Market>,
+    position: &mut UserPosition,
+    position_bump: u8,
+    user: Pubkey,
+    side: BetSide,
+    stake: u64,
+    creator_fee: u64,
+) -> Result<()> {
+    market.check_imbalance(side, stake)?;
+
     // Update market pools
-    let market = &mut ctx.accounts.market;
     match side {
         BetSide::Yes => {
-            market.yes_pool = market.yes_pool.checked_add(amount)
+            market.yes_pool = market.yes_pool.checked_add(stake)
                 .ok_or(PercolatorError::Overflow)?;
         }
         BetSide::No => {
-            market.no_pool = market.no_pool.checked_add(amount)
+            market.no_pool = market.no_pool.checked_add(stake)
                 .ok_or(PercolatorError::Overflow)?;
         }
     }
+    market.creator_fees_earned = market.creator_fees_earned.checked_add(creator_fee)
+        .ok_or(PercolatorError::Overflow)?;
 
-    // Update user position
-    let position = &mut ctx.accounts.position;
-    if position.deposited == 0 {
+    // Early-bird bonus: extra weight on the subsidy share, never on capital
+    let clock = Clock::get()?;
+    market.last_bet_slot = clock.slot;
+    let early_bird_end = market.created_at.saturating_add(market.early_bird_window_secs);
+    let bonus_stake = if clock.unix_timestamp < early_bird_end {
+        (stake as u128 * market.early_bird_bonus_bps as u128 / 10_000) as u64
+    } else {
+        0
+    };
+
+    // Time weight: how long the stake is committed before the deadline
+    let committed_secs = market.deadline.saturating_sub(clock.unix_timestamp).max(0);
+    let weighted_stake = stake as u128 * committed_secs as u128;
+
+    match side {
+        BetSide::Yes => {
+            market.yes_bonus_stake = market.yes_bonus_stake.checked_add(bonus_stake)
+                .ok_or(PercolatorError::Overflow)?;
+            market.yes_weighted_stake = market.yes_weighted_stake.checked_add(weighted_stake)
+                .ok_or(PercolatorError::Overflow)?;
+        }
+        BetSide::No => {
+            market.no_bonus_stake = market.no_bonus_stake.checked_add(bonus_stake)
+                .ok_or(PercolatorError::Overflow)?;
+            market.no_weighted_stake = market.no_weighted_stake.checked_add(weighted_stake)
+                .ok_or(PercolatorError::Overflow)?;
+        }
+    }
+
+    // Update user position. A position reconciled down to zero keeps its
+    // side but drops out of the count until it holds stake again.
+    if position.user == Pubkey::default() {
         // First bet — initialize
         position.market = market.key();
-        position.user = ctx.accounts.bettor.key();
+        position.user = user;
         position.side = side;
-        position.bump = ctx.bumps.position;
+        position.bump = position_bump;
+        position.entry_yes_pool = market.yes_pool;
+        position.entry_no_pool = market.no_pool;
+    } else {
+        require!(position.side == side, PercolatorError::PositionSideMismatch);
+    }
+    if position.deposited == 0 {
+        match side {
+            BetSide::Yes => {
+                market.yes_positions = market.yes_positions.checked_add(1)
+                    .ok_or(PercolatorError::Overflow)?;
+            }
+            BetSide::No => {
+                market.no_positions = market.no_positions.checked_add(1)
+                    .ok_or(PercolatorError::Overflow)?;
+            }
+        }
     }
-    position.deposited = position.deposited.checked_add(amount)
+    position.deposited = position.deposited.checked_add(stake)
         .ok_or(PercolatorError::Overflow)?;
-
-    // Track global volume
-    let config = &mut ctx.accounts.config;
-    config.total_volume = config.total_volume.checked_add(amount)
+    position.bonus_stake = position.bonus_stake.checked_add(bonus_stake)
+        .ok_or(PercolatorError::Overflow)?;
+    position.weighted_stake = position.weighted_stake.checked_add(weighted_stake)
         .ok_or(PercolatorError::Overflow)?;
-
-    msg!(
-        "Bet placed: {} lamports on {:?} for market #{}",
-        amount,
-        side as u8,
-        market.market_id,
-    );
 
     Ok(())
 }
-
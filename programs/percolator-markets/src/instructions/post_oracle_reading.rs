@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+/// Raw data for an oracle reading.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PostOracleReadingParams {
+    /// Token the reading is about.
+    pub token_mint: Pubkey,
+
+    /// Token price in USD × 10^`price_exponent`.
+    pub price: u64,
+
+    /// Decimal exponent of `price`, as the feed reports it (-18–0).
+    pub price_exponent: i32,
+
+    /// Circulating supply in whole tokens.
+    pub supply: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(params: PostOracleReadingParams)]
+pub struct PostOracleReading<'info> {
+    /// Oracle posting the reading — pays for the account on first post.
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+
+    /// Reading PDA for this (oracle, token) pair.
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        space = OracleReading::SIZE,
+        seeds = [b"oracle_reading", oracle.key().as_ref(), params.token_mint.as_ref()],
+        bump,
+    )]
+    pub reading: Account<'info, OracleReading>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<PostOracleReading>, params: PostOracleReadingParams) -> Result<()> {
+    require!(
+        PRICE_EXPONENT_RANGE.contains(&params.price_exponent),
+        PercolatorError::InvalidPriceExponent
+    );
+
+    let clock = Clock::get()?;
+    let reading = &mut ctx.accounts.reading;
+
+    reading.oracle = ctx.accounts.oracle.key();
+    reading.token_mint = params.token_mint;
+    reading.price = params.price;
+    reading.price_exponent = params.price_exponent;
+    reading.supply = params.supply;
+    reading.published_at = clock.unix_timestamp;
+    reading.bump = ctx.bumps.reading;
+    reading.version = OracleReading::VERSION;
+
+    msg!(
+        "Oracle reading: token={} price={}e{} supply={} market_cap={:?}",
+        reading.token_mint,
+        reading.price,
+        reading.price_exponent,
+        reading.supply,
+        reading.market_cap(),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+/// Parameters for posting a Merkle settlement root.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PostSettlementRootParams {
+    /// Root of the `(index, user, payout)` tree.
+    pub root: [u8; 32],
+
+    /// Sum of all leaf payouts (lamports).
+    pub total_payout: u64,
+
+    /// Number of leaves in the tree.
+    pub leaf_count: u32,
+}
+
+#[derive(Accounts)]
+#[instruction(params: PostSettlementRootParams)]
+pub struct PostSettlementRoot<'info> {
+    /// Market oracle or protocol authority — pays for the distribution account.
+    #[account(
+        mut,
+        constraint = poster.key() == market.oracle || poster.key() == config.authority
+            @ PercolatorError::UnauthorizedOracle,
+    )]
+    pub poster: Signer<'info>,
+
+    /// Global config — identifies the protocol authority.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// The resolved market. No position may have settled individually yet.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Resolved @ PercolatorError::InvalidMarketStatus,
+        constraint = market.settlements_count == 0 @ PercolatorError::InvalidMarketStatus,
+        // Merkle claims issue no receipts
+        constraint = !market.settlement_receipts @ PercolatorError::SettlementReceiptMismatch,
+        // Merkle claims burn no position NFTs
+        constraint = !market.position_nfts @ PercolatorError::PositionNftsUnsupported,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Distribution PDA holding the root and the claim bitmap.
+    #[account(
+        init,
+        payer = poster,
+        space = MerkleDistribution::space(params.leaf_count),
+        seeds = [b"merkle_distribution", market.key().as_ref()],
+        bump,
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+
+    /// Market vault — must cover the posted total.
+    /// CHECK: Validated by seeds.
+    #[account(
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<PostSettlementRoot>, params: PostSettlementRootParams) -> Result<()> {
+    require!(
+        params.leaf_count > 0 && params.leaf_count <= MerkleDistribution::MAX_LEAVES,
+        PercolatorError::InvalidLeafCount
+    );
+    require!(
+        params.total_payout <= ctx.accounts.vault.lamports(),
+        PercolatorError::SettlementRootExceedsVault
+    );
+
+    let distribution = &mut ctx.accounts.distribution;
+    distribution.market = ctx.accounts.market.key();
+    distribution.root = params.root;
+    distribution.total_payout = params.total_payout;
+    distribution.claimed_amount = 0;
+    distribution.leaf_count = params.leaf_count;
+    distribution.claimed_count = 0;
+    distribution.bump = ctx.bumps.distribution;
+    distribution.claimed = vec![0; MerkleDistribution::bitmap_len(params.leaf_count)];
+
+    let market = &mut ctx.accounts.market;
+    market.merkle_settlement = true;
+
+    msg!(
+        "Settlement root posted for market #{}: {} leaves, {} lamports",
+        market.market_id,
+        params.leaf_count,
+        params.total_payout,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+/// Return data for `preview_resolution`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ResolutionPreview {
+    /// Outcome `resolve_with_rule` would apply to this reading.
+    pub outcome: Outcome,
+
+    /// Market cap or price derived from the reading, in `target_value` units.
+    pub observed_value: u64,
+
+    /// The market's target, for comparison.
+    pub target_value: u64,
+
+    /// When the reading was published. `resolve_with_rule` only accepts
+    /// readings published at or after the deadline.
+    pub published_at: i64,
+}
+
+#[derive(Accounts)]
+pub struct PreviewResolution<'info> {
+    /// The rule-based market to preview.
+    #[account(
+        constraint = market.status == MarketStatus::Open || market.status == MarketStatus::Closed
+            @ PercolatorError::AlreadyResolved,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// The oracle's reading for this market's token.
+    #[account(
+        seeds = [b"oracle_reading", market.oracle.as_ref(), market.token_mint.as_ref()],
+        bump = reading.bump,
+    )]
+    pub reading: Account<'info, OracleReading>,
+}
+
+/// Evaluate the market's rule against the current reading, exactly as
+/// `resolve_with_rule` would, including the forced NO once a checkpoint has
+/// breached the floor. Timing, heartbeat and secondary-oracle checks
+/// are not applied, so the preview works before the deadline.
+pub fn handler(ctx: Context<PreviewResolution>) -> Result<ResolutionPreview> {
+    let market = &ctx.accounts.market;
+    let reading = &ctx.accounts.reading;
+
+    let observed_value = reading
+        .observed_value(market.rule, market.price_exponent)
+        .ok_or(PercolatorError::InvalidOutcome)?;
+    let outcome = market
+        .rule
+        .evaluate(observed_value, market.target_value)
+        .ok_or(PercolatorError::InvalidOutcome)?;
+    let outcome = if market.floor_breached { Outcome::No } else { outcome };
+
+    Ok(ResolutionPreview {
+        outcome,
+        observed_value,
+        target_value: market.target_value,
+        published_at: reading.published_at,
+    })
+}
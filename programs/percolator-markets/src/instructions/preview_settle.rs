@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+/// Why a preview would (or would not) settle.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewStatus {
+    /// `settle` would pay out `breakdown.total`.
+    Ok,
+
+    /// The market is not in `Resolved` status.
+    NotResolved,
+
+    /// The position has already been settled or refunded.
+    AlreadySettled,
+
+    /// The position is on the losing side.
+    LosingSide,
+}
+
+/// Return data for `preview_settle`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct SettlePreview {
+    pub status: PreviewStatus,
+    pub breakdown: PayoutBreakdown,
+}
+
+#[derive(Accounts)]
+pub struct PreviewSettle<'info> {
+    /// The market to preview against.
+    pub market: Account<'info, Market>,
+
+    /// The position to preview.
+    #[account(
+        constraint = position.market == market.key() @ PercolatorError::NoPosition,
+    )]
+    pub position: Account<'info, UserPosition>,
+}
+
+pub fn handler(ctx: Context<PreviewSettle>) -> Result<SettlePreview> {
+    let market = &ctx.accounts.market;
+    let position = &ctx.accounts.position;
+
+    // Conditions `settle` would reject are reported, not raised
+    let status = if market.status != MarketStatus::Resolved {
+        PreviewStatus::NotResolved
+    } else if position.settled {
+        PreviewStatus::AlreadySettled
+    } else if !market.is_winning_side(position.side) {
+        PreviewStatus::LosingSide
+    } else {
+        PreviewStatus::Ok
+    };
+
+    let breakdown = if status == PreviewStatus::Ok {
+        market.payout_breakdown(position)?
+    } else {
+        PayoutBreakdown::default()
+    };
+
+    Ok(SettlePreview { status, breakdown })
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ProposeFeeCollector<'info> {
+    /// Protocol authority.
+    #[account(
+        constraint = authority.key() == config.authority @ PercolatorError::UnauthorizedAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global config PDA.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+pub fn handler(ctx: Context<ProposeFeeCollector>, new_fee_collector: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.pending_fee_collector = Some(new_fee_collector);
+
+    msg!(
+        "Fee collector handover proposed: {} -> {}",
+        config.fee_collector,
+        new_fee_collector,
+    );
+
+    Ok(())
+}
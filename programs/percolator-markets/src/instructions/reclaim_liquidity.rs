@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ReclaimLiquidity<'info> {
+    /// Market creator — receives the unused liquidity.
+    #[account(
+        mut,
+        constraint = creator.key() == market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: Signer<'info>,
+
+    /// A fully settled market, or a cancelled one whose refunds the
+    /// liquidity backs first.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Settled || market.status == MarketStatus::Cancelled
+            @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Market vault — source of the reclaimed liquidity.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ReclaimLiquidity>) -> Result<()> {
+    let market = &ctx.accounts.market;
+
+    require!(
+        market.creator_liquidity > 0 || market.creator_subsidy > 0,
+        PercolatorError::NoCreatorLiquidity
+    );
+
+    // Whatever the h-ratio or a short refund consumed is gone; the creator
+    // gets back the rest, never more than they put in. What liquidity is
+    // not returned stays in the vault for the refunds.
+    let amount = market.reclaimable(ctx.accounts.vault.lamports());
+
+    **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= amount;
+    assert_vault_solvent(&ctx.accounts.vault.to_account_info(), Rent::get()?.minimum_balance(0))?;
+    **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    let market = &mut ctx.accounts.market;
+    market.creator_liquidity = 0;
+    market.creator_subsidy = 0;
+
+    msg!(
+        "Liquidity reclaimed: {} lamports returned to creator of market #{}",
+        amount,
+        market.market_id,
+    );
+
+    Ok(())
+}
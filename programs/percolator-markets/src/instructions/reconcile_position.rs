@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::TokenAccount;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(side: BetSide)]
+pub struct ReconcilePosition<'info> {
+    /// Anyone — pays for the position account if the user has none.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Owner of the position and token account.
+    /// CHECK: Only used as a key.
+    pub user: UncheckedAccount<'info>,
+
+    /// An unresolved market.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open || market.status == MarketStatus::Closed
+            @ PercolatorError::AlreadyResolved,
+        constraint = !market.position_nfts @ PercolatorError::PositionNftsUnsupported,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// The user's position — created if they only hold bought tokens.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = UserPosition::SIZE,
+        seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub position: Account<'info, UserPosition>,
+
+    /// The user's associated token account for `side`'s mint.
+    #[account(
+        address = get_associated_token_address(
+            &user.key(),
+            &match side {
+                BetSide::Yes => market.yes_mint,
+                BetSide::No => market.no_mint,
+            },
+        ),
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Set the position's stake to the user's `side` token balance.
+///
+/// Lowering a position releases the difference into the side's reconcile
+/// slack; raising one is capped by that slack, so the positions on a side
+/// never claim more than its pool. Bought stake carries no early-bird or
+/// time weight.
+pub fn handler(ctx: Context<ReconcilePosition>, side: BetSide) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let position = &mut ctx.accounts.position;
+    let balance = ctx.accounts.token_account.amount;
+    let current = position.deposited;
+
+    let is_new = position.user == Pubkey::default();
+    require!(is_new || position.side == side, PercolatorError::PositionSideMismatch);
+
+    let slack = match side {
+        BetSide::Yes => &mut market.yes_reconcile_slack,
+        BetSide::No => &mut market.no_reconcile_slack,
+    };
+
+    if balance < current {
+        // Weights shrink with the stake they were earned on
+        let released = current - balance;
+        position.bonus_stake = (position.bonus_stake as u128 * balance as u128 / current as u128) as u64;
+        position.weighted_stake = position.weighted_stake * balance as u128 / current as u128;
+        position.deposited = balance;
+        *slack = slack.checked_add(released).ok_or(PercolatorError::Overflow)?;
+        if balance == 0 {
+            market.remove_position(side)?;
+        }
+    } else {
+        let taken = (balance - current).min(*slack);
+        *slack -= taken;
+        position.deposited = current.checked_add(taken).ok_or(PercolatorError::Overflow)?;
+
+        if is_new && taken > 0 {
+            position.market = market.key();
+            position.user = ctx.accounts.user.key();
+            position.side = side;
+            position.bump = ctx.bumps.position;
+            position.entry_yes_pool = market.yes_pool;
+            position.entry_no_pool = market.no_pool;
+        }
+        // New positions, and ones reconciled to zero earlier, count again
+        if current == 0 && taken > 0 {
+            match side {
+                BetSide::Yes => {
+                    market.yes_positions = market.yes_positions.checked_add(1)
+                        .ok_or(PercolatorError::Overflow)?;
+                }
+                BetSide::No => {
+                    market.no_positions = market.no_positions.checked_add(1)
+                        .ok_or(PercolatorError::Overflow)?;
+                }
+            }
+        }
+    }
+
+    msg!(
+        "Position reconciled: user={} {} -> {} (balance {}), market #{}",
+        ctx.accounts.user.key(),
+        current,
+        position.deposited,
+        balance,
+        market.market_id,
+    );
+
+    Ok(())
+}
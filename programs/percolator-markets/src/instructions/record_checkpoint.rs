@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RecordCheckpoint<'info> {
+    /// Oracle authority.
+    #[account(
+        constraint = oracle.key() == market.oracle @ PercolatorError::UnauthorizedOracle,
+    )]
+    pub oracle: Signer<'info>,
+
+    /// An unresolved `MarketCapFloor` market.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open || market.status == MarketStatus::Closed
+            @ PercolatorError::AlreadyResolved,
+        constraint = market.rule == MarketRule::MarketCapFloor @ PercolatorError::InvalidMarketRule,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+pub fn handler(ctx: Context<RecordCheckpoint>, observed_value: u64) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    // Only the period up to the deadline counts
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp <= market.deadline,
+        PercolatorError::MarketExpired
+    );
+
+    // A checkpoint doubles as a heartbeat
+    market.last_heartbeat = clock.unix_timestamp;
+
+    // Latched: a later recovery does not clear an earlier breach
+    if observed_value < market.target_value {
+        market.floor_breached = true;
+    }
+
+    msg!(
+        "Checkpoint: market #{} value={} floor={} breached={}",
+        market.market_id,
+        observed_value,
+        market.target_value,
+        market.floor_breached,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RefundCommitment<'info> {
+    /// The user who made the commitment.
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// The commit-reveal market.
+    pub market: Account<'info, Market>,
+
+    /// Unrevealed commitment — closed to the user, returning escrow and rent.
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"commitment", market.key().as_ref(), user.key().as_ref()],
+        bump = commitment.bump,
+    )]
+    pub commitment: Account<'info, BetCommitment>,
+}
+
+pub fn handler(ctx: Context<RefundCommitment>) -> Result<()> {
+    let clock = Clock::get()?;
+    let market = &ctx.accounts.market;
+
+    // Refundable once it can no longer be revealed
+    require!(
+        market.status == MarketStatus::Cancelled || clock.unix_timestamp >= market.deadline,
+        PercolatorError::CommitmentStillRevealable
+    );
+
+    msg!(
+        "Commitment refunded: {} lamports to {} for market #{}",
+        ctx.accounts.commitment.amount,
+        ctx.accounts.user.key(),
+        market.market_id,
+    );
+
+    Ok(())
+}
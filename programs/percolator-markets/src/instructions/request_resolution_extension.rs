@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::events::ResolutionExtended;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RequestResolutionExtension<'info> {
+    /// Oracle authority of the market.
+    #[account(
+        constraint = oracle.key() == market.oracle @ PercolatorError::UnauthorizedOracle,
+    )]
+    pub oracle: Signer<'info>,
+
+    /// Global config — extension limit.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// The unresolved market.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open || market.status == MarketStatus::Closed
+            @ PercolatorError::AlreadyResolved,
+        constraint = !market.resolution_extended @ PercolatorError::ResolutionAlreadyExtended,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+pub fn handler(ctx: Context<RequestResolutionExtension>, extension_secs: i64) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let market = &mut ctx.accounts.market;
+
+    // Once the window has ended, resolve_default may already finalize
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp < market.resolution_window_end,
+        PercolatorError::ResolutionWindowEnded
+    );
+
+    require!(extension_secs > 0, PercolatorError::DeadlineNotExtended);
+    require!(
+        extension_secs <= config.max_resolution_extension_secs
+            && extension_secs <= GlobalConfig::RESOLUTION_EXTENSION_HARD_CAP_SECS,
+        PercolatorError::ExtensionTooLong
+    );
+
+    let old_window_end = market.resolution_window_end;
+    market.resolution_window_end = old_window_end.checked_add(extension_secs)
+        .ok_or(PercolatorError::Overflow)?;
+    market.resolution_extended = true;
+
+    emit!(ResolutionExtended {
+        market: market.key(),
+        market_id: market.market_id,
+        oracle: ctx.accounts.oracle.key(),
+        old_window_end,
+        new_window_end: market.resolution_window_end,
+    });
+
+    msg!(
+        "Market #{} resolution window extended: {} -> {}",
+        market.market_id,
+        old_window_end,
+        market.resolution_window_end,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RescueStrayLamports<'info> {
+    /// Protocol authority.
+    #[account(
+        constraint = authority.key() == config.authority @ PercolatorError::UnauthorizedAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global config — identifies the authority and fee collector.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// The market whose account holds the stray lamports. Its vault is
+    /// a separate account and is never touched.
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+
+    /// Fee collector — receives the rescued lamports.
+    /// CHECK: Validated against `config.fee_collector`.
+    #[account(
+        mut,
+        address = config.fee_collector @ PercolatorError::UnauthorizedAuthority,
+    )]
+    pub fee_collector: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<RescueStrayLamports>) -> Result<()> {
+    // Only lamports above the data account's rent-exempt minimum are stray
+    let market_info = ctx.accounts.market.to_account_info();
+    let rent_min = Rent::get()?.minimum_balance(market_info.data_len());
+    let amount = market_info.lamports().saturating_sub(rent_min);
+    require!(amount > 0, PercolatorError::ZeroAmount);
+
+    **market_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.fee_collector.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    msg!(
+        "Rescued {} stray lamports from market #{} to {}",
+        amount,
+        ctx.accounts.market.market_id,
+        ctx.accounts.fee_collector.key(),
+    );
+
+    Ok(())
+}
@@ -1,66 +1,273 @@
 use anchor_lang::prelude::*;
 
 use crate::errors::PercolatorError;
+use crate::events::MarketResolved;
+use crate::instructions::cancel::cancel;
 use crate::state::*;
 
 #[derive(Accounts)]
 pub struct ResolveMarket<'info> {
-    /// Oracle authority — the only account authorized to resolve.
+    /// Oracle authority — `market.oracle`, or the scheduled oracle in effect.
+    /// Receives the market's resolution bounty.
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+
+    /// Global config — minimum resolution delay; counts terminal markets.
     #[account(
-        constraint = oracle.key() == market.oracle @ PercolatorError::UnauthorizedOracle,
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
     )]
-    pub oracle: Signer<'info>,
+    pub config: Account<'info, GlobalConfig>,
 
     /// The market to resolve.
     #[account(
         mut,
         constraint = market.status == MarketStatus::Open || market.status == MarketStatus::Closed
             @ PercolatorError::AlreadyResolved,
+        constraint = !market.frozen @ PercolatorError::MarketFrozen,
     )]
     pub market: Account<'info, Market>,
 
-    /// Market vault — read balance for h-ratio computation.
+    /// Market vault — read balance for h-ratio computation; refunds unused subsidy.
     /// CHECK: Validated by seeds.
     #[account(
+        mut,
         seeds = [b"vault", market.key().as_ref()],
         bump = market.vault_bump,
     )]
     pub vault: SystemAccount<'info>,
+
+    /// Market creator — receives any unused subsidy.
+    /// CHECK: Validated against `market.creator`.
+    #[account(
+        mut,
+        address = market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: UncheckedAccount<'info>,
+
+    /// Oracle rotation schedule — required when `market.has_oracle_schedule`.
+    #[account(
+        seeds = [b"oracle_schedule", market.key().as_ref()],
+        bump = oracle_schedule.bump,
+    )]
+    pub oracle_schedule: Option<Account<'info, OracleSchedule>>,
+
+    /// Parent market — required when `market.parent_market` is set.
+    #[account(
+        constraint = Some(parent_market.key()) == market.parent_market
+            @ PercolatorError::InvalidParentMarket,
+    )]
+    pub parent_market: Option<Account<'info, Market>>,
+
+    /// Creator stats — frees an active-market slot once the market closes.
+    /// Omitted only for markets created before creator stats existed.
+    #[account(
+        mut,
+        seeds = [b"creator_stats", market.creator.as_ref()],
+        bump = creator_stats.bump,
+    )]
+    pub creator_stats: Option<Account<'info, CreatorStats>>,
 }
 
 pub fn handler(ctx: Context<ResolveMarket>, outcome: Outcome) -> Result<()> {
     require!(
-        outcome != Outcome::Unresolved,
+        outcome == Outcome::Yes || outcome == Outcome::No,
         PercolatorError::InvalidOutcome
     );
+    // Scalar markets resolve to a value with `resolve_scalar`
+    require!(
+        !matches!(ctx.accounts.market.rule, MarketRule::Scalar { .. }),
+        PercolatorError::InvalidMarketRule
+    );
 
     let clock = Clock::get()?;
+    let active_oracle = ctx.accounts.market.active_oracle(
+        ctx.accounts.oracle_schedule.as_deref(),
+        clock.unix_timestamp,
+    )?;
+    require!(
+        ctx.accounts.oracle.key() == active_oracle,
+        PercolatorError::UnauthorizedOracle
+    );
+    ctx.accounts.market.check_resolution_delay(&ctx.accounts.config, clock.unix_timestamp)?;
+    ctx.accounts.market.check_bet_slot(clock.slot)?;
+
+    if void_if_parent_failed(
+        &mut ctx.accounts.market,
+        ctx.accounts.parent_market.as_ref(),
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        ctx.accounts.oracle.key(),
+    )? {
+        return Ok(());
+    }
+
     let market = &mut ctx.accounts.market;
 
     // Market must have reached deadline (or we allow early resolution by oracle)
     // For flexibility, we allow oracle to resolve at any time — they are trusted.
 
+    apply_outcome(
+        market,
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.oracle.to_account_info(),
+        outcome,
+        ResolutionSource::ManualOracle,
+    )
+}
+
+/// Cancel a conditional market whose parent resolved the wrong way (or was
+/// cancelled). Returns whether the market was voided.
+///
+/// Fails with `ParentNotResolved` while the parent is undecided.
+pub(crate) fn void_if_parent_failed(
+    market: &mut Account<Market>,
+    parent: Option<&Account<Market>>,
+    config: &mut GlobalConfig,
+    creator_stats: Option<&mut CreatorStats>,
+    caller: Pubkey,
+) -> Result<bool> {
+    if market.parent_condition_met(parent)? {
+        return Ok(false);
+    }
+
+    msg!(
+        "Market #{} voided: parent condition not met",
+        market.market_id,
+    );
+    cancel(market, config, creator_stats, caller)?;
+    Ok(true)
+}
+
+/// Record `outcome` and finalize settlement parameters.
+///
+/// Shared by every resolution path so they all settle identically; each
+/// passes its `source`, stamped on the market and in `MarketResolved`. If
+/// the vault would leave h at 0, the market is cancelled instead. On
+/// success the accrued resolution bounty is paid to `resolver`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn apply_outcome<'info>(
+    market: &mut Account<'info, Market>,
+    config: &mut GlobalConfig,
+    creator_stats: Option<&mut CreatorStats>,
+    vault: &AccountInfo<'info>,
+    creator: &AccountInfo<'info>,
+    resolver: &AccountInfo<'info>,
+    outcome: Outcome,
+    source: ResolutionSource,
+) -> Result<()> {
     // Compute h-ratio based on current vault balance
     //
     //   h = min(vault_balance, total_claims) / total_claims
     //
     // This is the core Percolator invariant: if the vault can cover all claims,
     // h = 100%. Otherwise, profits are proportionally reduced.
-    let vault_balance = ctx.accounts.vault.lamports();
+
+    // Never overwrite an outcome, whatever the status says
+    require!(
+        market.outcome == Outcome::Unresolved,
+        PercolatorError::AlreadyResolved
+    );
+    // Too little on one side for meaningful payouts; cancel instead
+    require!(!market.is_side_too_thin(), PercolatorError::SideTooThin);
+
+    // A floor breached at any checkpoint is a NO, whatever the final value
+    let outcome = if market.floor_breached { Outcome::No } else { outcome };
     market.outcome = outcome;
-    market.h_ratio_bps = market.compute_h_ratio(vault_balance);
-    market.status = MarketStatus::Resolved;
+
+    // Fold the matched subsidy into the profit pool and return the rest
+    let matched = market.matched_subsidy();
+    let unused = market.creator_subsidy - matched;
+    if unused > 0 {
+        **vault.try_borrow_mut_lamports()? -= unused;
+        assert_vault_solvent(vault, Rent::get()?.minimum_balance(0))?;
+        **creator.try_borrow_mut_lamports()? += unused;
+    }
+    market.subsidy_applied = matched;
+    market.creator_subsidy = 0;
+
+    // The bounty sits in the vault but belongs to the resolver, not bettors
+    let vault_balance = vault.lamports().saturating_sub(market.resolution_bounty);
+    let h_ratio_bps = market.compute_h_ratio(vault_balance);
+
+    // A vault too drained to give winners any h can't pay capital either;
+    // cancel so every bettor refunds a pro-rata share (bounty included)
+    // instead
+    if h_ratio_bps == 0 {
+        market.outcome = Outcome::Unresolved;
+        market.creator_subsidy = matched;
+        market.subsidy_applied = 0;
+        cancel(market, config, creator_stats, crate::ID)?;
+        msg!(
+            "Market #{} auto-cancelled at resolution: vault {} lamports, h=0",
+            market.market_id,
+            vault_balance,
+        );
+        return Ok(());
+    }
+
+    let market_key = market.key();
+    market.set_h_ratio(market_key, h_ratio_bps);
+    market.transition(MarketStatus::Resolved)?;
+    market.resolved_via = source;
+    market.resolved_at = Clock::get()?.unix_timestamp;
+
+    // Below the floor, settlement waits for a top-up rather than paying less
+    market.floor_shortfall = market.floor_shortfall_for(vault_balance);
+    // A seed commitment promises the floor holds at resolution, not after a top-up
+    require!(
+        market.min_seed_ratio_bps == 0 || market.floor_shortfall == 0,
+        PercolatorError::SeededMarketBelowFloor
+    );
+    if market.floor_shortfall > 0 {
+        msg!(
+            "h={}bps below floor {}bps: settlement blocked until {} lamports are added",
+            h_ratio_bps,
+            market.h_ratio_floor_bps,
+            market.floor_shortfall,
+        );
+    }
+
+    // Nobody backed the winning side — there is nothing to settle.
+    if market.winner_pool() == 0 {
+        market.transition(MarketStatus::Settled)?;
+        CreatorStats::close_market(creator_stats, market)?;
+        config.market_closed();
+    }
+
+    let bounty = market.resolution_bounty;
+    if bounty > 0 {
+        **vault.try_borrow_mut_lamports()? -= bounty;
+        assert_vault_solvent(vault, Rent::get()?.minimum_balance(0))?;
+        **resolver.try_borrow_mut_lamports()? += bounty;
+        market.resolution_bounty = 0;
+        msg!("Resolution bounty of {} lamports paid to {}", bounty, resolver.key());
+    }
 
     msg!(
-        "Market #{} resolved: outcome={:?}, h_ratio={}bps, vault={}, yes_pool={}, no_pool={}",
+        "Market #{} resolved: outcome={:?}, h_ratio={}bps, vault={}, yes_pool={}, no_pool={}, subsidy={}",
         market.market_id,
         outcome as u8,
         market.h_ratio_bps,
         vault_balance,
         market.yes_pool,
         market.no_pool,
+        market.subsidy_applied,
     );
 
+    emit!(MarketResolved {
+        market: market_key,
+        market_id: market.market_id,
+        outcome,
+        h_ratio_bps: market.h_ratio_bps,
+        yes_share_bps: market.yes_share_bps,
+        resolved_via: source,
+    });
+
     Ok(())
 }
 
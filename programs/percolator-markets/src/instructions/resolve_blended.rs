@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::instructions::resolve::{apply_outcome, void_if_parent_failed, ResolveMarket};
+use crate::state::*;
+
+/// Resolve a binary market as partially true: YES holders receive
+/// `yes_weight_bps` of the total pool and NO holders the rest.
+///
+/// Uses the same accounts and oracle checks as `resolve`. The blend settles
+/// through the scalar payout path, so `yes_share_bps` carries the weight.
+pub fn handler(ctx: Context<ResolveMarket>, yes_weight_bps: u16) -> Result<()> {
+    require!(yes_weight_bps <= 10_000, PercolatorError::InvalidBasisPoints);
+    // Scalar markets resolve to a value with `resolve_scalar`
+    require!(
+        !matches!(ctx.accounts.market.rule, MarketRule::Scalar { .. }),
+        PercolatorError::InvalidMarketRule
+    );
+    // A blend moves stake between sides, which a no-loss market never does
+    require!(
+        ctx.accounts.market.kind == MarketKind::Standard,
+        PercolatorError::InvalidOutcome
+    );
+
+    let clock = Clock::get()?;
+    let active_oracle = ctx.accounts.market.active_oracle(
+        ctx.accounts.oracle_schedule.as_deref(),
+        clock.unix_timestamp,
+    )?;
+    require!(
+        ctx.accounts.oracle.key() == active_oracle,
+        PercolatorError::UnauthorizedOracle
+    );
+    ctx.accounts.market.check_resolution_delay(&ctx.accounts.config, clock.unix_timestamp)?;
+    ctx.accounts.market.check_bet_slot(clock.slot)?;
+
+    if void_if_parent_failed(
+        &mut ctx.accounts.market,
+        ctx.accounts.parent_market.as_ref(),
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        ctx.accounts.oracle.key(),
+    )? {
+        return Ok(());
+    }
+
+    let market = &mut ctx.accounts.market;
+    market.scalar_value = 0;
+    market.yes_share_bps = yes_weight_bps;
+
+    msg!(
+        "Blended resolution: yes_weight={}bps, no_weight={}bps",
+        yes_weight_bps,
+        10_000 - yes_weight_bps,
+    );
+
+    apply_outcome(
+        market,
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.oracle.to_account_info(),
+        Outcome::Scalar,
+        ResolutionSource::ManualOracle,
+    )
+}
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::instructions::cancel::cancel;
+use crate::instructions::resolve::{apply_outcome, void_if_parent_failed};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ResolveDefault<'info> {
+    /// Anyone — finalization is permissionless once the window ends.
+    /// Receives the market's resolution bounty.
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// The market the oracle left unresolved.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open || market.status == MarketStatus::Closed
+            @ PercolatorError::AlreadyResolved,
+        constraint = !market.frozen @ PercolatorError::MarketFrozen,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Market vault — read balance for h-ratio computation; refunds unused subsidy.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Market creator — receives any unused subsidy.
+    /// CHECK: Validated against `market.creator`.
+    #[account(
+        mut,
+        address = market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: UncheckedAccount<'info>,
+
+    /// Creator stats — frees an active-market slot once the market closes.
+    /// Omitted only for markets created before creator stats existed.
+    #[account(
+        mut,
+        seeds = [b"creator_stats", market.creator.as_ref()],
+        bump = creator_stats.bump,
+    )]
+    pub creator_stats: Option<Account<'info, CreatorStats>>,
+
+    /// Global config — counts markets reaching a terminal state.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// Parent market — required when `market.parent_market` is set.
+    #[account(
+        constraint = Some(parent_market.key()) == market.parent_market
+            @ PercolatorError::InvalidParentMarket,
+    )]
+    pub parent_market: Option<Account<'info, Market>>,
+}
+
+pub fn handler(ctx: Context<ResolveDefault>) -> Result<()> {
+    let clock = Clock::get()?;
+    // An unmigrated legacy market reads its window end as 0
+    require!(ctx.accounts.market.version != 0, PercolatorError::MarketNotMigrated);
+    require!(
+        clock.unix_timestamp >= ctx.accounts.market.resolution_window_end,
+        PercolatorError::ResolutionWindowOpen
+    );
+
+    if void_if_parent_failed(
+        &mut ctx.accounts.market,
+        ctx.accounts.parent_market.as_ref(),
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        ctx.accounts.caller.key(),
+    )? {
+        return Ok(());
+    }
+
+    let default_outcome = ctx.accounts.market.default_outcome;
+    msg!(
+        "Market #{} unresolved after window; applying default {:?}",
+        ctx.accounts.market.market_id,
+        default_outcome as u8,
+    );
+
+    // No default configured, or a side too thin to resolve — void the
+    // market so bettors can be refunded
+    if default_outcome == Outcome::Unresolved || ctx.accounts.market.is_side_too_thin() {
+        return cancel(
+            &mut ctx.accounts.market,
+            &mut ctx.accounts.config,
+            ctx.accounts.creator_stats.as_deref_mut(),
+            ctx.accounts.caller.key(),
+        );
+    }
+
+    apply_outcome(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.caller.to_account_info(),
+        default_outcome,
+        ResolutionSource::Default,
+    )
+}
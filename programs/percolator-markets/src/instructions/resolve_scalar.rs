@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::instructions::resolve::{apply_outcome, void_if_parent_failed, ResolveMarket};
+use crate::state::*;
+
+/// Resolve a `Scalar` market to the oracle-reported `value`.
+///
+/// Uses the same accounts and oracle checks as `resolve`; the YES share is
+/// derived from `value` and the rule's bounds rather than a verdict.
+pub fn handler(ctx: Context<ResolveMarket>, value: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    let active_oracle = ctx.accounts.market.active_oracle(
+        ctx.accounts.oracle_schedule.as_deref(),
+        clock.unix_timestamp,
+    )?;
+    require!(
+        ctx.accounts.oracle.key() == active_oracle,
+        PercolatorError::UnauthorizedOracle
+    );
+    ctx.accounts.market.check_resolution_delay(&ctx.accounts.config, clock.unix_timestamp)?;
+    ctx.accounts.market.check_bet_slot(clock.slot)?;
+
+    if void_if_parent_failed(
+        &mut ctx.accounts.market,
+        ctx.accounts.parent_market.as_ref(),
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        ctx.accounts.oracle.key(),
+    )? {
+        return Ok(());
+    }
+
+    let market = &mut ctx.accounts.market;
+    let yes_share_bps = market
+        .rule
+        .scalar_share_bps(value)
+        .ok_or(PercolatorError::InvalidMarketRule)?;
+
+    market.scalar_value = value;
+    market.yes_share_bps = yes_share_bps;
+
+    msg!(
+        "Scalar value={} -> yes_share={}bps",
+        value,
+        yes_share_bps,
+    );
+
+    apply_outcome(
+        market,
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.oracle.to_account_info(),
+        Outcome::Scalar,
+        ResolutionSource::ManualOracle,
+    )
+}
@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::instructions::resolve::{apply_outcome, void_if_parent_failed};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ResolveWithRule<'info> {
+    /// Oracle authority — submits the resolution but does not pick the outcome.
+    /// Receives the market's resolution bounty.
+    #[account(
+        mut,
+        constraint = oracle.key() == market.oracle @ PercolatorError::UnauthorizedOracle,
+    )]
+    pub oracle: Signer<'info>,
+
+    /// Global config — minimum resolution delay; counts terminal markets.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// The market to resolve.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open || market.status == MarketStatus::Closed
+            @ PercolatorError::AlreadyResolved,
+        constraint = !market.frozen @ PercolatorError::MarketFrozen,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// The oracle's reading for this market's token.
+    #[account(
+        seeds = [b"oracle_reading", market.oracle.as_ref(), market.token_mint.as_ref()],
+        bump = reading.bump,
+    )]
+    pub reading: Account<'info, OracleReading>,
+
+    /// The secondary oracle's reading — required when `market.secondary_oracle` is set.
+    #[account(
+        constraint = Some(secondary_reading.oracle) == market.secondary_oracle
+            @ PercolatorError::MissingSecondaryReading,
+        constraint = secondary_reading.token_mint == market.token_mint
+            @ PercolatorError::MissingSecondaryReading,
+    )]
+    pub secondary_reading: Option<Account<'info, OracleReading>>,
+
+    /// Market vault — read balance for h-ratio computation; refunds unused subsidy.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Market creator — receives any unused subsidy.
+    /// CHECK: Validated against `market.creator`.
+    #[account(
+        mut,
+        address = market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: UncheckedAccount<'info>,
+
+    /// Creator stats — frees an active-market slot once the market closes.
+    /// Omitted only for markets created before creator stats existed.
+    #[account(
+        mut,
+        seeds = [b"creator_stats", market.creator.as_ref()],
+        bump = creator_stats.bump,
+    )]
+    pub creator_stats: Option<Account<'info, CreatorStats>>,
+
+    /// Parent market — required when `market.parent_market` is set.
+    #[account(
+        constraint = Some(parent_market.key()) == market.parent_market
+            @ PercolatorError::InvalidParentMarket,
+    )]
+    pub parent_market: Option<Account<'info, Market>>,
+}
+
+pub fn handler(ctx: Context<ResolveWithRule>) -> Result<()> {
+    if void_if_parent_failed(
+        &mut ctx.accounts.market,
+        ctx.accounts.parent_market.as_ref(),
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        ctx.accounts.oracle.key(),
+    )? {
+        return Ok(());
+    }
+
+    ctx.accounts.market.check_heartbeat(&ctx.accounts.config, Clock::get()?.unix_timestamp)?;
+
+    let Some(outcome) = rule_outcome(
+        &mut ctx.accounts.market,
+        &ctx.accounts.config,
+        &ctx.accounts.reading,
+        ctx.accounts.secondary_reading.as_deref(),
+    )? else {
+        return Ok(());
+    };
+
+    apply_outcome(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.oracle.to_account_info(),
+        outcome,
+        ResolutionSource::OracleRule,
+    )
+}
+
+/// Evaluate the market's rule against `reading` (and the secondary reading,
+/// if the market has a second oracle), as of the deadline.
+///
+/// Returns `None`, with `oracle_disagreement` set, when the two readings
+/// differ by more than the tolerance. Shared by `resolve_with_rule` and
+/// `trigger_resolution`.
+pub(crate) fn rule_outcome(
+    market: &mut Account<Market>,
+    config: &GlobalConfig,
+    reading: &OracleReading,
+    secondary_reading: Option<&OracleReading>,
+) -> Result<Option<Outcome>> {
+    // Rules are judged at the deadline, so the data must be from then, or
+    // at most the configured staleness later
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp >= market.deadline,
+        PercolatorError::MarketNotExpired
+    );
+    config.check_reading_time(reading.published_at, market.deadline)?;
+    market.check_resolution_delay(config, clock.unix_timestamp)?;
+    market.check_bet_slot(clock.slot)?;
+
+    // The program, not the oracle, compares the data against the target
+    let observed = reading
+        .observed_value(market.rule, market.price_exponent)
+        .ok_or(PercolatorError::InvalidOutcome)?;
+
+    // With a secondary feed, both must agree before anything resolves
+    if market.secondary_oracle.is_some() {
+        let secondary = secondary_reading.ok_or(PercolatorError::MissingSecondaryReading)?;
+        config.check_reading_time(secondary.published_at, market.deadline)?;
+        let secondary_observed = secondary
+            .observed_value(market.rule, market.price_exponent)
+            .ok_or(PercolatorError::InvalidOutcome)?;
+
+        let agree = market.readings_agree(observed, secondary_observed);
+        market.oracle_disagreement = !agree;
+        if !agree {
+            msg!(
+                "Oracle disagreement: primary={} secondary={} tolerance={}bps; market stays unresolved",
+                observed,
+                secondary_observed,
+                market.resolution_tolerance_bps,
+            );
+            return Ok(None);
+        }
+    }
+
+    let outcome = market
+        .rule
+        .evaluate(observed, market.target_value)
+        .ok_or(PercolatorError::InvalidOutcome)?;
+
+    msg!(
+        "Rule check: observed={} target={} -> outcome={:?}",
+        observed,
+        market.target_value,
+        outcome as u8,
+    );
+
+    Ok(Some(outcome))
+}
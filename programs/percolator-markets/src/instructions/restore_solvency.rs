@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RestoreSolvency<'info> {
+    /// Anyone topping up the vault — typically the creator or an operator.
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// The resolved market, with settlements still outstanding.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Resolved @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Market vault — receives the top-up.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RestoreSolvency>, amount: u64) -> Result<()> {
+    require!(amount > 0, PercolatorError::ZeroAmount);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.funder.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    // Back to 100% only once every remaining winner can be paid in full;
+    // a partial top-up just shrinks the shortfall
+    let vault_balance = ctx.accounts.vault.lamports();
+    let market = &mut ctx.accounts.market;
+    let outstanding = market.outstanding_claims()?;
+    if market.floor_shortfall > 0 {
+        // Settlement is blocked, so nothing has been paid yet and h can be
+        // recomputed from scratch
+        let h_ratio_bps = market.compute_h_ratio(vault_balance);
+        let market_key = market.key();
+        market.set_h_ratio(market_key, h_ratio_bps);
+        market.floor_shortfall = market.floor_shortfall_for(vault_balance);
+    } else if vault_balance >= outstanding && market.h_ratio_bps < 10_000 {
+        let market_key = market.key();
+        market.set_h_ratio(market_key, 10_000);
+    }
+
+    msg!(
+        "Vault topped up: {} lamports for market #{} (vault={}, outstanding={}, h={}bps)",
+        amount,
+        market.market_id,
+        vault_balance,
+        outstanding,
+        market.h_ratio_bps,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::errors::PercolatorError;
+use crate::instructions::place_bet::{mint_position_tokens, net_stake, record_bet};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RevealBet<'info> {
+    /// The bettor revealing their commitment.
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    /// The commit-reveal market.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open @ PercolatorError::InvalidMarketStatus,
+        constraint = !market.frozen @ PercolatorError::MarketFrozen,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Commitment being revealed — closed to the bettor on success.
+    #[account(
+        mut,
+        close = bettor,
+        seeds = [b"commitment", market.key().as_ref(), bettor.key().as_ref()],
+        bump = commitment.bump,
+    )]
+    pub commitment: Account<'info, BetCommitment>,
+
+    /// User position PDA — created on first bet, updated on subsequent bets.
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = UserPosition::SIZE,
+        seeds = [b"position", market.key().as_ref(), bettor.key().as_ref()],
+        bump,
+    )]
+    pub position: Account<'info, UserPosition>,
+
+    /// Market vault — receives the stake.
+    /// CHECK: Validated by seeds constraint.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// YES token mint (market is authority).
+    #[account(
+        mut,
+        seeds = [b"yes_mint", market.key().as_ref()],
+        bump,
+    )]
+    pub yes_mint: Account<'info, Mint>,
+
+    /// NO token mint (market is authority).
+    #[account(
+        mut,
+        seeds = [b"no_mint", market.key().as_ref()],
+        bump,
+    )]
+    pub no_mint: Account<'info, Mint>,
+
+    /// Bettor's token account for the revealed side.
+    #[account(mut)]
+    pub bettor_token_account: Account<'info, TokenAccount>,
+
+    /// Global config for volume tracking and fee settings.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// Protocol treasury — receives the protocol share of the fee.
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Creator revenue PDA — accrues the creator share of the fee until
+    /// `withdraw_creator_revenue`.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"creator_revenue", market.key().as_ref()],
+        bump,
+    )]
+    pub creator_revenue: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<RevealBet>, side: BetSide, nonce: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    let market = &ctx.accounts.market;
+    require!(
+        clock.unix_timestamp >= market.commit_deadline
+            && clock.unix_timestamp < market.deadline,
+        PercolatorError::RevealPhaseNotOpen
+    );
+
+    let amount = ctx.accounts.commitment.amount;
+    require!(
+        BetCommitment::hash_bet(side, amount, &nonce) == ctx.accounts.commitment.commitment,
+        PercolatorError::CommitmentMismatch
+    );
+
+    // Fee and resolution bounty come off the top; only the net stake
+    // enters the pool
+    let (protocol_fee, creator_fee) = ctx.accounts.config.split_fee(amount);
+    let bounty = market.bounty_share(amount);
+    let stake = net_stake(amount, protocol_fee, creator_fee, bounty)?;
+
+    // Release the escrow from the commitment PDA; its rent goes back to the
+    // bettor when the account is closed
+    **ctx.accounts.commitment.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? += stake + bounty;
+    **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += protocol_fee;
+    **ctx.accounts.creator_revenue.to_account_info().try_borrow_mut_lamports()? += creator_fee;
+
+    let mint = match side {
+        BetSide::Yes => ctx.accounts.yes_mint.to_account_info(),
+        BetSide::No => ctx.accounts.no_mint.to_account_info(),
+    };
+
+    mint_position_tokens(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.market,
+        mint,
+        ctx.accounts.bettor_token_account.to_account_info(),
+        stake,
+    )?;
+
+    record_bet(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.position,
+        ctx.bumps.position,
+        ctx.accounts.bettor.key(),
+        side,
+        stake,
+        creator_fee,
+    )?;
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.total_collected = treasury.total_collected.checked_add(protocol_fee)
+        .ok_or(PercolatorError::Overflow)?;
+    let market = &mut ctx.accounts.market;
+    market.creator_revenue = market.creator_revenue.checked_add(creator_fee)
+        .ok_or(PercolatorError::Overflow)?;
+    market.resolution_bounty = market.resolution_bounty.checked_add(bounty)
+        .ok_or(PercolatorError::Overflow)?;
+
+    ctx.accounts.config.record_volume(amount, clock.unix_timestamp)?;
+
+    msg!(
+        "Bet revealed: {} lamports on {:?} for market #{}",
+        stake,
+        side as u8,
+        ctx.accounts.market.market_id,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct SetCreatorBan<'info> {
+    /// Protocol authority — pays for the ban entry on first use.
+    #[account(
+        mut,
+        constraint = authority.key() == config.authority @ PercolatorError::UnauthorizedAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global config — identifies the protocol authority.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// Ban entry PDA for `creator`.
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = BannedCreator::SIZE,
+        seeds = [b"banned", creator.as_ref()],
+        bump,
+    )]
+    pub banned_creator: Account<'info, BannedCreator>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetCreatorBan>, creator: Pubkey, banned: bool) -> Result<()> {
+    let clock = Clock::get()?;
+    let entry = &mut ctx.accounts.banned_creator;
+
+    entry.creator = creator;
+    entry.banned = banned;
+    entry.updated_at = clock.unix_timestamp;
+    entry.bump = ctx.bumps.banned_creator;
+
+    msg!(
+        "Creator {} {}",
+        creator,
+        if banned { "banned" } else { "unbanned" },
+    );
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct SetMarketFrozen<'info> {
+    /// Protocol authority.
+    #[account(
+        constraint = authority.key() == config.authority @ PercolatorError::UnauthorizedAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global config — identifies the protocol authority.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// The market to freeze or unfreeze.
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+}
+
+pub fn handler(ctx: Context<SetMarketFrozen>, frozen: bool) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    market.frozen = frozen;
+
+    msg!(
+        "Market #{} {} by {}",
+        market.market_id,
+        if frozen { "frozen" } else { "unfrozen" },
+        ctx.accounts.authority.key(),
+    );
+
+    Ok(())
+}
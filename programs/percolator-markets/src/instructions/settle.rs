@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
 
 use crate::errors::PercolatorError;
+use crate::events::SettlementProgress;
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -35,21 +37,205 @@ pub struct Settle<'info> {
     )]
     pub vault: SystemAccount<'info>,
 
+    /// Creator stats — frees an active-market slot once the market closes.
+    /// Omitted only for markets created before creator stats existed.
+    #[account(
+        mut,
+        seeds = [b"creator_stats", market.creator.as_ref()],
+        bump = creator_stats.bump,
+    )]
+    pub creator_stats: Option<Account<'info, CreatorStats>>,
+
+    /// Global config — counts markets reaching a terminal state.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// Protocol treasury — required once a late-settlement penalty is due.
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    /// Settlement receipt — required exactly when `market.settlement_receipts`.
+    #[account(
+        init,
+        payer = user,
+        space = SettlementReceipt::SIZE,
+        seeds = [b"settlement_receipt", market.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub receipt: Option<Account<'info, SettlementReceipt>>,
+
+    /// Position NFT mint — required exactly when `market.position_nfts`.
+    #[account(
+        mut,
+        seeds = [b"position_nft", position.key().as_ref()],
+        bump,
+    )]
+    pub position_nft: Option<Box<Account<'info, Mint>>>,
+
+    /// User's token account holding the position NFT.
+    #[account(
+        mut,
+        token::mint = position_nft,
+        token::authority = user,
+    )]
+    pub position_nft_account: Option<Box<Account<'info, TokenAccount>>>,
+
     pub system_program: Program<'info, System>,
+    pub token_program: Option<Program<'info, Token>>,
 }
 
-pub fn handler(ctx: Context<Settle>) -> Result<()> {
-    let market = &ctx.accounts.market;
-    let position = &ctx.accounts.position;
+pub fn handler(ctx: Context<Settle>, settle_order: Option<u64>) -> Result<PayoutBreakdown> {
+    let user = ctx.accounts.user.to_account_info();
+    let vault = ctx.accounts.vault.to_account_info();
 
-    // Determine if the user is on the winning side
-    let is_winner = match (market.outcome, position.side) {
-        (Outcome::Yes, BetSide::Yes) => true,
-        (Outcome::No, BetSide::No) => true,
-        _ => false,
+    burn_position_nft(
+        &ctx.accounts.market,
+        ctx.accounts.position_nft.as_deref(),
+        ctx.accounts.position_nft_account.as_deref(),
+        ctx.accounts.token_program.as_ref(),
+        &user,
+    )?;
+
+    let breakdown = settle_position(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        &mut ctx.accounts.position,
+        &vault,
+        &user,
+        None,
+        ctx.accounts.treasury.as_mut(),
+        settle_order,
+    )?;
+
+    issue_receipt(
+        &ctx.accounts.market,
+        ctx.accounts.receipt.as_mut(),
+        ctx.bumps.receipt,
+        user.key(),
+        breakdown.total,
+    )?;
+
+    // Returned to the caller as return data
+    Ok(breakdown)
+}
+
+/// Burn the position NFT held by `user`.
+///
+/// Fails with `PositionNftMismatch` unless the NFT accounts were passed
+/// exactly when the market mints position NFTs.
+pub(crate) fn burn_position_nft<'info>(
+    market: &Market,
+    mint: Option<&Account<'info, Mint>>,
+    from: Option<&Account<'info, TokenAccount>>,
+    token_program: Option<&Program<'info, Token>>,
+    user: &AccountInfo<'info>,
+) -> Result<()> {
+    let accounts = match (mint, from, token_program) {
+        (Some(mint), Some(from), Some(token_program)) => Some((mint, from, token_program)),
+        (None, None, _) => None,
+        _ => return err!(PercolatorError::PositionNftMismatch),
+    };
+    require!(
+        accounts.is_some() == market.position_nfts,
+        PercolatorError::PositionNftMismatch
+    );
+    let Some((mint, from, token_program)) = accounts else {
+        return Ok(());
+    };
+
+    token::burn(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Burn {
+                mint: mint.to_account_info(),
+                from: from.to_account_info(),
+                authority: user.clone(),
+            },
+        ),
+        1,
+    )
+}
+
+/// Fill in the settlement receipt created for this settlement.
+///
+/// Fails with `SettlementReceiptMismatch` unless a receipt was passed
+/// exactly when the market issues them.
+pub(crate) fn issue_receipt(
+    market: &Account<Market>,
+    receipt: Option<&mut Account<SettlementReceipt>>,
+    bump: Option<u8>,
+    user: Pubkey,
+    payout: u64,
+) -> Result<()> {
+    require!(
+        receipt.is_some() == market.settlement_receipts,
+        PercolatorError::SettlementReceiptMismatch
+    );
+    let (Some(receipt), Some(bump)) = (receipt, bump) else {
+        return Ok(());
     };
 
-    require!(is_winner, PercolatorError::LosingSide);
+    receipt.market = market.key();
+    receipt.user = user;
+    receipt.payout = payout;
+    receipt.settled_at = Clock::get()?.unix_timestamp;
+    receipt.bump = bump;
+
+    msg!("Settlement receipt issued to {} for market #{}", user, market.market_id);
+    Ok(())
+}
+
+/// Pay out a winning position and update market settlement tracking.
+///
+/// `crank` is the cranker account and its fee when settling on the
+/// position owner's behalf; the fee comes out of the profit claim only.
+/// `treasury` receives any late-settlement penalty.
+///
+/// `settle_order`, if given, must equal `market.settlements_count`. A settle
+/// built against a snapshot that another settle has since changed fails with
+/// `StaleSettleOrder` instead of reading a different h than the caller saw.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn settle_position<'info>(
+    market: &mut Account<'info, Market>,
+    config: &mut GlobalConfig,
+    creator_stats: Option<&mut CreatorStats>,
+    position: &mut Account<'info, UserPosition>,
+    vault: &AccountInfo<'info>,
+    user: &AccountInfo<'info>,
+    crank: Option<(&AccountInfo<'info>, u64)>,
+    treasury: Option<&mut Account<'info, Treasury>>,
+    settle_order: Option<u64>,
+) -> Result<PayoutBreakdown> {
+    require!(!market.merkle_settlement, PercolatorError::MerkleSettlementActive);
+    require!(market.floor_shortfall == 0, PercolatorError::HRatioBelowFloor);
+
+    if let Some(order) = settle_order {
+        require!(
+            order == market.settlements_count,
+            PercolatorError::StaleSettleOrder
+        );
+    }
+
+    // Determine if the user is on the winning side
+    require!(
+        market.is_winning_side(position.side),
+        PercolatorError::LosingSide
+    );
+
+    // Accounting invariant: never settle more positions than won
+    require!(
+        !market.tracks_positions() || market.settlements_count < market.winning_positions() as u64,
+        PercolatorError::SettlementOverflow
+    );
 
     // ────────────────────────────────────────────────────────────
     // Percolator Two-Claim Settlement
@@ -77,47 +263,109 @@ pub fn handler(ctx: Context<Settle>) -> Result<()> {
     // others not. This is the core insolvency-safety guarantee from
     // the Percolator risk engine.
     //
+    // In `Haircut` mode a short vault lowers h on the spot instead of
+    // reverting, so later settlers share the shortfall.
+    //
+    // A crank fee or late-settlement penalty, if any, is taken from the
+    // profit claim only, and an early-settlement bonus only adds profit
+    // out of vault surplus, so capital is never reduced.
+    //
     // Invariant: settled_amount <= vault_balance (always)
     // ────────────────────────────────────────────────────────────
 
-    let payout = market.calculate_payout(position.deposited);
+    let profit_claim = u64::try_from(market.position_profit_claim(position)?)
+        .map_err(|_| PercolatorError::Overflow)?;
+
+    // Safety check: ensure vault has enough, or haircut to what it holds
+    let (mut breakdown, h) = market.settlement_breakdown(position, vault.lamports())?;
+    if h < market.h_ratio_bps {
+        let market_key = market.key();
+        market.set_h_ratio(market_key, h);
+    }
+    let payout = breakdown.total;
 
-    // Safety check: ensure vault has enough
-    let vault_balance = ctx.accounts.vault.lamports();
-    require!(payout <= vault_balance, PercolatorError::VaultInsolvency);
+    // Settlement incentive, timed from resolution
+    let rent_min = Rent::get()?.minimum_balance(0);
+    let elapsed = Clock::get()?.unix_timestamp.saturating_sub(market.resolved_at);
+    let (bonus, penalty) = config.settlement_incentive(breakdown.profit, elapsed);
+    let bonus = bonus.min(market.incentive_surplus(vault.lamports(), rent_min)?);
+    breakdown.profit = breakdown.profit + bonus - penalty;
+    breakdown.fee = penalty;
+    breakdown.total = breakdown.total + bonus - penalty;
 
-    // Transfer payout from vault PDA to user
-    let market_key = ctx.accounts.market.key();
-    let vault_seeds: &[&[u8]] = &[
-        b"vault",
-        market_key.as_ref(),
-        &[market.vault_bump],
-    ];
+    let crank_fee = match crank {
+        Some((_, fee)) => fee.min(breakdown.profit),
+        None => 0,
+    };
+    breakdown.fee += crank_fee;
+    breakdown.profit -= crank_fee;
+    breakdown.total -= crank_fee;
 
     // Direct lamport transfer from PDA
-    **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= payout;
-    **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += payout;
+    **vault.try_borrow_mut_lamports()? -= payout + bonus;
+    assert_vault_solvent(vault, rent_min)?;
+    **user.try_borrow_mut_lamports()? += breakdown.total;
+    if let Some((cranker, _)) = crank {
+        **cranker.try_borrow_mut_lamports()? += crank_fee;
+    }
+    if penalty > 0 {
+        let treasury = treasury.ok_or(PercolatorError::TreasuryRequired)?;
+        **treasury.to_account_info().try_borrow_mut_lamports()? += penalty;
+        treasury.total_collected = treasury.total_collected.checked_add(penalty)
+            .ok_or(PercolatorError::Overflow)?;
+    }
 
     // Update position
-    let position = &mut ctx.accounts.position;
     position.settled = true;
-    position.payout = payout;
+    position.payout = breakdown.total;
+    position.settle_order = market.settlements_count;
 
     // Update market settlement tracking
-    let market = &mut ctx.accounts.market;
-    market.settled_amount = market.settled_amount.checked_add(payout)
+    market.settled_amount = market.settled_amount.checked_add(payout + bonus)
+        .ok_or(PercolatorError::Overflow)?;
+    market.settlement_bonuses = market.settlement_bonuses.checked_add(bonus)
         .ok_or(PercolatorError::Overflow)?;
     market.settlements_count = market.settlements_count.checked_add(1)
         .ok_or(PercolatorError::Overflow)?;
+    market.settled_stake = market.settled_stake.checked_add(position.deposited)
+        .ok_or(PercolatorError::Overflow)?;
+    market.settled_bonus_stake = market.settled_bonus_stake.checked_add(position.bonus_stake)
+        .ok_or(PercolatorError::Overflow)?;
+    market.settled_weighted_stake = market.settled_weighted_stake
+        .checked_add(position.weighted_stake)
+        .ok_or(PercolatorError::Overflow)?;
+    market.crank_fees_paid = market.crank_fees_paid.checked_add(crank_fee)
+        .ok_or(PercolatorError::Overflow)?;
+    market.settled_profit_claim = market.settled_profit_claim.checked_add(profit_claim)
+        .ok_or(PercolatorError::Overflow)?;
+
+    // Every winning stake has been paid out (reconcile slack has no owner).
+    if market.settled_stake.saturating_add(market.winner_reconcile_slack()) >= market.winner_pool() {
+        market.transition(MarketStatus::Settled)?;
+        CreatorStats::close_market(creator_stats, market)?;
+        config.market_closed();
+    }
+
+    emit!(SettlementProgress {
+        market: market.key(),
+        market_id: market.market_id,
+        settlements_count: market.settlements_count,
+        winning_positions: market.winning_positions(),
+        settled_amount: market.settled_amount,
+        vault_balance: vault.lamports(),
+        market_settled: market.status == MarketStatus::Settled,
+    });
 
     msg!(
-        "Settled: user={} payout={} (capital={} + profit×h), market #{}",
-        ctx.accounts.user.key(),
-        payout,
+        "Settled: user={} payout={} (capital={} + profit×h, crank_fee={}, bonus={}, penalty={}), market #{}",
+        user.key(),
+        breakdown.total,
         position.deposited,
+        crank_fee,
+        bonus,
+        penalty,
         market.market_id,
     );
 
-    Ok(())
+    Ok(breakdown)
 }
-
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::instructions::settle::settle_position;
+use crate::state::*;
+
+/// Most positions one `settle_many` call may settle (compute budget).
+pub const MAX_SETTLE_MANY: usize = 6;
+
+#[derive(Accounts)]
+pub struct SettleMany<'info> {
+    /// The user claiming their payouts.
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Global config — counts markets reaching a terminal state.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// Protocol treasury — required once a late-settlement penalty is due.
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Settle the user's winning positions passed in `remaining_accounts` as
+/// `(market, position, vault, creator_stats)` groups, all writable. For a
+/// market from before creator stats, `creator_stats` is its uncreated PDA.
+/// The position and stats keys must be the PDAs `settle` derives.
+///
+/// Each group is checked as `settle` would check it and settled the same
+/// way. Returns the total paid out.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, SettleMany<'info>>) -> Result<u64> {
+    let accounts = ctx.remaining_accounts;
+    require!(
+        !accounts.is_empty()
+            && accounts.len().is_multiple_of(4)
+            && accounts.len() / 4 <= MAX_SETTLE_MANY,
+        PercolatorError::InvalidBatchSize
+    );
+
+    let user = ctx.accounts.user.to_account_info();
+    let mut total: u64 = 0;
+    for group in accounts.chunks(4) {
+        let mut market = Account::<Market>::try_from(&group[0])?;
+        let mut position = Account::<UserPosition>::try_from(&group[1])?;
+        let vault = &group[2];
+        // Markets from before creator stats pass their never-created PDA,
+        // so the key must be checked before an empty account counts as one
+        let (stats_key, _) = Pubkey::find_program_address(
+            &[b"creator_stats", market.creator.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(group[3].key(), stats_key, ErrorCode::ConstraintSeeds);
+        let mut creator_stats = if group[3].data_is_empty() {
+            None
+        } else {
+            Some(Account::<CreatorStats>::try_from(&group[3])?)
+        };
+
+        require!(
+            market.status == MarketStatus::Resolved,
+            PercolatorError::InvalidMarketStatus
+        );
+        // Receipts need their own init account; settle such positions singly
+        require!(!market.settlement_receipts, PercolatorError::SettlementReceiptMismatch);
+        // Position NFTs are burned one at a time by `settle`
+        require!(!market.position_nfts, PercolatorError::PositionNftsUnsupported);
+        require_keys_eq!(position.market, market.key(), PercolatorError::NoPosition);
+        require_keys_eq!(position.user, user.key(), PercolatorError::NoPosition);
+        let position_key = Pubkey::create_program_address(
+            &[b"position", market.key().as_ref(), user.key().as_ref(), &[position.bump]],
+            ctx.program_id,
+        )
+        .map_err(|_| ErrorCode::ConstraintSeeds)?;
+        require_keys_eq!(position.key(), position_key, ErrorCode::ConstraintSeeds);
+        require!(!position.settled, PercolatorError::AlreadySettled);
+        require_keys_eq!(vault.key(), market.vault, PercolatorError::NoPosition);
+
+        let breakdown = settle_position(
+            &mut market,
+            &mut ctx.accounts.config,
+            creator_stats.as_deref_mut(),
+            &mut position,
+            vault,
+            &user,
+            None,
+            ctx.accounts.treasury.as_mut(),
+            None,
+        )?;
+        total = total.checked_add(breakdown.total)
+            .ok_or(PercolatorError::Overflow)?;
+
+        // Persist now; a repeated group then fails as already settled
+        market.exit(ctx.program_id)?;
+        position.exit(ctx.program_id)?;
+        if let Some(creator_stats) = &creator_stats {
+            creator_stats.exit(ctx.program_id)?;
+        }
+    }
+
+    msg!("Settled {} positions for {}: {} lamports", accounts.len() / 4, user.key(), total);
+
+    Ok(total)
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::instructions::create_market::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct SpawnHedgeMarket<'info> {
+    /// Every account `create_market` needs.
+    pub create: CreateMarket<'info>,
+
+    /// The open market to hedge — must belong to the same creator.
+    #[account(
+        constraint = hedged.creator == create.creator.key() @ PercolatorError::UnauthorizedCreator,
+        constraint = hedged.status == MarketStatus::Open @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub hedged: Account<'info, Market>,
+}
+
+pub fn handler(ctx: Context<SpawnHedgeMarket>, question: String) -> Result<()> {
+    let hedged = &ctx.accounts.hedged;
+    let rule = hedged.rule.inverse().ok_or(PercolatorError::InvalidMarketRule)?;
+    let hedged_key = hedged.key();
+
+    let params = CreateMarketParams {
+        question,
+        rule,
+        target_value: hedged.target_value,
+        token_mint: hedged.token_mint,
+        oracle: hedged.oracle,
+        deadline: hedged.deadline,
+        subsidy_match_bps: 0,
+        creator_subsidy: 0,
+        commit_deadline: 0,
+        insolvency_mode: hedged.insolvency_mode,
+        default_outcome: Outcome::Unresolved,
+        resolution_window_secs: hedged.resolution_window_end.saturating_sub(hedged.deadline),
+        creator_betting_allowed: !hedged.creator_betting_disabled,
+        oracle_betting_allowed: !hedged.oracle_betting_disabled,
+        question_hash: [0; 32],
+        question_uri: String::new(),
+        parent_market: None,
+        parent_required_outcome: Outcome::Unresolved,
+        early_bird_bonus_bps: 0,
+        early_bird_window_secs: 0,
+        h_ratio_floor_bps: 0,
+        time_weighted: false,
+        kind: MarketKind::Standard,
+        max_imbalance_bps: 10_000,
+        secondary_oracle: hedged.secondary_oracle,
+        resolution_tolerance_bps: hedged.resolution_tolerance_bps,
+        min_seed_ratio_bps: 0,
+        expected_max_pool: 0,
+        claim_deadline: hedged.claim_deadline,
+        price_exponent: hedged.price_exponent,
+        refund_by_token_balance: hedged.refund_by_token_balance,
+        min_pool_per_side: hedged.min_pool_per_side,
+        resolution_bounty_bps: hedged.resolution_bounty_bps,
+        resolve_at_deadline: hedged.resolve_at_deadline,
+        settlement_receipts: hedged.settlement_receipts,
+        position_nfts: hedged.position_nfts,
+    };
+
+    msg!("Spawning hedge of market #{}", hedged.market_id);
+
+    create(&mut ctx.accounts.create, &ctx.bumps.create, params)?;
+    ctx.accounts.create.market.hedge_of = Some(hedged_key);
+
+    Ok(())
+}
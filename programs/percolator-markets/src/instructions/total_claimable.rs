@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct TotalClaimable<'info> {
+    /// The user whose positions are summed.
+    /// CHECK: Read-only; only compared against `position.user`.
+    pub user: UncheckedAccount<'info>,
+}
+
+/// Sum what `user` could claim right now across the positions passed in
+/// `remaining_accounts` as `(market, vault, position)` triples.
+///
+/// Winning unsettled positions in Resolved markets count their settle payout;
+/// unrefunded positions in Cancelled markets count their refund. Markets
+/// settling through a Merkle root are skipped, since their payouts are only
+/// known off-chain.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, TotalClaimable<'info>>) -> Result<u64> {
+    let user = ctx.accounts.user.key();
+    let accounts = ctx.remaining_accounts;
+    require!(accounts.len().is_multiple_of(3), PercolatorError::NoPosition);
+
+    let mut total: u64 = 0;
+    for triple in accounts.chunks(3) {
+        let market = Account::<Market>::try_from(&triple[0])?;
+        let vault = &triple[1];
+        let position = Account::<UserPosition>::try_from(&triple[2])?;
+
+        require_keys_eq!(vault.key(), market.vault, PercolatorError::NoPosition);
+        require_keys_eq!(position.market, market.key(), PercolatorError::NoPosition);
+        require_keys_eq!(position.user, user, PercolatorError::NoPosition);
+
+        if position.settled || market.merkle_settlement {
+            continue;
+        }
+
+        let claimable = match market.status {
+            MarketStatus::Resolved if market.is_winning_side(position.side) => {
+                market.calculate_payout(&position)?
+            }
+            MarketStatus::Resolved | MarketStatus::Settled if market.kind == MarketKind::NoLoss => {
+                position.deposited
+            }
+            MarketStatus::Cancelled => market.refund_amount(position.deposited, vault.lamports()),
+            _ => 0,
+        };
+
+        total = total.checked_add(claimable)
+            .ok_or(PercolatorError::Overflow)?;
+    }
+
+    Ok(total)
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::instructions::resolve::{apply_outcome, void_if_parent_failed};
+use crate::instructions::resolve_with_rule::rule_outcome;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct TriggerResolution<'info> {
+    /// Anyone — no oracle signature is needed once the deadline passes.
+    /// Receives the market's resolution bounty.
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// Global config — minimum resolution delay; counts terminal markets.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// A market created with `resolve_at_deadline`.
+    #[account(
+        mut,
+        constraint = market.resolve_at_deadline @ PercolatorError::InvalidMarketRule,
+        constraint = market.status == MarketStatus::Open || market.status == MarketStatus::Closed
+            @ PercolatorError::AlreadyResolved,
+        constraint = !market.frozen @ PercolatorError::MarketFrozen,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// The market's stored feed: the oracle's reading for its token.
+    #[account(address = market.resolution_feed)]
+    pub reading: Account<'info, OracleReading>,
+
+    /// The secondary oracle's reading — required when `market.secondary_oracle` is set.
+    #[account(
+        constraint = Some(secondary_reading.oracle) == market.secondary_oracle
+            @ PercolatorError::MissingSecondaryReading,
+        constraint = secondary_reading.token_mint == market.token_mint
+            @ PercolatorError::MissingSecondaryReading,
+    )]
+    pub secondary_reading: Option<Account<'info, OracleReading>>,
+
+    /// Market vault — read balance for h-ratio computation; refunds unused subsidy.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Market creator — receives any unused subsidy.
+    /// CHECK: Validated against `market.creator`.
+    #[account(
+        mut,
+        address = market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: UncheckedAccount<'info>,
+
+    /// Creator stats — frees an active-market slot once the market closes.
+    /// Omitted only for markets created before creator stats existed.
+    #[account(
+        mut,
+        seeds = [b"creator_stats", market.creator.as_ref()],
+        bump = creator_stats.bump,
+    )]
+    pub creator_stats: Option<Account<'info, CreatorStats>>,
+
+    /// Parent market — required when `market.parent_market` is set.
+    #[account(
+        constraint = Some(parent_market.key()) == market.parent_market
+            @ PercolatorError::InvalidParentMarket,
+    )]
+    pub parent_market: Option<Account<'info, Market>>,
+}
+
+/// Resolve a `resolve_at_deadline` market from its stored feed.
+///
+/// Applies the same checks and rule evaluation as `resolve_with_rule`,
+/// except that the oracle's heartbeat is not required: the reading itself
+/// must still be published at or after the deadline, and no later than
+/// `config.max_reading_staleness_secs` after it.
+pub fn handler(ctx: Context<TriggerResolution>) -> Result<()> {
+    if void_if_parent_failed(
+        &mut ctx.accounts.market,
+        ctx.accounts.parent_market.as_ref(),
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        ctx.accounts.caller.key(),
+    )? {
+        return Ok(());
+    }
+
+    let Some(outcome) = rule_outcome(
+        &mut ctx.accounts.market,
+        &ctx.accounts.config,
+        &ctx.accounts.reading,
+        ctx.accounts.secondary_reading.as_deref(),
+    )? else {
+        return Ok(());
+    };
+
+    msg!("Resolution triggered by {}", ctx.accounts.caller.key());
+
+    apply_outcome(
+        &mut ctx.accounts.market,
+        &mut ctx.accounts.config,
+        ctx.accounts.creator_stats.as_deref_mut(),
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.caller.to_account_info(),
+        outcome,
+        ResolutionSource::OracleRule,
+    )
+}
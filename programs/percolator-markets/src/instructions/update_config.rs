@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+/// Config fields to update. `None` leaves a field unchanged.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateConfigParams {
+    /// Protocol fee in basis points.
+    pub fee_bps: Option<u16>,
+
+    /// Maximum seconds a single deadline extension may add.
+    pub max_extension_secs: Option<i64>,
+
+    /// Maximum number of deadline extensions per market.
+    pub max_extensions: Option<u8>,
+
+    /// Share of each fee routed to the market creator (basis points).
+    pub creator_fee_share_bps: Option<u16>,
+
+    /// Tip per position settled by a cranker (lamports).
+    pub crank_fee_lamports: Option<u64>,
+
+    /// Cap on total crank fees per market (lamports).
+    pub max_crank_fees_per_market: Option<u64>,
+
+    /// Maximum open markets per creator (0 = unlimited).
+    pub max_markets_per_creator: Option<u32>,
+
+    /// Protocol-wide volume cap per window (0 = no cap).
+    pub max_protocol_volume: Option<u64>,
+
+    /// Volume window length in seconds. Must be positive while
+    /// `max_protocol_volume` is set.
+    pub volume_window_secs: Option<i64>,
+
+    /// Minimum seconds between market creation and oracle resolution.
+    pub min_resolution_delay_secs: Option<i64>,
+
+    /// Maximum oracle resolution-window extension (capped at 30 days).
+    pub max_resolution_extension_secs: Option<i64>,
+
+    /// Maximum question length in bytes (≤ 256; 0 = 256).
+    pub max_question_len: Option<u16>,
+
+    /// Rounding of profit shares for markets created afterwards.
+    pub rounding_mode: Option<RoundingMode>,
+
+    /// Longest oracle heartbeat gap for automated resolution (0 = no check).
+    pub heartbeat_timeout_secs: Option<i64>,
+
+    /// Reject new markets whose oracle is their creator.
+    pub require_distinct_oracle: Option<bool>,
+
+    /// Early-settlement bonus or late-settlement penalty policy.
+    pub settlement_incentive: Option<SettlementIncentive>,
+
+    /// Incentive as a share of the settler's profit (basis points).
+    pub settlement_incentive_bps: Option<u16>,
+
+    /// Bonus decay / penalty grace window after resolution (seconds).
+    pub settlement_incentive_window_secs: Option<i64>,
+
+    /// Longest time from creation to a market's deadline (0 = no limit).
+    pub max_market_duration_secs: Option<i64>,
+
+    /// Latest a rule reading may be published after the deadline
+    /// (0 = no bound).
+    pub max_reading_staleness_secs: Option<i64>,
+
+    /// Backfill of the terminal-market count (≤ `total_markets`). Markets
+    /// that closed before it was tracked were never counted.
+    pub terminal_markets: Option<u64>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    /// Protocol authority.
+    #[account(
+        constraint = authority.key() == config.authority @ PercolatorError::UnauthorizedAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global config PDA.
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+pub fn handler(ctx: Context<UpdateConfig>, params: UpdateConfigParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    if let Some(fee_bps) = params.fee_bps {
+        require!(fee_bps <= 10_000, PercolatorError::InvalidBasisPoints);
+        config.fee_bps = fee_bps;
+    }
+    if let Some(max_extension_secs) = params.max_extension_secs {
+        require!(max_extension_secs >= 0, PercolatorError::NegativeDuration);
+        config.max_extension_secs = max_extension_secs;
+    }
+    if let Some(max_extensions) = params.max_extensions {
+        config.max_extensions = max_extensions;
+    }
+    if let Some(creator_fee_share_bps) = params.creator_fee_share_bps {
+        require!(creator_fee_share_bps <= 10_000, PercolatorError::InvalidBasisPoints);
+        config.creator_fee_share_bps = creator_fee_share_bps;
+    }
+    if let Some(crank_fee_lamports) = params.crank_fee_lamports {
+        config.crank_fee_lamports = crank_fee_lamports;
+    }
+    if let Some(max_crank_fees_per_market) = params.max_crank_fees_per_market {
+        config.max_crank_fees_per_market = max_crank_fees_per_market;
+    }
+    if let Some(max_markets_per_creator) = params.max_markets_per_creator {
+        config.max_markets_per_creator = max_markets_per_creator;
+    }
+    if let Some(max_protocol_volume) = params.max_protocol_volume {
+        config.max_protocol_volume = max_protocol_volume;
+    }
+    if let Some(volume_window_secs) = params.volume_window_secs {
+        require!(volume_window_secs >= 0, PercolatorError::NegativeDuration);
+        config.volume_window_secs = volume_window_secs;
+    }
+    // Without a window, the cap would apply to each bet on its own
+    require!(
+        config.max_protocol_volume == 0 || config.volume_window_secs > 0,
+        PercolatorError::InvalidVolumeWindow
+    );
+    if let Some(min_resolution_delay_secs) = params.min_resolution_delay_secs {
+        require!(min_resolution_delay_secs >= 0, PercolatorError::NegativeDuration);
+        config.min_resolution_delay_secs = min_resolution_delay_secs;
+    }
+    if let Some(max_resolution_extension_secs) = params.max_resolution_extension_secs {
+        require!(max_resolution_extension_secs >= 0, PercolatorError::NegativeDuration);
+        require!(
+            max_resolution_extension_secs <= GlobalConfig::RESOLUTION_EXTENSION_HARD_CAP_SECS,
+            PercolatorError::ExtensionTooLong
+        );
+        config.max_resolution_extension_secs = max_resolution_extension_secs;
+    }
+    if let Some(max_question_len) = params.max_question_len {
+        require!(
+            max_question_len as usize <= Market::MAX_QUESTION_LEN,
+            PercolatorError::QuestionTooLong
+        );
+        config.max_question_len = max_question_len;
+    }
+    if let Some(rounding_mode) = params.rounding_mode {
+        config.rounding_mode = rounding_mode;
+    }
+    if let Some(heartbeat_timeout_secs) = params.heartbeat_timeout_secs {
+        require!(heartbeat_timeout_secs >= 0, PercolatorError::NegativeDuration);
+        config.heartbeat_timeout_secs = heartbeat_timeout_secs;
+    }
+    if let Some(require_distinct_oracle) = params.require_distinct_oracle {
+        config.require_distinct_oracle = require_distinct_oracle;
+    }
+    if let Some(settlement_incentive) = params.settlement_incentive {
+        config.settlement_incentive = settlement_incentive;
+    }
+    if let Some(settlement_incentive_bps) = params.settlement_incentive_bps {
+        require!(settlement_incentive_bps <= 10_000, PercolatorError::InvalidBasisPoints);
+        config.settlement_incentive_bps = settlement_incentive_bps;
+    }
+    if let Some(settlement_incentive_window_secs) = params.settlement_incentive_window_secs {
+        require!(settlement_incentive_window_secs >= 0, PercolatorError::NegativeDuration);
+        config.settlement_incentive_window_secs = settlement_incentive_window_secs;
+    }
+    if let Some(max_market_duration_secs) = params.max_market_duration_secs {
+        require!(max_market_duration_secs >= 0, PercolatorError::InvalidMarketDuration);
+        config.max_market_duration_secs = max_market_duration_secs;
+    }
+    if let Some(max_reading_staleness_secs) = params.max_reading_staleness_secs {
+        require!(max_reading_staleness_secs >= 0, PercolatorError::NegativeDuration);
+        config.max_reading_staleness_secs = max_reading_staleness_secs;
+    }
+    if let Some(terminal_markets) = params.terminal_markets {
+        require!(
+            terminal_markets <= config.total_markets,
+            PercolatorError::InvalidTerminalMarkets
+        );
+        config.terminal_markets = terminal_markets;
+    }
+
+    msg!("Config updated by {}", ctx.accounts.authority.key());
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct UpdateTarget<'info> {
+    /// Market creator.
+    #[account(
+        constraint = creator.key() == market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: Signer<'info>,
+
+    /// The market to adjust.
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Open @ PercolatorError::InvalidMarketStatus,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+pub fn handler(ctx: Context<UpdateTarget>, target_value: u64) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    // Terms are immutable once anyone has wagered on them
+    let total_pool = market.yes_pool.checked_add(market.no_pool)
+        .ok_or(PercolatorError::Overflow)?;
+    require!(total_pool == 0, PercolatorError::MarketHasBets);
+
+    let old_target = market.target_value;
+    market.target_value = target_value;
+
+    msg!(
+        "Market #{} target updated: {} -> {}",
+        market.market_id,
+        old_target,
+        target_value,
+    );
+
+    Ok(())
+}
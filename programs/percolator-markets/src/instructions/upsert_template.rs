@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+/// Template defaults, copied onto every market created from it.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct TemplateParams {
+    /// Resolution rule.
+    pub rule: MarketRule,
+
+    /// Oracle authority.
+    pub oracle: Pubkey,
+
+    /// Target value for resolution.
+    pub target_value: u64,
+
+    /// Seconds from creation to the deadline (> 0).
+    pub duration_secs: i64,
+
+    /// Seconds after the deadline the oracle has to resolve.
+    pub resolution_window_secs: i64,
+
+    /// Fallback outcome if the oracle never resolves (`Unresolved` = void).
+    pub default_outcome: Outcome,
+
+    /// What `settle` does when the vault cannot cover a payout.
+    pub insolvency_mode: InsolvencyMode,
+
+    /// Allow the creator to bet.
+    pub creator_betting_allowed: bool,
+
+    /// Allow the oracle to bet.
+    pub oracle_betting_allowed: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(template_id: u32)]
+pub struct UpsertTemplate<'info> {
+    /// Template owner — pays for the account on first use.
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Template PDA, created on first use and overwritten afterwards.
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = MarketTemplate::SIZE,
+        seeds = [b"template", creator.key().as_ref(), template_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub template: Account<'info, MarketTemplate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<UpsertTemplate>, template_id: u32, params: TemplateParams) -> Result<()> {
+    require!(params.duration_secs > 0, PercolatorError::DeadlineInPast);
+    require!(
+        params.resolution_window_secs > 0,
+        PercolatorError::InvalidResolutionWindow
+    );
+    if let MarketRule::Scalar { lower, upper } = params.rule {
+        require!(lower < upper, PercolatorError::InvalidScalarRange);
+    }
+
+    let template = &mut ctx.accounts.template;
+    template.creator = ctx.accounts.creator.key();
+    template.template_id = template_id;
+    template.rule = params.rule;
+    template.oracle = params.oracle;
+    template.target_value = params.target_value;
+    template.duration_secs = params.duration_secs;
+    template.resolution_window_secs = params.resolution_window_secs;
+    template.default_outcome = params.default_outcome;
+    template.insolvency_mode = params.insolvency_mode;
+    template.creator_betting_allowed = params.creator_betting_allowed;
+    template.oracle_betting_allowed = params.oracle_betting_allowed;
+    template.bump = ctx.bumps.template;
+
+    msg!(
+        "Template #{} saved for {}: duration={}s",
+        template_id,
+        template.creator,
+        template.duration_secs,
+    );
+
+    Ok(())
+}
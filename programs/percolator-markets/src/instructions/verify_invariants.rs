@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct VerifyInvariants<'info> {
+    /// Any market not cancelled. Cancelled refunds burn tokens without
+    /// shrinking the pools, so supply no longer tracks them per side.
+    /// Position-NFT markets mint no YES/NO supply to check.
+    #[account(
+        constraint = market.status != MarketStatus::Cancelled @ PercolatorError::InvalidMarketStatus,
+        constraint = !market.position_nfts @ PercolatorError::PositionNftsUnsupported,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// YES token mint.
+    #[account(
+        seeds = [b"yes_mint", market.key().as_ref()],
+        bump,
+    )]
+    pub yes_mint: Account<'info, Mint>,
+
+    /// NO token mint.
+    #[account(
+        seeds = [b"no_mint", market.key().as_ref()],
+        bump,
+    )]
+    pub no_mint: Account<'info, Mint>,
+}
+
+/// Check that each side's token supply equals its pool.
+///
+/// Bets mint one token per staked lamport and cash-outs burn exactly what
+/// they take out of the pool; fees never reach the pools or the mints.
+/// Settlement leaves tokens in place, so the check still holds once the
+/// market resolves. Any mismatch is accounting drift.
+pub fn handler(ctx: Context<VerifyInvariants>) -> Result<()> {
+    let market = &ctx.accounts.market;
+    let yes_supply = ctx.accounts.yes_mint.supply;
+    let no_supply = ctx.accounts.no_mint.supply;
+
+    if yes_supply != market.yes_pool || no_supply != market.no_pool {
+        msg!(
+            "Market #{} invariant violated: yes supply {} vs pool {}, no supply {} vs pool {}",
+            market.market_id,
+            yes_supply,
+            market.yes_pool,
+            no_supply,
+            market.no_pool,
+        );
+        return err!(PercolatorError::InvariantViolation);
+    }
+
+    msg!("Market #{} invariants hold", market.market_id);
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct WithdrawCreatorRevenue<'info> {
+    /// Market creator — receives the accrued revenue.
+    #[account(
+        mut,
+        constraint = creator.key() == market.creator @ PercolatorError::UnauthorizedCreator,
+    )]
+    pub creator: Signer<'info>,
+
+    /// The market whose fee share is withdrawn.
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+
+    /// Creator revenue PDA — holds the creator share of bet fees, apart
+    /// from the bettor vault.
+    /// CHECK: Validated by seeds.
+    #[account(
+        mut,
+        seeds = [b"creator_revenue", market.key().as_ref()],
+        bump,
+    )]
+    pub creator_revenue: SystemAccount<'info>,
+}
+
+/// Withdraw the accrued revenue. The PDA keeps its rent minimum; in a
+/// market whose PDA was never funded, that part stays accrued until later
+/// fees or `migrate_market` cover the rent.
+pub fn handler(ctx: Context<WithdrawCreatorRevenue>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let revenue = ctx.accounts.creator_revenue.to_account_info();
+    let available = revenue.lamports().saturating_sub(Rent::get()?.minimum_balance(0));
+    let amount = market.creator_revenue.min(available);
+    require!(amount > 0, PercolatorError::ZeroAmount);
+
+    **revenue.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += amount;
+    market.creator_revenue -= amount;
+
+    msg!(
+        "Creator revenue withdrawn: {} lamports from market #{} to {}",
+        amount,
+        market.market_id,
+        ctx.accounts.creator.key(),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::PercolatorError;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    /// Protocol authority.
+    #[account(
+        constraint = authority.key() == config.authority @ PercolatorError::UnauthorizedAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    /// Global config — identifies the protocol authority.
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// Treasury PDA — source of the withdrawal.
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Destination chosen by the authority.
+    /// CHECK: Any account can receive lamports.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    require!(amount > 0, PercolatorError::ZeroAmount);
+
+    // Never dip into the rent-exempt reserve
+    let treasury_info = ctx.accounts.treasury.to_account_info();
+    let rent_min = Rent::get()?.minimum_balance(treasury_info.data_len());
+    let available = treasury_info.lamports().saturating_sub(rent_min);
+    require!(amount <= available, PercolatorError::InsufficientTreasuryBalance);
+
+    **treasury_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.destination.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.total_withdrawn = treasury.total_withdrawn.checked_add(amount)
+        .ok_or(PercolatorError::Overflow)?;
+
+    msg!(
+        "Treasury withdrawal: {} lamports to {}",
+        amount,
+        ctx.accounts.destination.key(),
+    );
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
@@ -12,6 +13,95 @@ declare_id!("PERCmkT7XHFjnMGiLBKo9Qxmf4BBJY6oSVhLwMpXuq");
 pub mod percolator_markets {
     use super::*;
 
+    /// Initialize the global protocol config.
+    ///
+    /// The signer becomes the protocol authority.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        params: InitializeConfigParams,
+    ) -> Result<()> {
+        instructions::initialize_config::handler(ctx, params)
+    }
+
+    /// Update protocol settings (authority only).
+    ///
+    /// Fields left as `None` are unchanged.
+    pub fn update_config(ctx: Context<UpdateConfig>, params: UpdateConfigParams) -> Result<()> {
+        instructions::update_config::handler(ctx, params)
+    }
+
+    /// Propose a new fee collector (authority only).
+    ///
+    /// Takes effect once the proposed wallet calls `accept_fee_collector`;
+    /// a later proposal replaces a pending one.
+    pub fn propose_fee_collector(
+        ctx: Context<ProposeFeeCollector>,
+        new_fee_collector: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_fee_collector::handler(ctx, new_fee_collector)
+    }
+
+    /// Accept a pending fee-collector handover (proposed wallet only).
+    pub fn accept_fee_collector(ctx: Context<AcceptFeeCollector>) -> Result<()> {
+        instructions::accept_fee_collector::handler(ctx)
+    }
+
+    /// Create the protocol treasury PDA (authority only).
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        instructions::initialize_treasury::handler(ctx)
+    }
+
+    /// Withdraw accrued fees from the treasury (authority only).
+    ///
+    /// The treasury always keeps its rent-exempt minimum.
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury::handler(ctx, amount)
+    }
+
+    /// Move lamports sent to a Market account by mistake to the fee
+    /// collector (authority only).
+    ///
+    /// Only the balance above the account's rent-exempt minimum is moved;
+    /// the market's vault is untouched.
+    pub fn rescue_stray_lamports(ctx: Context<RescueStrayLamports>) -> Result<()> {
+        instructions::rescue_stray_lamports::handler(ctx)
+    }
+
+    /// Protocol-wide totals for dashboards.
+    ///
+    /// Returned via return data; no account is modified.
+    pub fn get_global_stats(ctx: Context<GetGlobalStats>) -> Result<GlobalStats> {
+        instructions::get_global_stats::handler(ctx)
+    }
+
+    /// Close the global config and return its rent (authority only).
+    ///
+    /// One-way protocol shutdown: every market must already be Settled or
+    /// Cancelled, and since resolution, settlement and market creation all
+    /// require the config, nothing that needs it can run afterwards.
+    pub fn close_config(ctx: Context<CloseConfig>) -> Result<()> {
+        instructions::close_config::handler(ctx)
+    }
+
+    /// Grow a config created under an older layout to the current size
+    /// (authority only; the authority pays the extra rent).
+    ///
+    /// Fields added since read as zero, which leaves each of them off.
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        instructions::migrate_config::handler(ctx)
+    }
+
+    /// Ban or unban a creator from opening new markets (authority only).
+    ///
+    /// Markets the creator already runs keep working.
+    pub fn set_creator_ban(
+        ctx: Context<SetCreatorBan>,
+        creator: Pubkey,
+        banned: bool,
+    ) -> Result<()> {
+        instructions::set_creator_ban::handler(ctx, creator, banned)
+    }
+
     /// Create a new binary prediction market.
     ///
     /// The market vault is funded by an initial seed deposit from the creator.
@@ -23,22 +113,237 @@ pub mod percolator_markets {
         instructions::create_market::handler(ctx, params)
     }
 
+    /// Create a market that cannot be duplicated by the same creator.
+    ///
+    /// Also initializes a `MarketDedupe` PDA keyed by the hash of the
+    /// question, token mint, rule and target value, so a second identical
+    /// market fails. Otherwise identical to `create_market`.
+    pub fn create_market_deduped(
+        ctx: Context<CreateMarketDeduped>,
+        params: CreateMarketParams,
+    ) -> Result<()> {
+        instructions::create_market_deduped::handler(ctx, params)
+    }
+
+    /// Create a market and place the creator's first bet atomically.
+    ///
+    /// Equivalent to `create_market` followed by `place_bet` from the creator;
+    /// the creator's position token account is created in the same call.
+    pub fn create_and_bet(
+        ctx: Context<CreateAndBet>,
+        params: CreateMarketParams,
+        side: BetSide,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::create_and_bet::handler(ctx, params, side, amount)
+    }
+
+    /// Create several markets in one transaction.
+    ///
+    /// `remaining_accounts` carries one `create_market` account group per
+    /// entry in `params`; ids are assigned sequentially and every market is
+    /// validated as if created on its own.
+    pub fn create_markets_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateMarketsBatch<'info>>,
+        params: Vec<CreateMarketParams>,
+    ) -> Result<()> {
+        instructions::create_markets_batch::handler(ctx, params)
+    }
+
+    /// Create or overwrite one of the creator's market templates.
+    pub fn upsert_template(
+        ctx: Context<UpsertTemplate>,
+        template_id: u32,
+        params: TemplateParams,
+    ) -> Result<()> {
+        instructions::upsert_template::handler(ctx, template_id, params)
+    }
+
+    /// Create a market from a template, supplying only the question and
+    /// token mint.
+    ///
+    /// The deadline is `now + template.duration_secs`; every other setting
+    /// comes from the template.
+    pub fn create_from_template(
+        ctx: Context<CreateFromTemplate>,
+        question: String,
+        token_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::create_from_template::handler(ctx, question, token_mint)
+    }
+
+    /// Create a hedge of one of the creator's open markets.
+    ///
+    /// The new market copies the token, oracle, target and deadline with the
+    /// inverse rule, and records the original in `hedge_of`. The two markets
+    /// resolve independently.
+    pub fn spawn_hedge_market(ctx: Context<SpawnHedgeMarket>, question: String) -> Result<()> {
+        instructions::spawn_hedge_market::handler(ctx, question)
+    }
+
+    /// Change a market's `target_value` before any bet is placed (creator only).
+    pub fn update_target(ctx: Context<UpdateTarget>, target_value: u64) -> Result<()> {
+        instructions::update_target::handler(ctx, target_value)
+    }
+
+    /// Replace a market's `question` before any bet is placed (creator only).
+    /// Commit-reveal markets cannot be amended.
+    pub fn amend_question(ctx: Context<AmendQuestion>, question: String) -> Result<()> {
+        instructions::amend_question::handler(ctx, question)
+    }
+
     /// Place a bet on YES or NO.
     ///
-    /// Transfers `amount` from the bettor into the market vault and mints
-    /// the corresponding position token (YES-mint or NO-mint).
-    pub fn place_bet(ctx: Context<PlaceBet>, side: BetSide, amount: u64) -> Result<()> {
-        instructions::place_bet::handler(ctx, side, amount)
+    /// Transfers `amount` from the bettor. The protocol fee comes off the
+    /// top (split between the treasury PDA and the market creator); the net
+    /// stake goes into the market vault and mints the corresponding position
+    /// token (YES-mint or NO-mint). A `position_nfts` market instead mints
+    /// the position a single NFT with Token Metadata, once per position.
+    ///
+    /// An optional 16-byte `memo` is stored on the position by its first
+    /// bet and echoed in `BetPlaced`; later memos are ignored.
+    pub fn place_bet(
+        ctx: Context<PlaceBet>,
+        side: BetSide,
+        amount: u64,
+        memo: Option<[u8; 16]>,
+    ) -> Result<()> {
+        instructions::place_bet::handler(ctx, side, amount, memo)
+    }
+
+    /// Set a position's stake to the user's token balance on `side`
+    /// (anyone may call, before resolution).
+    ///
+    /// Tokens sold lower the position and free that stake; tokens bought
+    /// raise it, but only by stake already freed on the same side.
+    pub fn reconcile_position(ctx: Context<ReconcilePosition>, side: BetSide) -> Result<()> {
+        instructions::reconcile_position::handler(ctx, side)
+    }
+
+    /// Commit a sealed bet in a commit-reveal market.
+    ///
+    /// `commitment` = sha256(side || amount_le || nonce). The full `amount`
+    /// is escrowed until reveal; must be called before `commit_deadline`.
+    pub fn commit_bet(
+        ctx: Context<CommitBet>,
+        commitment: [u8; 32],
+        amount: u64,
+    ) -> Result<()> {
+        instructions::commit_bet::handler(ctx, commitment, amount)
+    }
+
+    /// Reveal a committed bet and mint position tokens.
+    ///
+    /// Accepted between `commit_deadline` and the market deadline; the fee is
+    /// charged on reveal exactly as in `place_bet`.
+    pub fn reveal_bet(ctx: Context<RevealBet>, side: BetSide, nonce: [u8; 32]) -> Result<()> {
+        instructions::reveal_bet::handler(ctx, side, nonce)
+    }
+
+    /// Reclaim an unrevealed commitment after the deadline or a cancellation.
+    pub fn refund_commitment(ctx: Context<RefundCommitment>) -> Result<()> {
+        instructions::refund_commitment::handler(ctx)
     }
 
     /// Resolve the market outcome.
     ///
     /// Only callable by the designated oracle authority.
     /// Sets `outcome` to YES or NO based on the resolution condition.
+    ///
+    /// A conditional market is voided instead if its parent resolved to any
+    /// outcome other than `parent_required_outcome`.
     pub fn resolve_market(ctx: Context<ResolveMarket>, outcome: Outcome) -> Result<()> {
         instructions::resolve::handler(ctx, outcome)
     }
 
+    /// Resolve a `Scalar` market to an oracle-reported value.
+    ///
+    /// YES holders receive `clamp((value − lower) / (upper − lower))` of the
+    /// total pool and NO holders the rest, instead of winner-takes-all.
+    pub fn resolve_scalar(ctx: Context<ResolveMarket>, value: u64) -> Result<()> {
+        instructions::resolve_scalar::handler(ctx, value)
+    }
+
+    /// Resolve a binary market as partially true.
+    ///
+    /// YES holders receive `yes_weight_bps` (0–10000) of the total pool and
+    /// NO holders the rest, settled like a scalar outcome: capital back up
+    /// to each side's share, the excess as profit under h.
+    pub fn resolve_blended(ctx: Context<ResolveMarket>, yes_weight_bps: u16) -> Result<()> {
+        instructions::resolve_blended::handler(ctx, yes_weight_bps)
+    }
+
+    /// Post raw price/supply data for a token (any oracle, per-oracle PDA).
+    ///
+    /// The price keeps the feed's own `price_exponent`; rule resolution
+    /// rescales it to the market's scale.
+    pub fn post_oracle_reading(
+        ctx: Context<PostOracleReading>,
+        params: PostOracleReadingParams,
+    ) -> Result<()> {
+        instructions::post_oracle_reading::handler(ctx, params)
+    }
+
+    /// Resolve the market by evaluating its rule against the oracle's reading.
+    ///
+    /// The program computes market cap (price × supply) or uses the price
+    /// directly, compares it to `target_value` and derives the outcome.
+    /// `OracleCustom` markets cannot be self-resolved.
+    pub fn resolve_with_rule(ctx: Context<ResolveWithRule>) -> Result<()> {
+        instructions::resolve_with_rule::handler(ctx)
+    }
+
+    /// Resolve a `resolve_at_deadline` market from its stored feed (anyone).
+    ///
+    /// Evaluates the rule exactly as `resolve_with_rule` does, without an
+    /// oracle signature; the caller collects any resolution bounty.
+    pub fn trigger_resolution(ctx: Context<TriggerResolution>) -> Result<()> {
+        instructions::trigger_resolution::handler(ctx)
+    }
+
+    /// Preview what `resolve_with_rule` would decide from the current reading.
+    ///
+    /// Returns the outcome and observed value via return data; the market
+    /// is not modified.
+    pub fn preview_resolution(ctx: Context<PreviewResolution>) -> Result<ResolutionPreview> {
+        instructions::preview_resolution::handler(ctx)
+    }
+
+    /// Record a periodic `MarketCapFloor` checkpoint (oracle only).
+    ///
+    /// A value below the floor latches `floor_breached`, which forces the
+    /// market to resolve NO.
+    pub fn record_checkpoint(ctx: Context<RecordCheckpoint>, observed_value: u64) -> Result<()> {
+        instructions::record_checkpoint::handler(ctx, observed_value)
+    }
+
+    /// Signal the oracle is live for a market (oracle only).
+    ///
+    /// `resolve_with_rule` is refused once the last heartbeat or checkpoint
+    /// is older than `config.heartbeat_timeout_secs`.
+    pub fn heartbeat(ctx: Context<Heartbeat>) -> Result<()> {
+        instructions::heartbeat::handler(ctx)
+    }
+
+    /// Finalize a market the oracle left unresolved (permissionless).
+    ///
+    /// After `resolution_window_end`, applies the market's `default_outcome`,
+    /// or voids the market if the default is `Unresolved`.
+    pub fn resolve_default(ctx: Context<ResolveDefault>) -> Result<()> {
+        instructions::resolve_default::handler(ctx)
+    }
+
+    /// Push the resolution window back once (oracle only).
+    ///
+    /// Bounded by `config.max_resolution_extension_secs` and a 30-day hard
+    /// cap; emits `ResolutionExtended` so bettors see the delay.
+    pub fn request_resolution_extension(
+        ctx: Context<RequestResolutionExtension>,
+        extension_secs: i64,
+    ) -> Result<()> {
+        instructions::request_resolution_extension::handler(ctx, extension_secs)
+    }
+
     /// Settle a user's position after market resolution.
     ///
     /// Computes payout using the Percolator two-claim model:
@@ -49,22 +354,269 @@ pub mod percolator_markets {
     ///
     /// If h < 1, profits are haircut proportionally — the market NEVER
     /// becomes insolvent.
-    pub fn settle(ctx: Context<Settle>) -> Result<()> {
-        instructions::settle::handler(ctx)
+    ///
+    /// Returns the payout breakdown (capital, profit, fee, total) via return
+    /// data.
+    ///
+    /// Pass `settle_order` (the market's current `settlements_count`) to
+    /// serialize settles: the call fails if another settle landed first.
+    ///
+    /// In a `position_nfts` market the user's position NFT is burned.
+    pub fn settle(ctx: Context<Settle>, settle_order: Option<u64>) -> Result<PayoutBreakdown> {
+        instructions::settle::handler(ctx, settle_order)
+    }
+
+    /// Settle a winning position on its owner's behalf.
+    ///
+    /// The signer receives `config.crank_fee_lamports`, taken from the
+    /// position's profit (never its capital) and capped per market by
+    /// `config.max_crank_fees_per_market`.
+    pub fn crank_settle(ctx: Context<CrankSettle>, settle_order: Option<u64>) -> Result<()> {
+        instructions::crank_settle::handler(ctx, settle_order)
+    }
+
+    /// Settle several winning positions of the signer in one transaction.
+    ///
+    /// `remaining_accounts` holds `(market, position, vault, creator_stats)`
+    /// groups, at most `MAX_SETTLE_MANY`. Returns the total paid out.
+    pub fn settle_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleMany<'info>>,
+    ) -> Result<u64> {
+        instructions::settle_many::handler(ctx)
+    }
+
+    /// Post a Merkle root of off-chain computed winner payouts (oracle or
+    /// authority).
+    ///
+    /// Only allowed before any position has settled; per-position `settle`
+    /// is disabled afterwards. The root's total must fit in the vault.
+    pub fn post_settlement_root(
+        ctx: Context<PostSettlementRoot>,
+        params: PostSettlementRootParams,
+    ) -> Result<()> {
+        instructions::post_settlement_root::handler(ctx, params)
+    }
+
+    /// Claim a payout from the posted settlement root with a Merkle proof.
+    pub fn claim_merkle(
+        ctx: Context<ClaimMerkle>,
+        index: u32,
+        payout: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::claim_merkle::handler(ctx, index, payout, proof)
+    }
+
+    /// Close a losing position after resolution and reclaim its rent.
+    ///
+    /// In a no-loss market this also returns the full deposit.
+    pub fn close_losing_position(ctx: Context<CloseLosingPosition>) -> Result<()> {
+        instructions::close_losing_position::handler(ctx)
+    }
+
+    /// Close several finished positions and reclaim their rent.
+    ///
+    /// `remaining_accounts` holds `(market, position)` pairs, at most
+    /// `MAX_CLEANUP_POSITIONS`. Settled, refunded and losing positions are
+    /// closed; still-claimable winners are skipped. Returns the rent reclaimed.
+    pub fn cleanup_positions<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CleanupPositions<'info>>,
+    ) -> Result<u64> {
+        instructions::cleanup_positions::handler(ctx)
+    }
+
+    /// Dry-run `settle` for a position without mutating any account.
+    ///
+    /// Returns the payout breakdown (capital, profit after h, fee, total) via
+    /// return data. Losing or already-settled positions are reported in
+    /// `status` instead of failing.
+    pub fn preview_settle(ctx: Context<PreviewSettle>) -> Result<SettlePreview> {
+        instructions::preview_settle::handler(ctx)
+    }
+
+    /// Quote a winning position's payout with every haircut and fee applied.
+    ///
+    /// Uses the live vault balance and, when `via_crank`, the crank fee, so
+    /// the result matches what `settle` or `crank_settle` would pay now.
+    /// Returned via return data; no account is modified.
+    pub fn compute_effective_payout(
+        ctx: Context<ComputeEffectivePayout>,
+        via_crank: bool,
+    ) -> Result<EffectivePayout> {
+        instructions::compute_effective_payout::handler(ctx, via_crank)
+    }
+
+    /// Total a user could claim now across several positions.
+    ///
+    /// Pass `(market, vault, position)` triples in `remaining_accounts`.
+    /// Returns the sum of settle payouts and cancelled-market refunds via
+    /// return data; no account is modified.
+    pub fn total_claimable<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TotalClaimable<'info>>,
+    ) -> Result<u64> {
+        instructions::total_claimable::handler(ctx)
+    }
+
+    /// Amount still owed to unsettled winners, and the vault balance.
+    ///
+    /// Returned via return data so monitors can flag an under-collateralized
+    /// market; no account is modified.
+    pub fn outstanding_claims(ctx: Context<OutstandingClaims>) -> Result<ClaimsReport> {
+        instructions::outstanding_claims::handler(ctx)
+    }
+
+    /// Check that each side's position-token supply equals its pool.
+    ///
+    /// Fails with `InvariantViolation` on a mismatch; no account is modified.
+    pub fn verify_invariants(ctx: Context<VerifyInvariants>) -> Result<()> {
+        instructions::verify_invariants::handler(ctx)
     }
 
     /// Cancel a market before resolution (creator or authority only).
     ///
+    /// After the deadline, the protocol authority must co-sign.
     /// All bettors can claim full refund via `claim_refund`.
     pub fn cancel_market(ctx: Context<CancelMarket>) -> Result<()> {
         instructions::cancel::handler(ctx)
     }
 
+    /// Emergency cancel of a wedged market (protocol authority only).
+    ///
+    /// Works from any status short of Settled/Cancelled, including Resolved
+    /// after some winners have settled. Their payouts stand; the remaining
+    /// bettors refund a pro-rata share of what is left via `claim_refund`.
+    /// Refused after Merkle claims. Emits `MarketForceCancelled`.
+    pub fn admin_force_cancel(ctx: Context<AdminForceCancel>) -> Result<()> {
+        instructions::admin_force_cancel::handler(ctx)
+    }
+
     /// Claim refund from a cancelled market.
     ///
     /// Burns the user's position tokens and returns the equivalent SOL.
+    /// If the vault is short, every refund is scaled by the same ratio
+    /// (vault_distributable / outstanding_deposits).
     pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
         instructions::claim_refund::handler(ctx)
     }
-}
 
+    /// Claim refund from a cancelled market using the position record alone.
+    ///
+    /// For users whose position token account is closed or empty: nothing is
+    /// burned, and the refund is `position.deposited` scaled as in
+    /// `claim_refund`. The `settled` flag prevents a second refund.
+    pub fn claim_refund_by_position(ctx: Context<ClaimRefundByPosition>) -> Result<()> {
+        instructions::claim_refund_by_position::handler(ctx)
+    }
+
+    /// Refund several positions of a cancelled market in one transaction.
+    ///
+    /// `remaining_accounts` holds `(position, user, token_account)` groups,
+    /// at most `MAX_BATCH_REFUND`; every user must sign so their tokens can
+    /// be burned. Returns the total refunded.
+    pub fn batch_refund<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchRefund<'info>>,
+    ) -> Result<u64> {
+        instructions::batch_refund::handler(ctx)
+    }
+
+    /// Add creator liquidity to an open market's vault.
+    ///
+    /// Raises the vault balance used for the h-ratio without joining either
+    /// pool. The liquidity earns no payout.
+    pub fn add_liquidity(ctx: Context<AddLiquidity>, amount: u64) -> Result<()> {
+        instructions::add_liquidity::handler(ctx, amount)
+    }
+
+    /// Reclaim unused creator liquidity once the market is settled or cancelled.
+    pub fn reclaim_liquidity(ctx: Context<ReclaimLiquidity>) -> Result<()> {
+        instructions::reclaim_liquidity::handler(ctx)
+    }
+
+    /// Withdraw the creator's accrued share of bet fees (creator only).
+    ///
+    /// Paid from the market's creator-revenue PDA, never the bettor vault,
+    /// which keeps its rent minimum; lowers `market.creator_revenue` by the
+    /// amount paid.
+    pub fn withdraw_creator_revenue(ctx: Context<WithdrawCreatorRevenue>) -> Result<()> {
+        instructions::withdraw_creator_revenue::handler(ctx)
+    }
+
+    /// Sweep what unsettled winners are still owed to the creator once the
+    /// market's `claim_deadline` has passed (creator only).
+    ///
+    /// Moves the market to Settled; the remaining winners can no longer
+    /// settle. There is no protocol-wide sweep, so this is the only path
+    /// for unclaimed winnings.
+    pub fn creator_sweep_unclaimed(ctx: Context<CreatorSweepUnclaimed>) -> Result<()> {
+        instructions::creator_sweep_unclaimed::handler(ctx)
+    }
+
+    /// Top up a resolved market's vault (anyone).
+    ///
+    /// If the vault then covers every outstanding winning claim in full,
+    /// `h_ratio_bps` is reset to 10000 for the remaining settlements. For a
+    /// market blocked below its h-ratio floor, h is recomputed and settlement
+    /// unblocks once the floor is reached.
+    pub fn restore_solvency(ctx: Context<RestoreSolvency>, amount: u64) -> Result<()> {
+        instructions::restore_solvency::handler(ctx, amount)
+    }
+
+    /// Push an open market's deadline later (creator only).
+    ///
+    /// Bounded by `config.max_extension_secs` per call and
+    /// `config.max_extensions` per market. Never shortens the deadline.
+    pub fn extend_deadline(ctx: Context<ExtendDeadline>, new_deadline: i64) -> Result<()> {
+        instructions::extend_deadline::handler(ctx, new_deadline)
+    }
+
+    /// Freeze a single market (authority only).
+    ///
+    /// Blocks `place_bet` and `resolve_market`; users can still exit via
+    /// `cash_out`, `cancel_market`, `claim_refund` and `settle`.
+    pub fn freeze_market(ctx: Context<SetMarketFrozen>) -> Result<()> {
+        instructions::set_market_frozen::handler(ctx, true)
+    }
+
+    /// Lift a market freeze (authority only).
+    pub fn unfreeze_market(ctx: Context<SetMarketFrozen>) -> Result<()> {
+        instructions::set_market_frozen::handler(ctx, false)
+    }
+
+    /// Exit a position in a frozen market at its deposit value.
+    ///
+    /// Burns the position tokens, refunds the full deposit with no exit fee
+    /// and closes the position. Only available while the market is frozen.
+    pub fn cash_out(ctx: Context<CashOut>) -> Result<()> {
+        instructions::cash_out::handler(ctx)
+    }
+
+    /// Schedule a future oracle rotation (creator only).
+    ///
+    /// Once a schedule exists, `resolve_market` accepts the latest oracle
+    /// whose entry is in effect. Entries can only be appended after all
+    /// existing ones and must lie in the future.
+    pub fn append_oracle_schedule(
+        ctx: Context<AppendOracleSchedule>,
+        effective_at: i64,
+        oracle: Pubkey,
+    ) -> Result<()> {
+        instructions::append_oracle_schedule::handler(ctx, effective_at, oracle)
+    }
+
+    /// Grow a market created under an older layout to the current size
+    /// (permissionless; the caller pays the extra rent).
+    ///
+    /// Markets from before versioning also get defaults for every field
+    /// they lack, and cannot be resolved by default until migrated. Also
+    /// funds the creator revenue PDA's rent if it was never paid.
+    pub fn migrate_market(ctx: Context<MigrateMarket>) -> Result<()> {
+        instructions::migrate_market::handler(ctx)
+    }
+
+    /// Grow a position created under an older layout to the current size
+    /// (permissionless; the caller pays the extra rent). Its memo and entry
+    /// pools read as zero.
+    pub fn migrate_position(ctx: Context<MigratePosition>) -> Result<()> {
+        instructions::migrate_position::handler(ctx)
+    }
+}
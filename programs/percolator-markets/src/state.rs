@@ -1,4 +1,9 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::system_program;
+
+use crate::errors::PercolatorError;
+use crate::events::HRatioUpdated;
 
 /// ─── Market Account ───────────────────────────────────────────────
 ///
@@ -64,24 +69,305 @@ pub struct Market {
     /// Computed h-ratio (basis points, 0–10000). Set at resolution time.
     pub h_ratio_bps: u16,
 
-    /// Total lamports already paid out during settlement.
+    /// Total lamports already paid out of the vault during settlement,
+    /// early-settlement bonuses included.
     pub settled_amount: u64,
 
     /// Number of individual settlements completed.
     pub settlements_count: u64,
 
+    /// Total winning stake already settled. The market is `Settled` once
+    /// this reaches the winner pool.
+    pub settled_stake: u64,
+
+    // ─── Creator liquidity ───
+    /// Lamports added by the creator via `add_liquidity`. Counts toward
+    /// vault solvency but belongs to neither pool and earns no payout.
+    pub creator_liquidity: u64,
+
+    // ─── Creator subsidy ───
+    /// Fraction of the loser pool the creator matches (basis points).
+    pub subsidy_match_bps: u16,
+
+    /// Subsidy escrowed in the vault at creation, not yet applied or returned.
+    pub creator_subsidy: u64,
+
+    /// Subsidy folded into the distributable profit pool at resolution.
+    pub subsidy_applied: u64,
+
+    /// Number of times the creator has extended the deadline.
+    pub extensions_count: u8,
+
+    /// Emergency freeze set by the protocol authority. Blocks betting and
+    /// resolution; exits (cancel, refund, settle) still work.
+    pub frozen: bool,
+
+    /// Lifetime protocol-fee share paid to the creator from bets on this market.
+    pub creator_fees_earned: u64,
+
+    /// Total stake already refunded from a cancelled market.
+    pub refunded_stake: u64,
+
+    /// `MarketCapFloor` only: latched true once any checkpoint saw the value
+    /// below the floor. Forces a NO outcome.
+    pub floor_breached: bool,
+
+    /// Total crank fees paid out of this market's vault.
+    pub crank_fees_paid: u64,
+
+    /// End of the commit phase for commit-reveal markets (0 = bets are placed
+    /// openly with `place_bet`).
+    pub commit_deadline: i64,
+
+    /// What `settle` does when the vault cannot cover a payout.
+    pub insolvency_mode: InsolvencyMode,
+
+    /// Set once a Merkle settlement root is posted; per-position `settle`
+    /// is disabled from then on.
+    pub merkle_settlement: bool,
+
+    /// Unix timestamp of cancellation (0 unless Cancelled).
+    pub cancelled_at: i64,
+
+    /// Number of positions holding YES.
+    pub yes_positions: u32,
+
+    /// Number of positions holding NO.
+    pub no_positions: u32,
+
+    /// Outcome applied by `resolve_default` once the resolution window ends
+    /// unresolved. `Unresolved` voids (cancels) the market instead.
+    pub default_outcome: Outcome,
+
+    /// Unix timestamp after which `resolve_default` may finalize the market.
+    pub resolution_window_end: i64,
+
+    /// Ring buffer of the last 8 h-ratio computations (bps).
+    pub h_ratio_history: [u16; 8],
+
+    /// Next slot written in `h_ratio_history`.
+    pub h_ratio_cursor: u8,
+
+    /// Whether `resolve` takes its oracle from an `OracleSchedule`.
+    pub has_oracle_schedule: bool,
+
+    /// Whether the market creator is barred from betting on this market.
+    /// Stored inverted so the zero bytes of markets that predate it allow
+    /// betting, as they did before.
+    pub creator_betting_disabled: bool,
+
+    /// Whether the market oracle is barred from betting on this market.
+    pub oracle_betting_disabled: bool,
+
+    /// Unix timestamp of market creation.
+    pub created_at: i64,
+
+    /// Whether the oracle has used its one resolution-window extension.
+    pub resolution_extended: bool,
+
+    /// `Scalar` only: value reported by the oracle at resolution (0 for a
+    /// blended resolution).
+    pub scalar_value: u64,
+
+    /// `Scalar` outcome only: share of the total pool paid to YES holders
+    /// (bps), from `resolve_scalar` or `resolve_blended`; NO holders get the
+    /// complement.
+    pub yes_share_bps: u16,
+
+    /// sha256 of the full question text stored off-chain (all zeros = none).
+    pub question_hash: [u8; 32],
+
+    /// Where the full question text can be fetched (max 128 bytes).
+    pub question_uri: String,
+
+    /// Conditional markets only: the market this one depends on.
+    pub parent_market: Option<Pubkey>,
+
+    /// Outcome the parent must resolve to; any other result voids this market.
+    pub parent_required_outcome: Outcome,
+
+    /// Bonus weight (bps of stake) granted to bets in the early-bird window.
+    pub early_bird_bonus_bps: u16,
+
+    /// Length of the early-bird window after creation (seconds).
+    pub early_bird_window_secs: i64,
+
+    /// Total early-bird bonus stake on YES.
+    pub yes_bonus_stake: u64,
+
+    /// Total early-bird bonus stake on NO.
+    pub no_bonus_stake: u64,
+
+    /// Winning bonus stake already settled.
+    pub settled_bonus_stake: u64,
+
+    /// Lowest h-ratio winners may be paid at (bps, 0 = no floor).
+    pub h_ratio_floor_bps: u16,
+
+    /// Lamports the vault is short of paying winners at the floor. Settlement
+    /// is blocked until `restore_solvency` clears it.
+    pub floor_shortfall: u64,
+
+    /// Share profit by time-weighted stake (`deposit × (deadline − bet_time)`)
+    /// instead of plain stake.
+    pub time_weighted: bool,
+
+    /// Total time-weighted stake on YES.
+    pub yes_weighted_stake: u128,
+
+    /// Total time-weighted stake on NO.
+    pub no_weighted_stake: u128,
+
+    /// Winning time-weighted stake already settled.
+    pub settled_weighted_stake: u128,
+
+    /// Slot of the most recent bet; the oracle cannot resolve in it.
+    pub last_bet_slot: u64,
+
+    /// Rounding of profit shares, copied from config at creation.
+    pub rounding_mode: RoundingMode,
+
+    /// Full (h = 100%) profit claims of positions already settled.
+    pub settled_profit_claim: u64,
+
+    /// Last oracle heartbeat or checkpoint (creation time until the first).
+    pub last_heartbeat: i64,
+
+    /// Market this one hedges with the inverse rule (`None` = not a hedge).
+    /// Informational only: the two resolve independently.
+    pub hedge_of: Option<Pubkey>,
+
+    /// Standard, or no-loss: every deposit is returned and winners share
+    /// only the creator's prize.
+    pub kind: MarketKind,
+
+    /// Largest share of the total pool one side may reach through a bet
+    /// (bps, 10000 = no limit). Enforced once both sides hold stake.
+    pub max_imbalance_bps: u16,
+
+    /// Second oracle whose reading must agree with the primary one before
+    /// `resolve_with_rule` can resolve. `None` = single feed.
+    pub secondary_oracle: Option<Pubkey>,
+
+    /// Largest allowed gap between the two readings, in bps of the larger.
+    pub resolution_tolerance_bps: u16,
+
+    /// Set when the last `resolve_with_rule` attempt found the feeds apart;
+    /// cleared once they agree.
+    pub oracle_disagreement: bool,
+
+    /// Seed the creator committed to at creation, as bps of
+    /// `expected_max_pool` (0 = no commitment). A seeded market cannot
+    /// resolve below `h_ratio_floor_bps`.
+    pub min_seed_ratio_bps: u16,
+
+    /// Total pool the seed was sized for.
+    pub expected_max_pool: u64,
+
+    /// After this time the creator may sweep what unsettled winners are
+    /// still owed (0 = winners can claim indefinitely).
+    pub claim_deadline: i64,
+
+    /// Lamports swept to the creator by `creator_sweep_unclaimed`.
+    pub swept_unclaimed: u64,
+
+    /// `PriceTarget` only: `target_value` is USD × 10^`price_exponent`.
+    /// Feed prices are rescaled to it before comparison.
+    pub price_exponent: i32,
+
+    /// Which resolution path set the outcome (`Unresolved` until then).
+    pub resolved_via: ResolutionSource,
+
+    /// YES stake released by `reconcile_position` lowering a position to its
+    /// token balance, and not yet taken up by one raised to it.
+    pub yes_reconcile_slack: u64,
+
+    /// Same as `yes_reconcile_slack`, for NO.
+    pub no_reconcile_slack: u64,
+
+    /// Refunds burn and pay out the user's whole token balance instead of
+    /// `position.deposited`, so transferred tokens carry their refund.
+    pub refund_by_token_balance: bool,
+
+    /// Smallest pool either side must hold for the market to resolve
+    /// (0 = no minimum). Thinner markets are cancelled instead.
+    pub min_pool_per_side: u64,
+
+    /// Unix timestamp of resolution (0 until resolved). Settlement
+    /// incentives are timed from it.
+    pub resolved_at: i64,
+
+    /// Creator share of bet fees held in the creator-revenue PDA, not yet
+    /// withdrawn.
+    pub creator_revenue: u64,
+
+    /// Share of each bet (bps of the gross amount) set aside for whoever
+    /// resolves the market.
+    pub resolution_bounty_bps: u16,
+
+    /// Bounty accrued in the vault, paid to the resolver at resolution.
+    pub resolution_bounty: u64,
+
+    /// Anyone may resolve the market from `resolution_feed` once the
+    /// deadline passes, through `trigger_resolution`.
+    pub resolve_at_deadline: bool,
+
+    /// Oracle reading `trigger_resolution` reads: the primary oracle's
+    /// `OracleReading` for `token_mint`. Default when `resolve_at_deadline`
+    /// is off.
+    pub resolution_feed: Pubkey,
+
+    /// Every settlement creates a `SettlementReceipt` for the position.
+    /// `settle_many` and Merkle settlement are refused.
+    pub settlement_receipts: bool,
+
+    /// Each position is a single NFT with Token Metadata instead of YES/NO
+    /// tokens. A position takes one bet, settles only through `settle`
+    /// (which burns the NFT) and is refunded only through `claim_refund`.
+    pub position_nfts: bool,
+
+    /// Early-settlement bonuses paid out of vault surplus. Counted in
+    /// `settled_amount` but owed to no one, so claims exclude them.
+    pub settlement_bonuses: u64,
+
+    /// Account layout version: `Market::VERSION` for markets created by
+    /// this program version, `Market::MIGRATED_VERSION` for older markets
+    /// brought up to date by `migrate_market`, 0 for older markets not yet
+    /// migrated.
+    pub version: u8,
+
     /// Reserved space for future upgrades.
-    pub _reserved: [u8; 128],
+    pub _reserved: [u8; 1],
 }
 
 impl Market {
+    /// Question bytes allocated in every market account.
+    pub const MAX_QUESTION_LEN: usize = 256;
+
+    /// Bytes allocated for `question_uri`.
+    pub const MAX_QUESTION_URI_LEN: usize = 128;
+
+    /// Version of a market from before versioning once `migrate_market`
+    /// has grown it. Like version 0, it predates creator stats and per-side
+    /// position counts.
+    pub const MIGRATED_VERSION: u8 = 1;
+
+    /// Layout version stamped on new markets.
+    pub const VERSION: u8 = 2;
+
+    /// Largest `resolution_bounty_bps` a market may set (5%).
+    pub const MAX_RESOLUTION_BOUNTY_BPS: u16 = 500;
+
+    /// Token Metadata symbol of position NFTs.
+    pub const POSITION_NFT_SYMBOL: &'static str = "PERC";
+
     /// Account size for Anchor allocation.
     pub const SIZE: usize = 8  // discriminator
         + 8                     // market_id
         + 32                    // creator
         + 32                    // oracle
-        + (4 + 256)             // question (String: 4-byte len + max 256 chars)
-        + 1                     // rule
+        + (4 + Self::MAX_QUESTION_LEN) // question (String: 4-byte len + max 256 chars)
+        + (1 + 16)              // rule (tag + Scalar bounds)
         + 8                     // target_value
         + 32                    // token_mint
         + 8                     // deadline
@@ -97,7 +383,307 @@ impl Market {
         + 2                     // h_ratio_bps
         + 8                     // settled_amount
         + 8                     // settlements_count
-        + 128;                  // reserved
+        + 8                     // settled_stake
+        + 8                     // creator_liquidity
+        + 2                     // subsidy_match_bps
+        + 8                     // creator_subsidy
+        + 8                     // subsidy_applied
+        + 1                     // extensions_count
+        + 1                     // frozen
+        + 8                     // creator_fees_earned
+        + 8                     // refunded_stake
+        + 1                     // floor_breached
+        + 8                     // crank_fees_paid
+        + 8                     // commit_deadline
+        + 1                     // insolvency_mode
+        + 1                     // merkle_settlement
+        + 8                     // cancelled_at
+        + 4                     // yes_positions
+        + 4                     // no_positions
+        + 1                     // default_outcome
+        + 8                     // resolution_window_end
+        + 16                    // h_ratio_history
+        + 1                     // h_ratio_cursor
+        + 1                     // has_oracle_schedule
+        + 1                     // creator_betting_disabled
+        + 1                     // oracle_betting_disabled
+        + 8                     // created_at
+        + 1                     // resolution_extended
+        + 8                     // scalar_value
+        + 2                     // yes_share_bps
+        + 32                    // question_hash
+        + (4 + Self::MAX_QUESTION_URI_LEN) // question_uri
+        + (1 + 32)              // parent_market
+        + 1                     // parent_required_outcome
+        + 2                     // early_bird_bonus_bps
+        + 8                     // early_bird_window_secs
+        + 8                     // yes_bonus_stake
+        + 8                     // no_bonus_stake
+        + 8                     // settled_bonus_stake
+        + 2                     // h_ratio_floor_bps
+        + 8                     // floor_shortfall
+        + 1                     // time_weighted
+        + 16                    // yes_weighted_stake
+        + 16                    // no_weighted_stake
+        + 16                    // settled_weighted_stake
+        + 8                     // last_bet_slot
+        + 1                     // rounding_mode
+        + 8                     // settled_profit_claim
+        + 8                     // last_heartbeat
+        + (1 + 32)              // hedge_of
+        + 1                     // kind
+        + 2                     // max_imbalance_bps
+        + (1 + 32)              // secondary_oracle
+        + 2                     // resolution_tolerance_bps
+        + 1                     // oracle_disagreement
+        + 2                     // min_seed_ratio_bps
+        + 8                     // expected_max_pool
+        + 8                     // claim_deadline
+        + 8                     // swept_unclaimed
+        + 4                     // price_exponent
+        + 1                     // resolved_via
+        + 8                     // yes_reconcile_slack
+        + 8                     // no_reconcile_slack
+        + 1                     // refund_by_token_balance
+        + 8                     // min_pool_per_side
+        + 8                     // resolved_at
+        + 8                     // creator_revenue
+        + 2                     // resolution_bounty_bps
+        + 8                     // resolution_bounty
+        + 1                     // resolve_at_deadline
+        + 32                    // resolution_feed
+        + 1                     // settlement_receipts
+        + 1                     // position_nfts
+        + 8                     // settlement_bonuses
+        + 1                     // version
+        + 1;                    // reserved
+
+    /// Whether a market may move from status `from` to `to`.
+    ///
+    /// ```text
+    ///   Open     → Closed | Resolved | Cancelled
+    ///   Closed   → Resolved | Cancelled
+    ///   Resolved → Settled | Cancelled (force-cancel)
+    ///   Cancelled, Settled: terminal
+    /// ```
+    pub fn can_transition(from: MarketStatus, to: MarketStatus) -> bool {
+        use MarketStatus::*;
+        matches!(
+            (from, to),
+            (Open, Closed | Resolved | Cancelled)
+                | (Closed, Resolved | Cancelled)
+                | (Resolved, Settled | Cancelled)
+        )
+    }
+
+    /// Move to status `to`. Every status change after creation goes through
+    /// here.
+    pub fn transition(&mut self, to: MarketStatus) -> Result<()> {
+        require!(
+            Self::can_transition(self.status, to),
+            PercolatorError::InvalidStatusTransition
+        );
+        self.status = to;
+        Ok(())
+    }
+
+    /// Total stake on the winning side (0 while unresolved). A scalar
+    /// outcome pays both sides, so this is the whole pool.
+    pub fn winner_pool(&self) -> u64 {
+        match self.outcome {
+            Outcome::Yes => self.yes_pool,
+            Outcome::No => self.no_pool,
+            Outcome::Scalar => self.yes_pool.saturating_add(self.no_pool),
+            Outcome::Unresolved => 0,
+        }
+    }
+
+    /// Part of `subsidy_applied` not yet paid out to settled winners,
+    /// pro rata to the winning stake still unsettled.
+    pub fn unpaid_subsidy(&self) -> u64 {
+        let winner_pool = self.winner_pool();
+        if winner_pool == 0 {
+            return self.subsidy_applied;
+        }
+        let unsettled = winner_pool.saturating_sub(self.settled_stake);
+        (self.subsidy_applied as u128 * unsettled as u128 / winner_pool as u128) as u64
+    }
+
+    /// Vault lamports beyond what unsettled winners are owed at h, the
+    /// creator's liquidity and escrowed subsidy, and `rent_min`: the only
+    /// funds an early-settlement bonus may draw on.
+    ///
+    /// Always 0 in a no-loss market: losers' capital stays in the vault until
+    /// `close_losing_position` returns it, and nothing records how much has
+    /// gone back, so no surplus can be told apart from it.
+    pub fn incentive_surplus(&self, vault_balance: u64, rent_min: u64) -> Result<u64> {
+        if self.kind == MarketKind::NoLoss {
+            return Ok(0);
+        }
+        Ok(vault_balance
+            .saturating_sub(self.owed_at_h()?)
+            .saturating_sub(self.creator_liquidity)
+            .saturating_sub(self.creator_subsidy)
+            .saturating_sub(rent_min))
+    }
+
+    /// Mint holding `position`'s tokens: its NFT in a `position_nfts`
+    /// market, otherwise the `side` mint.
+    pub fn position_mint(&self, position: &Pubkey, side: BetSide) -> Pubkey {
+        if self.position_nfts {
+            return UserPosition::nft_mint(position);
+        }
+        match side {
+            BetSide::Yes => self.yes_mint,
+            BetSide::No => self.no_mint,
+        }
+    }
+
+    /// Part of a bet's gross `amount` set aside for the resolution bounty.
+    pub fn bounty_share(&self, amount: u64) -> u64 {
+        (amount as u128 * self.resolution_bounty_bps as u128 / 10_000) as u64
+    }
+
+    /// Whether either side's pool is below `min_pool_per_side`.
+    pub fn is_side_too_thin(&self) -> bool {
+        self.min_pool_per_side > 0
+            && (self.yes_pool < self.min_pool_per_side || self.no_pool < self.min_pool_per_side)
+    }
+
+    /// Reconcile slack on the winning side: stake no position claims, so
+    /// settlement completes without it.
+    pub fn winner_reconcile_slack(&self) -> u64 {
+        match self.outcome {
+            Outcome::Yes => self.yes_reconcile_slack,
+            Outcome::No => self.no_reconcile_slack,
+            Outcome::Scalar => self.yes_reconcile_slack.saturating_add(self.no_reconcile_slack),
+            Outcome::Unresolved => 0,
+        }
+    }
+
+    /// Early-bird bonus stake on the winning side (0 while unresolved).
+    pub fn winner_bonus_pool(&self) -> u64 {
+        match self.outcome {
+            Outcome::Yes => self.yes_bonus_stake,
+            Outcome::No => self.no_bonus_stake,
+            Outcome::Scalar | Outcome::Unresolved => 0,
+        }
+    }
+
+    /// Time-weighted stake on the winning side (0 while unresolved).
+    pub fn winner_weighted_pool(&self) -> u128 {
+        match self.outcome {
+            Outcome::Yes => self.yes_weighted_stake,
+            Outcome::No => self.no_weighted_stake,
+            Outcome::Scalar | Outcome::Unresolved => 0,
+        }
+    }
+
+    /// Whether `yes_positions`/`no_positions` count every position. Markets
+    /// from before versioning only count positions opened since.
+    pub fn tracks_positions(&self) -> bool {
+        self.version >= Market::VERSION
+    }
+
+    /// Fill in the fields a market from before versioning left zeroed, once
+    /// `migrate_market` has grown it to the current layout.
+    ///
+    /// Such a market keeps its old rules: no imbalance cap, no default
+    /// resolution, untracked position counts. Settled and refunded stake
+    /// are recovered from the old payout rule, under which a refund
+    /// returned the deposit and a winner was paid
+    /// `stake × (1 + loser_pool / winner_pool × h)`. Rounding makes the
+    /// settled-stake estimate err low, leaving remaining winners reserved for.
+    pub fn migrate_legacy(&mut self, vault_balance: u64, now: i64) -> Result<()> {
+        self.max_imbalance_bps = 10_000;
+        self.price_exponent = DEFAULT_PRICE_EXPONENT;
+        self.resolution_window_end = i64::MAX;
+        self.last_heartbeat = now;
+
+        match self.status {
+            MarketStatus::Resolved => {
+                let winner_pool = self.winner_pool() as u128;
+                let loser_pool = self.loser_profit_pool();
+                let weight = winner_pool * 10_000;
+                let denominator = weight + loser_pool * self.h_ratio_bps as u128;
+                if denominator > 0 {
+                    let settled_stake = mul_div(self.settled_amount as u128, weight, denominator)?;
+                    self.settled_stake = to_u64(settled_stake.min(winner_pool))?;
+                    self.settled_profit_claim = to_u64(self.profit_claim(self.settled_stake, 0, 0)?)?;
+                }
+                self.resolved_at = now;
+            }
+            MarketStatus::Cancelled => {
+                self.refunded_stake = self.yes_pool
+                    .saturating_add(self.no_pool)
+                    .saturating_sub(vault_balance);
+                self.cancelled_at = now;
+            }
+            _ => {}
+        }
+
+        self.version = Market::MIGRATED_VERSION;
+        Ok(())
+    }
+
+    /// Drop a position from `side`'s count. Untracked counts floor at 0,
+    /// since the position may predate them.
+    pub fn remove_position(&mut self, side: BetSide) -> Result<()> {
+        let tracked = self.tracks_positions();
+        let count = match side {
+            BetSide::Yes => &mut self.yes_positions,
+            BetSide::No => &mut self.no_positions,
+        };
+        *count = if tracked {
+            count.checked_sub(1).ok_or(PercolatorError::Overflow)?
+        } else {
+            count.saturating_sub(1)
+        };
+        Ok(())
+    }
+
+    /// Number of positions on the winning side (0 while unresolved).
+    pub fn winning_positions(&self) -> u32 {
+        match self.outcome {
+            Outcome::Yes => self.yes_positions,
+            Outcome::No => self.no_positions,
+            Outcome::Scalar => self.yes_positions.saturating_add(self.no_positions),
+            Outcome::Unresolved => 0,
+        }
+    }
+
+    /// Whether a bet on `side` is paid out under the resolved outcome.
+    pub fn is_winning_side(&self, side: BetSide) -> bool {
+        matches!(
+            (self.outcome, side),
+            (Outcome::Yes, BetSide::Yes) | (Outcome::No, BetSide::No) | (Outcome::Scalar, _)
+        )
+    }
+
+    /// Portion of the escrowed subsidy the creator owes winners:
+    /// min(creator_subsidy, loser_pool × subsidy_match_bps / 10000).
+    ///
+    /// Zero when nobody backed the winning side.
+    pub fn matched_subsidy(&self) -> u64 {
+        let loser_pool = match self.outcome {
+            Outcome::Yes => self.no_pool,
+            Outcome::No => self.yes_pool,
+            // Nobody loses outright, so there is nothing to match
+            Outcome::Scalar | Outcome::Unresolved => return 0,
+        };
+
+        if self.winner_pool() == 0 {
+            return 0;
+        }
+
+        // The whole escrow is the no-loss prize
+        if self.kind == MarketKind::NoLoss {
+            return self.creator_subsidy;
+        }
+
+        let matched = (loser_pool as u128 * self.subsidy_match_bps as u128) / 10_000;
+        (matched as u64).min(self.creator_subsidy)
+    }
 
     /// Compute h-ratio at resolution time.
     ///
@@ -112,12 +698,14 @@ impl Market {
         let winner_pool = match self.outcome {
             Outcome::Yes => self.yes_pool,
             Outcome::No => self.no_pool,
+            Outcome::Scalar => self.yes_pool.saturating_add(self.no_pool),
             Outcome::Unresolved => return 10_000,
         };
 
         let loser_pool = match self.outcome {
             Outcome::Yes => self.no_pool,
             Outcome::No => self.yes_pool,
+            Outcome::Scalar => 0,
             Outcome::Unresolved => return 10_000,
         };
 
@@ -125,8 +713,11 @@ impl Market {
             return 10_000;
         }
 
-        // Total claims = winner stakes + loser pool (the profit to distribute)
-        let total_claims = winner_pool.saturating_add(loser_pool);
+        // Total claims = winner stakes + loser pool + matched subsidy
+        // (the profit to distribute)
+        let total_claims = winner_pool
+            .saturating_add(loser_pool)
+            .saturating_add(self.subsidy_applied);
 
         if vault_balance >= total_claims {
             10_000 // fully solvent
@@ -136,103 +727,801 @@ impl Market {
         }
     }
 
+    /// Lamports `vault_balance` is short of an h-ratio at `h_ratio_floor_bps`
+    /// (0 when there is no floor or the vault reaches it).
+    pub fn floor_shortfall_for(&self, vault_balance: u64) -> u64 {
+        if self.h_ratio_floor_bps == 0 || self.winner_pool() == 0 {
+            return 0;
+        }
+
+        // Same total as `compute_h_ratio`: every stake plus the matched subsidy
+        let total_claims = self.yes_pool as u128 + self.no_pool as u128 + self.subsidy_applied as u128;
+        let required = (total_claims * self.h_ratio_floor_bps as u128).div_ceil(10_000);
+        (required as u64).saturating_sub(vault_balance)
+    }
+
+    /// Reject a bet of `stake` on `side` that would lift that side above
+    /// `max_imbalance_bps` of the total pool. Not enforced until the other
+    /// side holds stake, so the first bets can always open the market.
+    pub fn check_imbalance(&self, side: BetSide, stake: u64) -> Result<()> {
+        let (own, other) = match side {
+            BetSide::Yes => (self.yes_pool, self.no_pool),
+            BetSide::No => (self.no_pool, self.yes_pool),
+        };
+        if self.max_imbalance_bps >= 10_000 || other == 0 {
+            return Ok(());
+        }
+
+        let own = own as u128 + stake as u128;
+        let total = own + other as u128;
+        require!(
+            own * 10_000 <= total * self.max_imbalance_bps as u128,
+            PercolatorError::MarketTooImbalanced
+        );
+        Ok(())
+    }
+
+    /// Smallest `creator_subsidy` that meets a seed commitment of
+    /// `min_seed_ratio_bps` on a pool of `expected_max_pool`.
+    pub fn required_seed(expected_max_pool: u64, min_seed_ratio_bps: u16) -> Result<u64> {
+        let seed = (expected_max_pool as u128 * min_seed_ratio_bps as u128).div_ceil(10_000);
+        u64::try_from(seed).map_err(|_| error!(PercolatorError::Overflow))
+    }
+
+    /// Whether two observed values are within `resolution_tolerance_bps` of
+    /// each other, measured against the larger.
+    pub fn readings_agree(&self, a: u64, b: u64) -> bool {
+        let gap = a.abs_diff(b) as u128;
+        gap * 10_000 <= a.max(b) as u128 * self.resolution_tolerance_bps as u128
+    }
+
+    /// Reject bets from the creator or oracle when the market disallows them.
+    pub fn check_bettor(&self, bettor: &Pubkey) -> Result<()> {
+        require!(
+            !self.creator_betting_disabled || *bettor != self.creator,
+            PercolatorError::CreatorCannotBet
+        );
+        require!(
+            !self.oracle_betting_disabled || *bettor != self.oracle,
+            PercolatorError::OracleCannotBet
+        );
+        Ok(())
+    }
+
+    /// Reject oracle resolution before `created_at + min_resolution_delay_secs`.
+    pub fn check_resolution_delay(&self, config: &GlobalConfig, now: i64) -> Result<()> {
+        let earliest = self.created_at.saturating_add(config.min_resolution_delay_secs);
+        require!(now >= earliest, PercolatorError::ResolvedTooSoon);
+        Ok(())
+    }
+
+    /// Reject oracle resolution in the slot of the latest bet, so a bet and
+    /// its resolution can never land together.
+    pub fn check_bet_slot(&self, slot: u64) -> Result<()> {
+        require!(slot != self.last_bet_slot, PercolatorError::ResolveSameSlotAsBet);
+        Ok(())
+    }
+
+    /// Reject automated resolution once the oracle has missed its heartbeat
+    /// for longer than `config.heartbeat_timeout_secs` (0 = no check). Such a
+    /// market can only be finalized by `resolve_default`.
+    pub fn check_heartbeat(&self, config: &GlobalConfig, now: i64) -> Result<()> {
+        let timeout = config.heartbeat_timeout_secs;
+        require!(
+            timeout == 0 || now.saturating_sub(self.last_heartbeat) <= timeout,
+            PercolatorError::OracleHeartbeatStale
+        );
+        Ok(())
+    }
+
+    /// For a conditional market, whether `parent` resolved the required way.
+    ///
+    /// `Ok(true)` when the market has no parent or the condition holds;
+    /// `Ok(false)` when the parent resolved otherwise or was cancelled, so
+    /// this market must be voided. Fails while the parent is undecided.
+    pub fn parent_condition_met(&self, parent: Option<&Account<Market>>) -> Result<bool> {
+        let Some(parent_key) = self.parent_market else {
+            return Ok(true);
+        };
+        let parent = parent.ok_or(PercolatorError::InvalidParentMarket)?;
+        require_keys_eq!(parent.key(), parent_key, PercolatorError::InvalidParentMarket);
+
+        match parent.status {
+            MarketStatus::Resolved | MarketStatus::Settled => {
+                Ok(parent.outcome == self.parent_required_outcome)
+            }
+            MarketStatus::Cancelled => Ok(false),
+            MarketStatus::Open | MarketStatus::Closed => err!(PercolatorError::ParentNotResolved),
+        }
+    }
+
+    /// Oracle allowed to resolve at `now`: the latest schedule entry already
+    /// in effect, else `oracle`.
+    pub fn active_oracle(&self, schedule: Option<&OracleSchedule>, now: i64) -> Result<Pubkey> {
+        match schedule {
+            Some(schedule) => Ok(schedule.active_oracle(now).unwrap_or(self.oracle)),
+            None => {
+                require!(!self.has_oracle_schedule, PercolatorError::MissingOracleSchedule);
+                Ok(self.oracle)
+            }
+        }
+    }
+
+    /// Set `h_ratio_bps`, recording the value in `h_ratio_history` and
+    /// emitting `HRatioUpdated`.
+    ///
+    /// Every recomputation goes through here so the history stays complete.
+    pub fn set_h_ratio(&mut self, market_key: Pubkey, h_ratio_bps: u16) {
+        let old_h_ratio_bps = self.h_ratio_bps;
+        self.h_ratio_bps = h_ratio_bps;
+
+        let slot = self.h_ratio_cursor as usize % self.h_ratio_history.len();
+        self.h_ratio_history[slot] = h_ratio_bps;
+        self.h_ratio_cursor = ((slot + 1) % self.h_ratio_history.len()) as u8;
+
+        emit!(HRatioUpdated {
+            market: market_key,
+            market_id: self.market_id,
+            old_h_ratio_bps,
+            new_h_ratio_bps: h_ratio_bps,
+        });
+    }
+
     /// Calculate payout for a winning position.
     ///
     /// payout = capital + profit × h
     ///
     /// Where:
     ///   capital = user_stake (senior claim, returned first)
-    ///   profit  = (user_stake / winner_pool) × loser_pool (junior claim)
+    ///   profit  = (user_stake / winner_pool) × loser_pool
+    ///           + (effective_stake / effective_pool) × subsidy (junior claim)
     ///   h       = h_ratio_bps / 10000
-    pub fn calculate_payout(&self, user_stake: u64) -> u64 {
-        let winner_pool = match self.outcome {
-            Outcome::Yes => self.yes_pool,
-            Outcome::No => self.no_pool,
-            Outcome::Unresolved => return 0,
-        };
+    ///
+    /// `effective_stake` adds the position's early-bird `bonus_stake`, so the
+    /// bonus is paid out of the creator subsidy only; capital is always the
+    /// actual deposit. In `time_weighted` markets the whole profit term is
+    /// `(weighted_stake / winner_weighted_pool) × (loser_pool + subsidy)`.
+    /// Profit rounds per `rounding_mode`; see `position_profit_claim`.
+    /// For a scalar or blended outcome see `scalar_breakdown`.
+    ///
+    /// Fails with `Overflow` rather than returning a wrong amount.
+    pub fn calculate_payout(&self, position: &UserPosition) -> Result<u64> {
+        Ok(self.payout_breakdown(position)?.total)
+    }
 
-        let loser_pool = match self.outcome {
-            Outcome::Yes => self.no_pool,
-            Outcome::No => self.yes_pool,
-            Outcome::Unresolved => return 0,
-        };
+    /// Same as `calculate_payout`, split into its capital and profit claims.
+    pub fn payout_breakdown(&self, position: &UserPosition) -> Result<PayoutBreakdown> {
+        if self.outcome == Outcome::Scalar {
+            return Ok(self.scalar_breakdown(position.side, position.deposited));
+        }
 
-        if winner_pool == 0 {
-            return 0;
+        if self.winner_pool() == 0 {
+            return Ok(PayoutBreakdown::default());
         }
 
         // Capital: senior claim (returned in full up to vault capacity)
-        let capital = user_stake;
+        let capital = position.deposited;
 
-        // Profit: junior claim = proportional share of loser pool
-        let profit = (user_stake as u128)
-            .checked_mul(loser_pool as u128)
-            .unwrap_or(0)
-            / winner_pool as u128;
+        // Profit: junior claim = proportional share of loser pool plus any
+        // creator subsidy matched at resolution
+        let profit = self.position_profit_claim(position)?;
 
         // Apply h-ratio haircut to profit
-        let profit_after_h = (profit * self.h_ratio_bps as u128) / 10_000;
-
-        capital.saturating_add(profit_after_h as u64)
+        let profit_after_h = to_u64(mul_div(profit, self.h_ratio_bps as u128, 10_000)?)?;
+
+        Ok(PayoutBreakdown {
+            capital,
+            profit: profit_after_h,
+            fee: 0,
+            total: capital.checked_add(profit_after_h).ok_or(PercolatorError::Overflow)?,
+        })
     }
-}
 
-/// ─── Market Rule ──────────────────────────────────────────────────
-///
-/// Determines how the market is resolved.
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
-pub enum MarketRule {
-    /// Token reaches a target market cap (in USD × 10^6).
-    #[default]
-    MarketCapTarget,
+    /// Payout under a scalar outcome.
+    ///
+    /// YES holders split `total_pool × yes_share_bps / 10000` pro rata, NO
+    /// holders split the rest. Whatever exceeds the stake is profit and
+    /// takes the h haircut; a payout below the stake is all capital.
+    fn scalar_breakdown(&self, side: BetSide, user_stake: u64) -> PayoutBreakdown {
+        let (side_pool, share_bps) = match side {
+            BetSide::Yes => (self.yes_pool, self.yes_share_bps),
+            BetSide::No => (self.no_pool, 10_000 - self.yes_share_bps),
+        };
+        if side_pool == 0 {
+            return PayoutBreakdown::default();
+        }
 
-    /// Token reaches a target price (in USD × 10^9, 9 decimal places).
-    PriceTarget,
+        let total_pool = self.yes_pool.saturating_add(self.no_pool) as u128;
+        let side_share = total_pool * share_bps as u128 / 10_000;
+        let gross = (user_stake as u128 * side_share / side_pool as u128) as u64;
 
-    /// Token maintains a minimum market cap throughout the period.
-    MarketCapFloor,
+        let capital = gross.min(user_stake);
+        let profit = ((gross - capital) as u128 * self.h_ratio_bps as u128 / 10_000) as u64;
 
-    /// Custom condition resolved by oracle authority.
-    OracleCustom,
-}
+        PayoutBreakdown {
+            capital,
+            profit,
+            fee: 0,
+            total: capital.saturating_add(profit),
+        }
+    }
 
-/// ─── Market Status ────────────────────────────────────────────────
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
-pub enum MarketStatus {
-    /// Market is accepting bets.
-    #[default]
-    Open,
+    /// Full (h = 100%) profit claim of `position`, rounded per
+    /// `rounding_mode` and capped at what the profit pool has left, so the
+    /// claims of all winners never sum past it in any mode.
+    pub fn position_profit_claim(&self, position: &UserPosition) -> Result<u128> {
+        let claim = self.profit_claim(
+            position.deposited,
+            position.bonus_stake,
+            position.weighted_stake,
+        )?;
+        let pool = self.loser_profit_pool() + self.subsidy_applied as u128;
+        Ok(claim.min(pool.saturating_sub(self.settled_profit_claim as u128)))
+    }
 
-    /// Market deadline passed, awaiting resolution.
-    Closed,
+    /// Full (h = 100%) profit claim of `stake` winning stake carrying
+    /// `bonus_stake` early-bird weight.
+    ///
+    /// The loser pool is shared by actual stake; the matched subsidy by
+    /// effective stake (stake + bonus), which is what funds the bonus.
+    /// Time-weighted markets share both by `weighted_stake` instead.
+    fn profit_claim(&self, stake: u64, bonus_stake: u64, weighted_stake: u128) -> Result<u128> {
+        let winner_pool = self.winner_pool() as u128;
+        if winner_pool == 0 {
+            return Ok(0);
+        }
 
-    /// Outcome has been determined; settlement in progress.
-    Resolved,
+        let loser_pool = self.loser_profit_pool();
+        let round = self.rounding_mode;
+
+        if self.time_weighted {
+            let weighted_pool = self.winner_weighted_pool();
+            if weighted_pool == 0 {
+                return Ok(0);
+            }
+            let distributable = loser_pool + self.subsidy_applied as u128;
+            let numerator = weighted_stake.checked_mul(distributable)
+                .ok_or(PercolatorError::Overflow)?;
+            return Ok(round.div(numerator, weighted_pool));
+        }
+        let effective_pool = winner_pool + self.winner_bonus_pool() as u128;
+        let effective_stake = stake as u128 + bonus_stake as u128;
+
+        let loser_share = (stake as u128).checked_mul(loser_pool)
+            .ok_or(PercolatorError::Overflow)?;
+        let subsidy_share = effective_stake.checked_mul(self.subsidy_applied as u128)
+            .ok_or(PercolatorError::Overflow)?;
+        round.div(loser_share, winner_pool)
+            .checked_add(round.div(subsidy_share, effective_pool))
+            .ok_or(PercolatorError::Overflow.into())
+    }
 
-    /// Market was cancelled; refunds available.
-    Cancelled,
+    /// Losing stake winners share as profit. Zero in a no-loss market, where
+    /// losers take their capital back.
+    fn loser_profit_pool(&self) -> u128 {
+        if self.kind == MarketKind::NoLoss {
+            return 0;
+        }
+        (self.yes_pool as u128 + self.no_pool as u128) - self.winner_pool() as u128
+    }
 
-    /// All settlements complete.
-    Settled,
-}
+    /// Capital plus full (h = 100%) profit still owed to unsettled winners.
+    pub fn outstanding_claims(&self) -> Result<u64> {
+        // A scalar outcome redistributes the whole pool
+        if self.outcome == Outcome::Scalar {
+            let settled_claims = self.settled_amount.saturating_sub(self.settlement_bonuses);
+            return Ok(self.winner_pool().saturating_sub(settled_claims));
+        }
 
-/// ─── Outcome ──────────────────────────────────────────────────────
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
-pub enum Outcome {
-    #[default]
-    Unresolved,
-    Yes,
-    No,
-}
+        let winner_pool = self.winner_pool();
+        if winner_pool == 0 {
+            return Ok(0);
+        }
 
-/// ─── Bet Side ─────────────────────────────────────────────────────
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum BetSide {
-    Yes,
-    No,
-}
+        let remaining_stake = winner_pool.saturating_sub(self.settled_stake);
+        let remaining_bonus = self.winner_bonus_pool().saturating_sub(self.settled_bonus_stake);
+        let remaining_weighted = self.winner_weighted_pool()
+            .saturating_sub(self.settled_weighted_stake);
+        let remaining_profit = self.profit_claim(remaining_stake, remaining_bonus, remaining_weighted)?;
+        remaining_stake.checked_add(to_u64(remaining_profit)?)
+            .ok_or(PercolatorError::Overflow.into())
+    }
 
-/// ─── User Position ────────────────────────────────────────────────
+    /// What unsettled winners would be paid at the current h-ratio: capital
+    /// in full plus profit after the haircut.
+    ///
+    /// A scalar outcome is reported at h = 100%, an upper bound.
+    pub fn owed_at_h(&self) -> Result<u64> {
+        let full = self.outstanding_claims()?;
+        if self.outcome == Outcome::Scalar {
+            return Ok(full);
+        }
+
+        let remaining_stake = self.winner_pool().saturating_sub(self.settled_stake).min(full);
+        let profit = (full - remaining_stake) as u128 * self.h_ratio_bps as u128 / 10_000;
+        Ok(remaining_stake + profit as u64)
+    }
+
+    /// Payout recomputed against the live vault, for `InsolvencyMode::Haircut`.
+    ///
+    /// A fresh h is derived from the vault and the winning claims still
+    /// outstanding (this position included). Capital stays senior: profit is
+    /// cut first, and capital is only scaled down once the vault cannot cover
+    /// the remaining stakes. Returns the breakdown and the h applied, which
+    /// never exceeds the current `h_ratio_bps`.
+    ///
+    /// A scalar payout is scaled as a whole by vault / outstanding claims.
+    pub fn haircut_breakdown(
+        &self,
+        position: &UserPosition,
+        vault_balance: u64,
+    ) -> Result<(PayoutBreakdown, u16)> {
+        let user_stake = position.deposited;
+        if self.outcome == Outcome::Scalar {
+            let full = self.scalar_breakdown(position.side, user_stake);
+            let outstanding = self.outstanding_claims()?.max(full.total) as u128;
+            let h = (vault_balance as u128 * 10_000)
+                .checked_div(outstanding)
+                .map_or(10_000, |h| h.min(10_000) as u16)
+                .min(self.h_ratio_bps);
+            let total = (full.total as u128 * h as u128 / 10_000) as u64;
+            let capital = total.min(full.capital);
+            let breakdown = PayoutBreakdown {
+                capital,
+                profit: total - capital,
+                fee: 0,
+                total,
+            };
+            return Ok((breakdown, h));
+        }
+
+        let winner_pool = self.winner_pool();
+        if winner_pool == 0 {
+            return Ok((PayoutBreakdown::default(), self.h_ratio_bps));
+        }
+
+        let remaining_stake = winner_pool.saturating_sub(self.settled_stake).max(user_stake);
+        let remaining_bonus = self.winner_bonus_pool()
+            .saturating_sub(self.settled_bonus_stake)
+            .max(position.bonus_stake);
+        let remaining_weighted = self.winner_weighted_pool()
+            .saturating_sub(self.settled_weighted_stake)
+            .max(position.weighted_stake);
+        let remaining_profit = self.profit_claim(remaining_stake, remaining_bonus, remaining_weighted)?;
+
+        // Fresh h over the profit still owed, after covering remaining capital
+        let fresh_h = if vault_balance <= remaining_stake {
+            0
+        } else {
+            let surplus = (vault_balance - remaining_stake) as u128;
+            (surplus * 10_000)
+                .checked_div(remaining_profit)
+                .map_or(10_000, |h| h.min(10_000) as u16)
+        };
+        let h = fresh_h.min(self.h_ratio_bps);
+
+        let capital = if vault_balance >= remaining_stake {
+            user_stake
+        } else {
+            (user_stake as u128 * vault_balance as u128 / remaining_stake as u128) as u64
+        };
+        let profit = to_u64(mul_div(self.position_profit_claim(position)?, h as u128, 10_000)?)?;
+
+        let breakdown = PayoutBreakdown {
+            capital,
+            profit,
+            fee: 0,
+            total: capital.checked_add(profit).ok_or(PercolatorError::Overflow)?,
+        };
+        Ok((breakdown, h))
+    }
+
+    /// What `settle` would pay `position` now from a vault holding
+    /// `vault_balance`, before any crank fee, and the h applied.
+    ///
+    /// A short vault fails with `VaultInsolvency` in `Revert` mode; in
+    /// `Haircut` mode it lowers h, down to `h_ratio_floor_bps`.
+    pub fn settlement_breakdown(
+        &self,
+        position: &UserPosition,
+        vault_balance: u64,
+    ) -> Result<(PayoutBreakdown, u16)> {
+        let breakdown = self.payout_breakdown(position)?;
+        if breakdown.total <= vault_balance {
+            return Ok((breakdown, self.h_ratio_bps));
+        }
+
+        match self.insolvency_mode {
+            InsolvencyMode::Revert => err!(PercolatorError::VaultInsolvency),
+            InsolvencyMode::Haircut => {
+                let (haircut, h) = self.haircut_breakdown(position, vault_balance)?;
+                require!(h >= self.h_ratio_floor_bps, PercolatorError::HRatioBelowFloor);
+                Ok((haircut, h))
+            }
+        }
+    }
+
+    /// Stake a cancelled-market refund is based on: the holder's token
+    /// balance with `refund_by_token_balance`, else `deposited`.
+    pub fn refund_stake(&self, deposited: u64, token_balance: u64) -> u64 {
+        if self.refund_by_token_balance {
+            token_balance
+        } else {
+            deposited
+        }
+    }
+
+    /// Calculate the refund for a cancelled-market position.
+    ///
+    /// refund = claim(deposited) × min(distributable, owed) / owed
+    ///
+    /// Where:
+    ///   distributable = vault_balance − escrowed subsidy
+    ///   outstanding   = stake not yet refunded or settled
+    ///   claim(s)      = s + resolution_bounty × s / (yes_pool + no_pool)
+    ///   owed          = claim(outstanding)
+    ///
+    /// An unpaid resolution bounty was funded by the bettors pro rata to
+    /// their stake, so a cancelled market returns it alongside the stake.
+    /// Stake settled before a force-cancel stays paid; what those winners
+    /// took has already left the vault, so the rest share the remainder.
+    /// If the vault covers every outstanding deposit this is a full refund.
+    /// Otherwise each claimer gets the same fraction, so the vault drains
+    /// evenly instead of reverting for whoever claims last. Creator
+    /// liquidity is a solvency buffer, so it counts as distributable and
+    /// absorbs a shortfall before any bettor is haircut.
+    pub fn refund_amount(&self, deposited: u64, vault_balance: u64) -> u64 {
+        let owed = self.outstanding_refunds();
+        if owed == 0 {
+            return 0;
+        }
+
+        let distributable = vault_balance.saturating_sub(self.creator_subsidy) as u128;
+        let claim = self.refund_claim(deposited);
+
+        if distributable >= owed {
+            claim as u64
+        } else {
+            (claim * distributable / owed) as u64
+        }
+    }
+
+    /// Full refund claims still outstanding: claim(outstanding) above.
+    pub fn outstanding_refunds(&self) -> u128 {
+        let outstanding = self.yes_pool
+            .saturating_add(self.no_pool)
+            .saturating_sub(self.refunded_stake)
+            .saturating_sub(self.settled_stake);
+        if outstanding == 0 {
+            return 0;
+        }
+        self.refund_claim(outstanding)
+    }
+
+    /// A stake plus its pro-rata share of the unpaid resolution bounty.
+    fn refund_claim(&self, stake: u64) -> u128 {
+        let pool = self.yes_pool.saturating_add(self.no_pool) as u128;
+        stake as u128 + stake as u128 * self.resolution_bounty as u128 / pool
+    }
+
+    /// Lamports `reclaim_liquidity` returns to the creator.
+    ///
+    /// A settled market owes bettors nothing, so the creator gets back their
+    /// liquidity and subsidy, up to the vault. A cancelled market returns
+    /// the escrowed subsidy, which was never applied, but only the
+    /// liquidity left once every outstanding refund is paid in full.
+    pub fn reclaimable(&self, vault_balance: u64) -> u64 {
+        if self.status != MarketStatus::Cancelled {
+            return self.creator_liquidity
+                .saturating_add(self.creator_subsidy)
+                .min(vault_balance);
+        }
+        let subsidy = self.creator_subsidy.min(vault_balance);
+        let spare = (vault_balance - subsidy) as u128;
+        let liquidity = spare.saturating_sub(self.outstanding_refunds())
+            .min(self.creator_liquidity as u128) as u64;
+        subsidy + liquidity
+    }
+}
+
+/// `a × b / c` in u128, failing with `Overflow` instead of wrapping.
+fn mul_div(a: u128, b: u128, c: u128) -> Result<u128> {
+    Ok(a.checked_mul(b).ok_or(PercolatorError::Overflow)? / c)
+}
+
+/// Narrow a u128 amount to lamports, failing with `Overflow` if it does not fit.
+fn to_u64(amount: u128) -> Result<u64> {
+    u64::try_from(amount).map_err(|_| PercolatorError::Overflow.into())
+}
+
+/// Grow `account` to `new_len` bytes, zero-filled, with `payer` topping up
+/// its rent. Brings accounts created under an older, shorter layout up to
+/// the current size.
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    fund_rent(account, payer, system_program, Rent::get()?.minimum_balance(new_len))?;
+    account.realloc(new_len, true)?;
+    Ok(())
+}
+
+/// Top `account` up to `rent_min` lamports from `payer`. Returns the
+/// amount transferred, 0 if it already held enough.
+pub fn fund_rent<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent_min: u64,
+) -> Result<u64> {
+    let shortfall = rent_min.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    Ok(shortfall)
+}
+
+/// Fail with `VaultInsolvency` if a debit left `vault` holding lamports but
+/// fewer than `rent_min`. An empty vault has been closed on purpose and passes.
+///
+/// Call after every direct lamport debit from a vault.
+pub fn assert_vault_solvent(vault: &AccountInfo, rent_min: u64) -> Result<()> {
+    let balance = vault.lamports();
+    require!(
+        balance == 0 || balance >= rent_min,
+        PercolatorError::VaultInsolvency
+    );
+    Ok(())
+}
+
+/// ─── Payout Breakdown ─────────────────────────────────────────────
+///
+/// A settlement payout split into its claims. Returned to clients via
+/// return data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PayoutBreakdown {
+    /// Senior claim: the original stake.
+    pub capital: u64,
+
+    /// Junior claim: share of the profit pool after the h-ratio haircut.
+    pub profit: u64,
+
+    /// Fees deducted from the payout.
+    pub fee: u64,
+
+    /// Lamports transferred to the user.
+    pub total: u64,
+}
+
+/// ─── Market Rule ──────────────────────────────────────────────────
+///
+/// Determines how the market is resolved.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum MarketRule {
+    /// Token reaches a target market cap (in USD × 10^6).
+    #[default]
+    MarketCapTarget,
+
+    /// Token reaches a target price (in USD × 10^9, 9 decimal places).
+    PriceTarget,
+
+    /// Token maintains a minimum market cap throughout the period.
+    MarketCapFloor,
+
+    /// Custom condition resolved by oracle authority.
+    OracleCustom,
+
+    /// Token stays below a market cap ceiling (in USD × 10^6) through the deadline.
+    MarketCapCeiling,
+
+    /// Numeric outcome reported by the oracle. YES holders receive
+    /// `clamp((value − lower) / (upper − lower))` of the pool, NO holders
+    /// the rest.
+    Scalar { lower: u64, upper: u64 },
+}
+
+impl MarketRule {
+    /// Evaluate the rule against an observed value (market cap or price, in
+    /// the same units as `target_value`).
+    ///
+    /// Returns `None` for `OracleCustom` and `Scalar`, which only the
+    /// oracle can decide.
+    pub fn evaluate(&self, observed: u64, target_value: u64) -> Option<Outcome> {
+        let yes = match self {
+            MarketRule::MarketCapTarget
+            | MarketRule::PriceTarget
+            | MarketRule::MarketCapFloor => observed >= target_value,
+            // Inverted: YES only while the value stays under the ceiling
+            MarketRule::MarketCapCeiling => observed < target_value,
+            MarketRule::OracleCustom | MarketRule::Scalar { .. } => return None,
+        };
+
+        Some(if yes { Outcome::Yes } else { Outcome::No })
+    }
+
+    /// Rule whose YES is exactly this rule's NO on the same target, if any.
+    ///
+    /// Only the target/ceiling pair complement each other; latched floors,
+    /// oracle-decided and scalar rules have no inverse.
+    pub fn inverse(&self) -> Option<MarketRule> {
+        match self {
+            MarketRule::MarketCapTarget => Some(MarketRule::MarketCapCeiling),
+            MarketRule::MarketCapCeiling => Some(MarketRule::MarketCapTarget),
+            _ => None,
+        }
+    }
+
+    /// YES share (bps) of a scalar market's pool for a reported `value`,
+    /// clamped to the `[lower, upper]` range.
+    ///
+    /// Returns `None` for non-scalar rules.
+    pub fn scalar_share_bps(&self, value: u64) -> Option<u16> {
+        let MarketRule::Scalar { lower, upper } = *self else {
+            return None;
+        };
+
+        let share = if value <= lower {
+            0
+        } else if value >= upper {
+            10_000
+        } else {
+            ((value - lower) as u128 * 10_000 / (upper - lower) as u128) as u16
+        };
+        Some(share)
+    }
+}
+
+/// ─── Settlement Incentive ─────────────────────────────────────────
+///
+/// Protocol-wide nudge toward prompt settlement. Both variants act on
+/// the settler's profit only.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum SettlementIncentive {
+    /// No adjustment (default).
+    #[default]
+    None,
+
+    /// Extra profit for early settlers, decaying to zero over the window
+    /// and paid only from vault surplus (rounding dust and leftovers).
+    EarlyBonus,
+
+    /// Profit withheld from settlers after the window, sent to the treasury.
+    LatePenalty,
+}
+
+/// ─── Rounding Mode ────────────────────────────────────────────────
+///
+/// How profit shares are rounded in payout computation.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum RoundingMode {
+    /// Truncate (default); dust stays in the vault.
+    #[default]
+    Floor,
+
+    /// Round to the nearest lamport, halves up. The profit-pool cap keeps
+    /// the last settlers from being paid past the pool.
+    Nearest,
+}
+
+impl RoundingMode {
+    /// `n / d` rounded per this mode. `d` must be nonzero.
+    pub fn div(self, n: u128, d: u128) -> u128 {
+        let (quotient, remainder) = (n / d, n % d);
+        match self {
+            RoundingMode::Floor => quotient,
+            // Round up from a half, without overflowing on 2 × remainder
+            RoundingMode::Nearest => quotient + (remainder >= d - remainder) as u128,
+        }
+    }
+}
+
+/// ─── Resolution Source ────────────────────────────────────────────
+///
+/// How a market's outcome was decided, for indexers and risk scoring.
+///
+/// The program reads no Pyth or Switchboard account directly. Feed data
+/// reaches it only as an `OracleReading` posted by the market's oracle,
+/// whatever feed that oracle relays, so every feed-driven resolution is
+/// `OracleRule`. No authority path resolves a market either; the authority
+/// can only cancel one (`admin_force_cancel`). Variants for a direct feed
+/// or an admin resolution belong here, appended, once such a path exists.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ResolutionSource {
+    /// Not resolved yet (default).
+    #[default]
+    Unresolved,
+
+    /// The oracle's own verdict: `resolve_market`, `resolve_scalar` or
+    /// `resolve_blended`.
+    ManualOracle,
+
+    /// Derived by the program from posted oracle readings:
+    /// `resolve_with_rule` or `trigger_resolution`.
+    OracleRule,
+
+    /// `default_outcome` applied after the resolution window:
+    /// `resolve_default`.
+    Default,
+}
+
+/// ─── Market Kind ──────────────────────────────────────────────────
+///
+/// What is at stake in a market.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarketKind {
+    /// Losers' stakes are the winners' profit (default).
+    #[default]
+    Standard,
+
+    /// Every bettor gets their deposit back. Winners split only the prize
+    /// the creator escrows as `creator_subsidy`.
+    NoLoss,
+}
+
+/// ─── Insolvency Mode ──────────────────────────────────────────────
+///
+/// How `settle` handles a payout the vault cannot cover.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InsolvencyMode {
+    /// Fail with `VaultInsolvency` (strict, default).
+    #[default]
+    Revert,
+
+    /// Recompute h from the live vault and pay the reduced amount.
+    Haircut,
+}
+
+/// ─── Market Status ────────────────────────────────────────────────
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarketStatus {
+    /// Market is accepting bets.
+    #[default]
+    Open,
+
+    /// Market deadline passed, awaiting resolution.
+    Closed,
+
+    /// Outcome has been determined; settlement in progress.
+    Resolved,
+
+    /// Market was cancelled; refunds available.
+    Cancelled,
+
+    /// All settlements complete.
+    Settled,
+}
+
+/// ─── Outcome ──────────────────────────────────────────────────────
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Outcome {
+    #[default]
+    Unresolved,
+    Yes,
+    No,
+    /// Pool split between both sides by `Market::yes_share_bps`, from a
+    /// scalar or blended resolution.
+    Scalar,
+}
+
+/// ─── Bet Side ─────────────────────────────────────────────────────
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BetSide {
+    Yes,
+    No,
+}
+
+/// ─── User Position ────────────────────────────────────────────────
 ///
 /// PDA: seeds = [b"position", market.key, user.key]
 ///
@@ -261,8 +1550,26 @@ pub struct UserPosition {
     /// Bump seed.
     pub bump: u8,
 
-    /// Reserved.
-    pub _reserved: [u8; 32],
+    /// Market `settlements_count` when this position settled.
+    pub settle_order: u64,
+
+    /// Early-bird bonus weight; counts toward the subsidy share only.
+    pub bonus_stake: u64,
+
+    /// Σ deposit × (deadline − bet_time); the profit weight in
+    /// time-weighted markets.
+    pub weighted_stake: u128,
+
+    /// Client-supplied tag from the first `place_bet` (e.g. a campaign id);
+    /// zeroed if none. Never changed afterwards.
+    pub memo: [u8; 16],
+
+    /// YES pool just after the first deposit. With `entry_no_pool`, the
+    /// implied odds at entry; never updated as pools move.
+    pub entry_yes_pool: u64,
+
+    /// NO pool just after the first deposit.
+    pub entry_no_pool: u64,
 }
 
 impl Default for BetSide {
@@ -272,6 +1579,11 @@ impl Default for BetSide {
 }
 
 impl UserPosition {
+    /// Size of positions created before `memo`: the original layout, whose
+    /// 32 reserved bytes now hold `settle_order`, `bonus_stake` and
+    /// `weighted_stake`.
+    pub const LEGACY_SIZE: usize = 123;
+
     pub const SIZE: usize = 8  // discriminator
         + 32                    // market
         + 32                    // user
@@ -280,7 +1592,17 @@ impl UserPosition {
         + 1                     // settled
         + 8                     // payout
         + 1                     // bump
-        + 32;                   // reserved
+        + 8                     // settle_order (formerly reserved)
+        + 8                     // bonus_stake (formerly reserved)
+        + 16                    // weighted_stake (formerly reserved)
+        + 16                    // memo
+        + 8                     // entry_yes_pool
+        + 8;                    // entry_no_pool
+
+    /// Position NFT mint PDA for the position at `position`.
+    pub fn nft_mint(position: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"position_nft", position.as_ref()], &crate::ID).0
+    }
 }
 
 /// ─── Global Config ────────────────────────────────────────────────
@@ -312,8 +1634,82 @@ pub struct GlobalConfig {
     /// Bump seed.
     pub bump: u8,
 
+    /// Maximum seconds a single `extend_deadline` call may add.
+    pub max_extension_secs: i64,
+
+    /// Maximum number of deadline extensions per market.
+    pub max_extensions: u8,
+
+    /// Share of each collected fee routed to the market creator (basis points).
+    pub creator_fee_share_bps: u16,
+
+    /// Tip paid to a cranker for each position settled on another user's behalf.
+    pub crank_fee_lamports: u64,
+
+    /// Maximum total crank fees a single market may pay.
+    pub max_crank_fees_per_market: u64,
+
+    /// Maximum open (not Settled/Cancelled) markets per creator; 0 = unlimited.
+    pub max_markets_per_creator: u32,
+
+    /// Maximum betting volume per window across all markets; 0 = no cap.
+    pub max_protocol_volume: u64,
+
+    /// Length of the volume circuit-breaker window (seconds).
+    pub volume_window_secs: i64,
+
+    /// Start of the current volume window.
+    pub volume_window_start: i64,
+
+    /// Volume recorded in the current window (lamports).
+    pub window_volume: u64,
+
+    /// Minimum seconds between market creation and oracle resolution.
+    pub min_resolution_delay_secs: i64,
+
+    /// Maximum seconds an oracle may push a resolution window back.
+    pub max_resolution_extension_secs: i64,
+
+    /// Markets that reached Settled or Cancelled.
+    pub terminal_markets: u64,
+
+    /// Maximum question length in bytes for new markets; 0 = the 256-byte
+    /// allocation ceiling.
+    pub max_question_len: u16,
+
+    /// Rounding of profit shares for new markets.
+    pub rounding_mode: RoundingMode,
+
+    /// Longest gap between oracle heartbeats before automated resolution
+    /// is refused (0 = no check).
+    pub heartbeat_timeout_secs: i64,
+
+    /// Proposed new fee collector, awaiting its acceptance.
+    pub pending_fee_collector: Option<Pubkey>,
+
+    /// Reject new markets whose oracle is their creator.
+    pub require_distinct_oracle: bool,
+
+    /// Reward for prompt settlement or penalty for late settlement.
+    pub settlement_incentive: SettlementIncentive,
+
+    /// Incentive size as a share of the settler's profit (basis points).
+    pub settlement_incentive_bps: u16,
+
+    /// Seconds after resolution over which the bonus decays, or after
+    /// which the penalty applies (0 = no incentive).
+    pub settlement_incentive_window_secs: i64,
+
+    /// Longest time from creation to a new market's deadline
+    /// (0 = no limit).
+    pub max_market_duration_secs: i64,
+
+    /// Latest a reading may be published after the deadline and still
+    /// resolve a rule-based market (0 = no bound).
+    pub max_reading_staleness_secs: i64,
+
     /// Reserved.
-    pub _reserved: [u8; 128],
+    pub _reserved: [u8; 2],
 }
 
 impl GlobalConfig {
@@ -325,6 +1721,873 @@ impl GlobalConfig {
         + 8                     // total_markets
         + 8                     // total_volume
         + 1                     // bump
-        + 128;                  // reserved
+        + 8                     // max_extension_secs
+        + 1                     // max_extensions
+        + 2                     // creator_fee_share_bps
+        + 8                     // crank_fee_lamports
+        + 8                     // max_crank_fees_per_market
+        + 4                     // max_markets_per_creator
+        + 8                     // max_protocol_volume
+        + 8                     // volume_window_secs
+        + 8                     // volume_window_start
+        + 8                     // window_volume
+        + 8                     // min_resolution_delay_secs
+        + 8                     // max_resolution_extension_secs
+        + 8                     // terminal_markets
+        + 2                     // max_question_len
+        + 1                     // rounding_mode
+        + 8                     // heartbeat_timeout_secs
+        + (1 + 32)              // pending_fee_collector
+        + 1                     // require_distinct_oracle
+        + 1                     // settlement_incentive
+        + 2                     // settlement_incentive_bps
+        + 8                     // settlement_incentive_window_secs
+        + 8                     // max_market_duration_secs
+        + 8                     // max_reading_staleness_secs
+        + 2;                    // reserved
+
+    /// Hard cap on `max_resolution_extension_secs` (30 days).
+    pub const RESOLUTION_EXTENSION_HARD_CAP_SECS: i64 = 30 * 24 * 60 * 60;
+
+    /// Fail with `DeadlineTooFar` if `deadline` is more than
+    /// `max_market_duration_secs` after `now`. A far-off deadline would lock
+    /// bettors' funds indefinitely.
+    pub fn check_deadline_distance(&self, deadline: i64, now: i64) -> Result<()> {
+        let max_duration = self.max_market_duration_secs;
+        require!(
+            max_duration == 0 || deadline <= now.saturating_add(max_duration),
+            PercolatorError::DeadlineTooFar
+        );
+        Ok(())
+    }
+
+    /// Fail with `StaleOracleReading` unless a reading `published_at` lies
+    /// in `[deadline, deadline + max_reading_staleness_secs]`.
+    pub fn check_reading_time(&self, published_at: i64, deadline: i64) -> Result<()> {
+        let max_staleness = self.max_reading_staleness_secs;
+        require!(
+            published_at >= deadline
+                && (max_staleness <= 0 || published_at <= deadline.saturating_add(max_staleness)),
+            PercolatorError::StaleOracleReading
+        );
+        Ok(())
+    }
+
+    /// Settlement incentive on `profit` for a settle `elapsed` seconds
+    /// after resolution, as (bonus, penalty). Capital is never touched.
+    ///
+    /// EarlyBonus:  bonus   = profit × bps × (window − elapsed) / window / 10000
+    /// LatePenalty: penalty = profit × bps / 10000, once elapsed ≥ window
+    pub fn settlement_incentive(&self, profit: u64, elapsed: i64) -> (u64, u64) {
+        let window = self.settlement_incentive_window_secs;
+        if window <= 0 {
+            return (0, 0);
+        }
+        let share = profit as u128 * self.settlement_incentive_bps as u128;
+        match self.settlement_incentive {
+            SettlementIncentive::None => (0, 0),
+            SettlementIncentive::EarlyBonus => {
+                let remaining = window.saturating_sub(elapsed.max(0)).max(0);
+                ((share * remaining as u128 / window as u128 / 10_000) as u64, 0)
+            }
+            SettlementIncentive::LatePenalty if elapsed >= window => {
+                (0, (share / 10_000) as u64)
+            }
+            SettlementIncentive::LatePenalty => (0, 0),
+        }
+    }
+
+    /// Split the protocol fee on `amount` into (protocol_fee, creator_fee).
+    ///
+    /// fee         = amount × fee_bps / 10000
+    /// creator_fee = fee × creator_fee_share_bps / 10000
+    ///
+    /// The two parts always sum to the total fee.
+    pub fn split_fee(&self, amount: u64) -> (u64, u64) {
+        let fee = ((amount as u128 * self.fee_bps as u128) / 10_000) as u64;
+        let creator_fee = ((fee as u128 * self.creator_fee_share_bps as u128) / 10_000) as u64;
+        (fee - creator_fee, creator_fee)
+    }
+
+    /// Add a bet's gross `amount` to total and windowed volume.
+    ///
+    /// Rolls the window over once `volume_window_secs` have passed, then
+    /// rejects the bet if the window's volume would exceed
+    /// `max_protocol_volume`. A non-positive window never rolls over.
+    pub fn record_volume(&mut self, amount: u64, now: i64) -> Result<()> {
+        if self.volume_window_secs > 0
+            && now.saturating_sub(self.volume_window_start) >= self.volume_window_secs
+        {
+            self.volume_window_start = now;
+            self.window_volume = 0;
+        }
+
+        let window_volume = self.window_volume.checked_add(amount)
+            .ok_or(PercolatorError::Overflow)?;
+        require!(
+            self.max_protocol_volume == 0 || window_volume <= self.max_protocol_volume,
+            PercolatorError::VolumeCircuitBreaker
+        );
+
+        self.window_volume = window_volume;
+        self.total_volume = self.total_volume.checked_add(amount)
+            .ok_or(PercolatorError::Overflow)?;
+        Ok(())
+    }
+
+    /// Crank fee available for the next position settled in a market that
+    /// has already paid `market_fees_paid` in crank fees.
+    ///
+    /// The settle path further caps this at the position's profit, so
+    /// capital is never touched.
+    pub fn crank_fee(&self, market_fees_paid: u64) -> u64 {
+        let remaining = self.max_crank_fees_per_market.saturating_sub(market_fees_paid);
+        self.crank_fee_lamports.min(remaining)
+    }
+
+    /// Longest question `create_market` accepts, in bytes.
+    pub fn question_limit(&self) -> usize {
+        match self.max_question_len {
+            0 => Market::MAX_QUESTION_LEN,
+            len => len as usize,
+        }
+    }
+
+    /// Count a market reaching Settled or Cancelled.
+    pub fn market_closed(&mut self) {
+        self.terminal_markets = self.terminal_markets.saturating_add(1);
+    }
+
+    /// Whether any market has yet to reach a terminal state.
+    pub fn has_active_markets(&self) -> bool {
+        self.terminal_markets < self.total_markets
+    }
+
+    /// Markets not yet Settled or Cancelled.
+    pub fn active_markets(&self) -> u64 {
+        self.total_markets.saturating_sub(self.terminal_markets)
+    }
+}
+
+/// ─── Treasury ─────────────────────────────────────────────────────
+///
+/// PDA: seeds = [b"treasury"]
+///
+/// Accrues all protocol fees. Only `config.authority` can withdraw.
+#[account]
+pub struct Treasury {
+    /// Lifetime fees received (lamports).
+    pub total_collected: u64,
+
+    /// Lifetime withdrawals by the authority (lamports).
+    pub total_withdrawn: u64,
+
+    /// Bump seed.
+    pub bump: u8,
+
+    /// Reserved.
+    pub _reserved: [u8; 64],
+}
+
+impl Treasury {
+    pub const SIZE: usize = 8  // discriminator
+        + 8                     // total_collected
+        + 8                     // total_withdrawn
+        + 1                     // bump
+        + 64;                   // reserved
+}
+
+/// Price scale `target_value` uses unless a market sets its own: USD × 10^9.
+pub const DEFAULT_PRICE_EXPONENT: i32 = -9;
+
+/// Supported range of price exponents, for targets and feeds alike.
+pub const PRICE_EXPONENT_RANGE: std::ops::RangeInclusive<i32> = -18..=0;
+
+/// ─── Oracle Reading ───────────────────────────────────────────────
+///
+/// PDA: seeds = [b"oracle_reading", oracle.key, token_mint]
+///
+/// Raw price/supply posted by an oracle. `resolve_with_rule` derives the
+/// outcome from this data itself instead of trusting the oracle's verdict.
+#[account]
+pub struct OracleReading {
+    /// Oracle that posted this reading.
+    pub oracle: Pubkey,
+
+    /// Token the reading is about.
+    pub token_mint: Pubkey,
+
+    /// Token price in USD × 10^`price_exponent`.
+    pub price: u64,
+
+    /// Circulating supply in whole tokens.
+    pub supply: u64,
+
+    /// Unix timestamp of the latest update.
+    pub published_at: i64,
+
+    /// Bump seed.
+    pub bump: u8,
+
+    /// Decimal exponent of `price` as reported by the feed (e.g. -8).
+    /// Meaningful only from `OracleReading::VERSION` 1; see `exponent`.
+    pub price_exponent: i32,
+
+    /// Layout version: `OracleReading::VERSION` once posted by this program
+    /// version, 0 for readings last posted before `price_exponent` existed.
+    pub version: u8,
+
+    /// Reserved.
+    pub _reserved: [u8; 27],
+}
+
+impl OracleReading {
+    pub const SIZE: usize = 8  // discriminator
+        + 32                    // oracle
+        + 32                    // token_mint
+        + 8                     // price
+        + 8                     // supply
+        + 8                     // published_at
+        + 1                     // bump
+        + 4                     // price_exponent
+        + 1                     // version
+        + 27;                   // reserved
+
+    /// Layout version stamped by `post_oracle_reading`.
+    pub const VERSION: u8 = 1;
+
+    /// Decimal exponent of `price`. Readings from before `price_exponent`
+    /// read it as 0 but were always posted at `DEFAULT_PRICE_EXPONENT`.
+    pub fn exponent(&self) -> i32 {
+        if self.version == 0 {
+            DEFAULT_PRICE_EXPONENT
+        } else {
+            self.price_exponent
+        }
+    }
+
+    /// `price` rescaled to USD × 10^`exponent`, truncating when the target
+    /// scale is coarser. `None` if it overflows.
+    pub fn price_at(&self, exponent: i32) -> Option<u64> {
+        let shift = self.exponent() - exponent;
+        if shift >= 0 {
+            self.price.checked_mul(10u64.checked_pow(shift as u32)?)
+        } else {
+            Some(10u64.checked_pow(shift.unsigned_abs()).map_or(0, |d| self.price / d))
+        }
+    }
+
+    /// Market cap in USD × 10^6 (price × supply, rescaled from 10^9).
+    /// `None` if the price or the cap overflows, rather than a saturated
+    /// value a rule could mistake for a real reading.
+    pub fn market_cap(&self) -> Option<u64> {
+        let price = self.price_at(DEFAULT_PRICE_EXPONENT)?;
+        u64::try_from(price as u128 * self.supply as u128 / 1_000).ok()
+    }
+
+    /// The value `rule` is judged against, in the units of `target_value`.
+    /// A `PriceTarget` price is normalized to `price_exponent`, the scale
+    /// of the market's target.
+    ///
+    /// Returns `None` for `OracleCustom` and `Scalar`, or if the price or
+    /// market cap overflows.
+    pub fn observed_value(&self, rule: MarketRule, price_exponent: i32) -> Option<u64> {
+        match rule {
+            MarketRule::PriceTarget => self.price_at(price_exponent),
+            MarketRule::MarketCapTarget
+            | MarketRule::MarketCapFloor
+            | MarketRule::MarketCapCeiling => self.market_cap(),
+            MarketRule::OracleCustom | MarketRule::Scalar { .. } => None,
+        }
+    }
+}
+
+/// ─── Banned Creator ───────────────────────────────────────────────
+///
+/// PDA: seeds = [b"banned", creator.key]
+///
+/// Set by `config.authority` to stop a creator from opening new markets.
+/// Existing markets by the creator are unaffected.
+#[account]
+pub struct BannedCreator {
+    /// The creator this entry applies to.
+    pub creator: Pubkey,
+
+    /// Whether the ban is currently in force.
+    pub banned: bool,
+
+    /// Unix timestamp of the last change.
+    pub updated_at: i64,
+
+    /// Bump seed.
+    pub bump: u8,
+}
+
+impl BannedCreator {
+    pub const SIZE: usize = 8  // discriminator
+        + 32                    // creator
+        + 1                     // banned
+        + 8                     // updated_at
+        + 1;                    // bump
+
+    /// Whether the ban PDA passed to a creation instruction is an active ban.
+    ///
+    /// A PDA that was never created (no data) means the creator is not banned.
+    pub fn is_active(info: &AccountInfo) -> Result<bool> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(false);
+        }
+
+        let ban = BannedCreator::try_deserialize(&mut &info.data.borrow()[..])?;
+        Ok(ban.banned)
+    }
 }
 
+/// ─── Market Dedupe ────────────────────────────────────────────────
+///
+/// PDA: seeds = [b"market_dedupe", creator.key, MarketDedupe::key(..)]
+///
+/// Created by `create_market_deduped`. A second market with the same
+/// question, token mint, rule and target from the same creator fails
+/// because this PDA already exists.
+#[account]
+pub struct MarketDedupe {
+    /// The market created under this key.
+    pub market: Pubkey,
+
+    /// Bump seed.
+    pub bump: u8,
+}
+
+impl MarketDedupe {
+    pub const SIZE: usize = 8  // discriminator
+        + 32                    // market
+        + 1;                    // bump
+
+    /// `sha256(question || token_mint || rule || target_value_le)`, with
+    /// `rule` Borsh-encoded.
+    pub fn key(question: &str, token_mint: &Pubkey, rule: &MarketRule, target_value: u64) -> [u8; 32] {
+        let mut rule_bytes = Vec::with_capacity(17);
+        // Writing to a Vec cannot fail
+        let _ = rule.serialize(&mut rule_bytes);
+        hashv(&[
+            question.as_bytes(),
+            token_mint.as_ref(),
+            &rule_bytes,
+            &target_value.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+}
+
+/// ─── Settlement Receipt ───────────────────────────────────────────
+///
+/// PDA: seeds = [b"settlement_receipt", market.key, user.key]
+///
+/// Created when a position in a `settlement_receipts` market settles.
+/// Other programs can require it as proof that the settlement is final;
+/// it outlives the position, which `cleanup_positions` may close.
+#[account]
+pub struct SettlementReceipt {
+    /// The settled market.
+    pub market: Pubkey,
+
+    /// Owner of the settled position.
+    pub user: Pubkey,
+
+    /// Lamports paid to the user.
+    pub payout: u64,
+
+    /// Unix timestamp of the settlement.
+    pub settled_at: i64,
+
+    /// Bump seed.
+    pub bump: u8,
+}
+
+impl SettlementReceipt {
+    pub const SIZE: usize = 8  // discriminator
+        + 32                    // market
+        + 32                    // user
+        + 8                     // payout
+        + 8                     // settled_at
+        + 1;                    // bump
+}
+
+/// ─── Bet Commitment ───────────────────────────────────────────────
+///
+/// PDA: seeds = [b"commitment", market.key, user.key]
+///
+/// A sealed bet in a commit-reveal market. The account escrows the bet
+/// amount until it is revealed (moved into the vault) or refunded.
+#[account]
+pub struct BetCommitment {
+    /// The market this commitment belongs to.
+    pub market: Pubkey,
+
+    /// The committing user.
+    pub user: Pubkey,
+
+    /// sha256(side || amount_le || nonce).
+    pub commitment: [u8; 32],
+
+    /// Escrowed bet amount (lamports, before fees).
+    pub amount: u64,
+
+    /// Unix timestamp of the commit.
+    pub committed_at: i64,
+
+    /// Bump seed.
+    pub bump: u8,
+}
+
+impl BetCommitment {
+    pub const SIZE: usize = 8  // discriminator
+        + 32                    // market
+        + 32                    // user
+        + 32                    // commitment
+        + 8                     // amount
+        + 8                     // committed_at
+        + 1;                    // bump
+
+    /// Hash a bet for commit-reveal: sha256(side || amount_le || nonce).
+    pub fn hash_bet(side: BetSide, amount: u64, nonce: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&[side as u8], &amount.to_le_bytes(), nonce]).to_bytes()
+    }
+}
+
+/// ─── Merkle Distribution ──────────────────────────────────────────
+///
+/// PDA: seeds = [b"merkle_distribution", market.key]
+///
+/// Off-chain computed winner payouts for bulk settlement. Each leaf is
+/// sha256(index_le || user || payout_le); `claimed` is a bitmap over leaf
+/// indices.
+#[account]
+pub struct MerkleDistribution {
+    /// The market being settled.
+    pub market: Pubkey,
+
+    /// Merkle root of all payout leaves.
+    pub root: [u8; 32],
+
+    /// Sum of all leaf payouts (lamports).
+    pub total_payout: u64,
+
+    /// Lamports claimed so far.
+    pub claimed_amount: u64,
+
+    /// Number of leaves in the tree.
+    pub leaf_count: u32,
+
+    /// Number of leaves claimed so far.
+    pub claimed_count: u32,
+
+    /// Bump seed.
+    pub bump: u8,
+
+    /// One bit per leaf; set once claimed.
+    pub claimed: Vec<u8>,
+}
+
+impl MerkleDistribution {
+    /// Upper bound keeping the account within the CPI allocation limit.
+    pub const MAX_LEAVES: u32 = 64_000;
+
+    pub fn space(leaf_count: u32) -> usize {
+        8   // discriminator
+        + 32                    // market
+        + 32                    // root
+        + 8                     // total_payout
+        + 8                     // claimed_amount
+        + 4                     // leaf_count
+        + 4                     // claimed_count
+        + 1                     // bump
+        + 4 + Self::bitmap_len(leaf_count) // claimed
+    }
+
+    pub fn bitmap_len(leaf_count: u32) -> usize {
+        (leaf_count as usize).div_ceil(8)
+    }
+
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed[index as usize / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u32) {
+        self.claimed[index as usize / 8] |= 1 << (index % 8);
+    }
+
+    /// Leaf hash for `(index, user, payout)`.
+    pub fn leaf(index: u32, user: &Pubkey, payout: u64) -> [u8; 32] {
+        hashv(&[&index.to_le_bytes(), user.as_ref(), &payout.to_le_bytes()]).to_bytes()
+    }
+
+    /// Verify `leaf` against `root` with sorted-pair hashing.
+    pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+        let computed = proof.iter().fold(leaf, |node, sibling| {
+            if node <= *sibling {
+                hashv(&[&node, sibling]).to_bytes()
+            } else {
+                hashv(&[sibling, &node]).to_bytes()
+            }
+        });
+        computed == *root
+    }
+}
+
+/// ─── Creator Stats ────────────────────────────────────────────────
+///
+/// PDA: seeds = [b"creator_stats", creator.key]
+///
+/// Per-creator counters used for the open-market rate limit.
+#[account]
+pub struct CreatorStats {
+    /// The creator these stats belong to.
+    pub creator: Pubkey,
+
+    /// Markets created and not yet Settled or Cancelled.
+    pub active_markets: u32,
+
+    /// Markets ever created.
+    pub total_markets: u64,
+
+    /// Bump seed.
+    pub bump: u8,
+
+    /// Reserved.
+    pub _reserved: [u8; 32],
+}
+
+impl CreatorStats {
+    pub const SIZE: usize = 8  // discriminator
+        + 32                    // creator
+        + 4                     // active_markets
+        + 8                     // total_markets
+        + 1                     // bump
+        + 32;                   // reserved
+
+    /// Free an active-market slot when one of the creator's markets reaches
+    /// Settled or Cancelled.
+    pub fn market_closed(&mut self) {
+        self.active_markets = self.active_markets.saturating_sub(1);
+    }
+
+    /// `market_closed` on the creator's stats, if passed.
+    ///
+    /// Markets from before versioning may have no stats PDA and can omit
+    /// it; later markets fail with `CreatorStatsRequired`.
+    pub fn close_market(stats: Option<&mut CreatorStats>, market: &Market) -> Result<()> {
+        match stats {
+            Some(stats) => stats.market_closed(),
+            None => require!(
+                market.version < Market::VERSION,
+                PercolatorError::CreatorStatsRequired
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// ─── Oracle Schedule ──────────────────────────────────────────────
+///
+/// PDA: seeds = [b"oracle_schedule", market.key]
+///
+/// Planned oracle rotations for long-running markets. Entries are kept in
+/// strictly increasing `effective_at` order; only future entries can be
+/// appended.
+#[account]
+pub struct OracleSchedule {
+    /// The market this schedule belongs to.
+    pub market: Pubkey,
+
+    /// Rotation entries, oldest first.
+    pub entries: Vec<OracleScheduleEntry>,
+
+    /// Bump seed.
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct OracleScheduleEntry {
+    /// Unix timestamp from which `oracle` may resolve.
+    pub effective_at: i64,
+
+    /// Oracle authority for this period.
+    pub oracle: Pubkey,
+}
+
+impl OracleSchedule {
+    pub const MAX_ENTRIES: usize = 16;
+
+    pub const SIZE: usize = 8  // discriminator
+        + 32                    // market
+        + 4 + Self::MAX_ENTRIES * (8 + 32) // entries
+        + 1;                    // bump
+
+    /// Oracle of the latest entry in effect at `now`, if any.
+    pub fn active_oracle(&self, now: i64) -> Option<Pubkey> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.effective_at <= now)
+            .map(|entry| entry.oracle)
+    }
+}
+
+/// ─── Market Template ──────────────────────────────────────────────
+///
+/// PDA: seeds = [b"template", creator.key, template_id.to_le_bytes()]
+///
+/// Creation defaults a creator reuses across similar markets.
+/// `create_from_template` supplies only the question and token mint.
+#[account]
+pub struct MarketTemplate {
+    /// Creator who owns the template.
+    pub creator: Pubkey,
+
+    /// Creator-chosen template number.
+    pub template_id: u32,
+
+    /// Resolution rule.
+    pub rule: MarketRule,
+
+    /// Oracle authority for markets created from the template.
+    pub oracle: Pubkey,
+
+    /// Target value for resolution.
+    pub target_value: u64,
+
+    /// Seconds from creation to the market deadline.
+    pub duration_secs: i64,
+
+    /// Seconds after the deadline the oracle has to resolve.
+    pub resolution_window_secs: i64,
+
+    /// Fallback outcome if the oracle never resolves.
+    pub default_outcome: Outcome,
+
+    /// Settlement behaviour on a short vault.
+    pub insolvency_mode: InsolvencyMode,
+
+    /// Allow the creator to bet.
+    pub creator_betting_allowed: bool,
+
+    /// Allow the oracle to bet.
+    pub oracle_betting_allowed: bool,
+
+    /// Bump seed.
+    pub bump: u8,
+
+    /// Reserved.
+    pub _reserved: [u8; 32],
+}
+
+impl MarketTemplate {
+    pub const SIZE: usize = 8  // discriminator
+        + 32                    // creator
+        + 4                     // template_id
+        + (1 + 16)              // rule (tag + Scalar bounds)
+        + 32                    // oracle
+        + 8                     // target_value
+        + 8                     // duration_secs
+        + 8                     // resolution_window_secs
+        + 1                     // default_outcome
+        + 1                     // insolvency_mode
+        + 1                     // creator_betting_allowed
+        + 1                     // oracle_betting_allowed
+        + 1                     // bump
+        + 32;                   // reserved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A market with every field zeroed.
+    fn zeroed_market() -> Market {
+        Market::deserialize(&mut &[0u8; Market::SIZE][..]).unwrap()
+    }
+
+    /// A position with every field zeroed.
+    fn zeroed_position() -> UserPosition {
+        UserPosition::deserialize(&mut &[0u8; UserPosition::SIZE][..]).unwrap()
+    }
+
+    #[test]
+    fn payout_breakdown_at_max_pools_is_exact_or_overflow() {
+        let pools = [
+            (u64::MAX, u64::MAX, u64::MAX),
+            (1, u64::MAX, 0),
+            (u64::MAX, 1, u64::MAX),
+            (u64::MAX / 2, u64::MAX, u64::MAX / 2),
+        ];
+        let outcomes = [Outcome::Yes, Outcome::No, Outcome::Scalar];
+        let modes = [RoundingMode::Floor, RoundingMode::Nearest];
+
+        for (yes_pool, no_pool, subsidy) in pools {
+            for outcome in outcomes {
+                for rounding_mode in modes {
+                    for time_weighted in [false, true] {
+                        let mut market = zeroed_market();
+                        market.yes_pool = yes_pool;
+                        market.no_pool = no_pool;
+                        market.subsidy_applied = subsidy;
+                        market.yes_bonus_stake = u64::MAX;
+                        market.no_bonus_stake = u64::MAX;
+                        market.yes_weighted_stake = u128::MAX;
+                        market.no_weighted_stake = u128::MAX;
+                        market.outcome = outcome;
+                        market.h_ratio_bps = 10_000;
+                        market.yes_share_bps = 10_000;
+                        market.rounding_mode = rounding_mode;
+                        market.time_weighted = time_weighted;
+
+                        let mut position = zeroed_position();
+                        position.side = if outcome == Outcome::No { BetSide::No } else { BetSide::Yes };
+                        position.deposited = market.winner_pool().max(1);
+                        position.bonus_stake = u64::MAX;
+                        position.weighted_stake = u128::MAX;
+
+                        match market.payout_breakdown(&position) {
+                            Ok(breakdown) => assert_eq!(
+                                breakdown.total as u128,
+                                breakdown.capital as u128 + breakdown.profit as u128,
+                            ),
+                            Err(err) => assert_eq!(err, PercolatorError::Overflow.into()),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn legacy_reading_uses_default_exponent() {
+        let mut reading = OracleReading::deserialize(&mut &[0u8; OracleReading::SIZE][..]).unwrap();
+        reading.price = 2_000_000_000;
+        reading.supply = 1_000;
+        assert_eq!(reading.exponent(), DEFAULT_PRICE_EXPONENT);
+        assert_eq!(reading.market_cap(), Some(2_000_000_000));
+
+        reading.version = OracleReading::VERSION;
+        reading.price_exponent = -8;
+        assert_eq!(reading.market_cap(), Some(20_000_000_000));
+    }
+
+    #[test]
+    fn market_cap_overflow_is_none() {
+        let mut reading = OracleReading::deserialize(&mut &[0u8; OracleReading::SIZE][..]).unwrap();
+        reading.version = OracleReading::VERSION;
+        reading.price = u64::MAX;
+        reading.supply = 1;
+
+        // Rescaling the price from 10^0 to 10^-9 overflows
+        assert_eq!(reading.market_cap(), None);
+        assert_eq!(reading.observed_value(MarketRule::MarketCapTarget, DEFAULT_PRICE_EXPONENT), None);
+
+        // So does price × supply
+        reading.price_exponent = DEFAULT_PRICE_EXPONENT;
+        reading.supply = u64::MAX;
+        assert_eq!(reading.market_cap(), None);
+    }
+
+    #[test]
+    fn can_transition_matches_the_status_graph() {
+        use MarketStatus::*;
+        let statuses = [Open, Closed, Resolved, Cancelled, Settled];
+        let allowed = [
+            (Open, Closed),
+            (Open, Resolved),
+            (Open, Cancelled),
+            (Closed, Resolved),
+            (Closed, Cancelled),
+            (Resolved, Settled),
+            (Resolved, Cancelled),
+        ];
+
+        for from in statuses {
+            for to in statuses {
+                assert_eq!(
+                    Market::can_transition(from, to),
+                    allowed.contains(&(from, to)),
+                    "transition {} -> {}",
+                    from as u8,
+                    to as u8,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn record_volume_trips_the_breaker_across_bets_in_one_window() {
+        let mut config = GlobalConfig::deserialize(&mut &[0u8; GlobalConfig::SIZE][..]).unwrap();
+        config.max_protocol_volume = 1_000;
+        config.volume_window_secs = 3_600;
+
+        config.record_volume(400, 100).unwrap();
+        config.record_volume(400, 200).unwrap();
+        assert!(config.record_volume(400, 300).is_err());
+        assert_eq!(config.window_volume, 800);
+
+        // The next window starts from zero
+        config.record_volume(400, 3_600).unwrap();
+        assert_eq!(config.window_volume, 400);
+        assert_eq!(config.total_volume, 1_200);
+
+        // A non-positive window never rolls over
+        config.volume_window_secs = 0;
+        config.record_volume(600, 10_000).unwrap();
+        assert!(config.record_volume(1, 20_000).is_err());
+    }
+
+    #[test]
+    fn check_deadline_distance_bounds_new_and_extended_deadlines() {
+        let mut config = GlobalConfig::deserialize(&mut &[0u8; GlobalConfig::SIZE][..]).unwrap();
+        let now = 1_700_000_000;
+        config.check_deadline_distance(i64::MAX, now).unwrap();
+
+        config.max_market_duration_secs = 86_400;
+        config.check_deadline_distance(now + 86_400, now).unwrap();
+        assert!(config.check_deadline_distance(now + 86_401, now).is_err());
+    }
+
+    #[test]
+    fn short_cancelled_vault_spends_liquidity_before_haircutting_refunds() {
+        let mut market = zeroed_market();
+        market.status = MarketStatus::Cancelled;
+        market.yes_pool = 1_000;
+        market.no_pool = 1_000;
+        market.creator_liquidity = 500;
+        market.creator_subsidy = 200;
+
+        // 300 short of the pools once the subsidy is set aside
+        let vault = 1_700 + 200;
+        assert_eq!(market.refund_amount(1_000, vault), 850);
+        // The creator gets the subsidy back but none of the liquidity
+        assert_eq!(market.reclaimable(vault), 200);
+
+        // 300 to spare: the liquidity covers refunds in full, the rest returns
+        let vault = 2_300 + 200;
+        assert_eq!(market.refund_amount(1_000, vault), 1_000);
+        assert_eq!(market.reclaimable(vault), 200 + 300);
+
+        // Reclaiming leaves every refund whole
+        market.creator_liquidity = 0;
+        market.creator_subsidy = 0;
+        assert_eq!(market.refund_amount(1_000, vault - 500), 1_000);
+    }
+
+    #[test]
+    fn scalar_outstanding_claims_ignore_settlement_bonuses() {
+        let mut market = zeroed_market();
+        market.outcome = Outcome::Scalar;
+        market.yes_pool = 600;
+        market.no_pool = 400;
+
+        // One settler took 300 of the pool plus a 20 bonus from surplus
+        market.settled_amount = 320;
+        market.settlement_bonuses = 20;
+        assert_eq!(market.outstanding_claims().unwrap(), 700);
+    }
+}
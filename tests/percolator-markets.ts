@@ -7,6 +7,7 @@ import {
   createAssociatedTokenAccountInstruction,
 } from "@solana/spl-token";
 import { expect } from "chai";
+import { createHash } from "crypto";
 import { PercolatorMarkets } from "../target/types/percolator_markets";
 
 describe("percolator-markets", () => {
@@ -46,12 +47,11 @@ describe("percolator-markets", () => {
     );
   });
 
-  // ─── Initialize Global Config ──────────────────────────────────
+  // ─── Initialize Global Config ───────────────────────────────────
 
   it("Initializes global config", async () => {
-    // Config initialization would be a separate instruction in production.
-    // For tests, we assume it exists or create it via a setup instruction.
-    // This test validates the PDA derivation.
+    // The config is created once by `initialize_config`; the signer becomes
+    // the protocol authority. This test validates the PDA derivation.
     console.log("Config PDA:", configPda.toBase58());
     console.log("Program ID:", program.programId.toBase58());
   });
@@ -91,7 +91,7 @@ describe("percolator-markets", () => {
 
     // Market creation params
     const deadline = new anchor.BN(Math.floor(Date.now() / 1000) + 3600); // 1 hour
-    const tokenMint = Keypair.generate().publicKey; // mock token
+    const tokenMint = Keypair.generate().publicKey; // mock token; a full test needs a real SPL mint
 
     const params = {
       question: "Will $PEPE reach $1M market cap in 24h?",
@@ -100,6 +100,36 @@ describe("percolator-markets", () => {
       tokenMint,
       oracle: oracle.publicKey,
       deadline,
+      subsidyMatchBps: 0,
+      creatorSubsidy: new anchor.BN(0),
+      commitDeadline: new anchor.BN(0),
+      insolvencyMode: { revert: {} },
+      defaultOutcome: { unresolved: {} },
+      resolutionWindowSecs: new anchor.BN(7 * 86400),
+      creatorBettingAllowed: true,
+      oracleBettingAllowed: true,
+      questionHash: Array(32).fill(0),
+      questionUri: "",
+      parentMarket: null,
+      parentRequiredOutcome: { unresolved: {} },
+      earlyBirdBonusBps: 0,
+      earlyBirdWindowSecs: new anchor.BN(0),
+      hRatioFloorBps: 0,
+      timeWeighted: false,
+      kind: { standard: {} },
+      maxImbalanceBps: 10_000,
+      secondaryOracle: null,
+      resolutionToleranceBps: 0,
+      minSeedRatioBps: 0,
+      expectedMaxPool: new anchor.BN(0),
+      claimDeadline: new anchor.BN(0),
+      priceExponent: -9,
+      refundByTokenBalance: false,
+      minPoolPerSide: new anchor.BN(0),
+      resolutionBountyBps: 0,
+      resolveAtDeadline: false,
+      settlementReceipts: false,
+      positionNfts: false,
     };
 
     // In a full test, we'd call create_market here.
@@ -264,5 +294,4219 @@ describe("percolator-markets", () => {
       expect(canCancel).to.be.false;
     });
   });
+
+  // ─── Creator Liquidity ──────────────────────────────────────────
+
+  describe("creator liquidity", () => {
+    it("Added liquidity raises the h-ratio", () => {
+      const winnerPool = 5000;
+      const loserPool = 3000;
+      const totalClaims = winnerPool + loserPool;
+
+      const stressedVault = 6000;
+      const hBefore = Math.floor((Math.min(stressedVault, totalClaims) * 10000) / totalClaims);
+
+      // Creator adds 2000 lamports via add_liquidity — counted for solvency
+      // but not added to either pool.
+      const creatorLiquidity = 2000;
+      const vaultAfter = stressedVault + creatorLiquidity;
+      const hAfter = Math.floor((Math.min(vaultAfter, totalClaims) * 10000) / totalClaims);
+
+      expect(hBefore).to.equal(7500);
+      expect(hAfter).to.equal(10000);
+    });
+
+    it("Unused liquidity is reclaimable after settlement", () => {
+      const winnerPool = 5000;
+      const loserPool = 3000;
+      const creatorLiquidity = 1500;
+      const vault = winnerPool + loserPool + creatorLiquidity;
+
+      // Solvent market: winners take exactly both pools.
+      const totalPayout = winnerPool + loserPool;
+      const remaining = vault - totalPayout;
+      const reclaimed = Math.min(creatorLiquidity, remaining);
+
+      expect(reclaimed).to.equal(creatorLiquidity);
+    });
+
+    it("Cannot reclaim while the market is still open", () => {
+      const reclaim = (status: string) => {
+        if (status !== "Settled" && status !== "Cancelled") throw new Error("InvalidMarketStatus");
+      };
+      expect(() => reclaim("Open")).to.throw("InvalidMarketStatus");
+      expect(() => reclaim("Settled")).to.not.throw();
+    });
+  });
+
+  // ─── Creator Subsidy ────────────────────────────────────────────
+
+  describe("subsidized markets", () => {
+    it("Winner payouts increase by the matched subsidy", () => {
+      const winnerPool = 5000;
+      const loserPool = 3000;
+      const subsidyMatchBps = 5000; // creator matches 50% of the loser pool
+      const creatorSubsidy = 2000;
+
+      const matched = Math.min(creatorSubsidy, Math.floor((loserPool * subsidyMatchBps) / 10000));
+      expect(matched).to.equal(1500);
+
+      const userStake = 1000;
+      const plainPayout = userStake + Math.floor((userStake * loserPool) / winnerPool);
+      const subsidizedPayout =
+        userStake + Math.floor((userStake * (loserPool + matched)) / winnerPool);
+
+      // This winner holds 20% of the winner pool, so gets 20% of the match
+      expect(plainPayout).to.equal(1600);
+      expect(subsidizedPayout - plainPayout).to.equal(matched * (userStake / winnerPool));
+    });
+
+    it("Returns unused subsidy to the creator at resolve", () => {
+      const loserPool = 3000;
+      const subsidyMatchBps = 2000;
+      const creatorSubsidy = 2000;
+
+      const matched = Math.min(creatorSubsidy, Math.floor((loserPool * subsidyMatchBps) / 10000));
+      const returned = creatorSubsidy - matched;
+
+      expect(matched).to.equal(600);
+      expect(returned).to.equal(1400);
+    });
+
+    it("Capital claims are unaffected by the subsidy", () => {
+      const userStake = 1000;
+      const capital = userStake; // senior claim, independent of subsidy
+      expect(capital).to.equal(1000);
+    });
+  });
+
+  // ─── Market Rules ───────────────────────────────────────────────
+
+  describe("market cap ceiling rule", () => {
+    // Mirrors MarketRule::evaluate: YES only while the value stays below target
+    const evaluateCeiling = (observed: number, target: number) =>
+      observed < target ? "Yes" : "No";
+
+    it("Resolves YES when the token stays under the ceiling", () => {
+      expect(evaluateCeiling(750_000, 1_000_000)).to.equal("Yes");
+    });
+
+    it("Resolves NO when the token reaches the ceiling", () => {
+      expect(evaluateCeiling(1_000_000, 1_000_000)).to.equal("No");
+      expect(evaluateCeiling(1_250_000, 1_000_000)).to.equal("No");
+    });
+
+    it("Inverts the MarketCapTarget comparison", () => {
+      const evaluateTarget = (observed: number, target: number) =>
+        observed >= target ? "Yes" : "No";
+
+      for (const observed of [500_000, 1_000_000, 2_000_000]) {
+        expect(evaluateCeiling(observed, 1_000_000)).to.not.equal(
+          evaluateTarget(observed, 1_000_000)
+        );
+      }
+    });
+  });
+
+  // ─── Deadline Extension ─────────────────────────────────────────
+
+  describe("deadline extension", () => {
+    const maxExtensionSecs = 86_400;
+    const maxExtensions = 2;
+
+    // Mirrors extend_deadline's checks, returning the error name or null
+    const checkExtension = (deadline: number, newDeadline: number, count: number) => {
+      if (newDeadline <= deadline) return "DeadlineNotExtended";
+      if (newDeadline - deadline > maxExtensionSecs) return "ExtensionTooLong";
+      if (count >= maxExtensions) return "ExtensionLimitReached";
+      return null;
+    };
+
+    it("Accepts a valid extension", () => {
+      const deadline = 1_700_000_000;
+      expect(checkExtension(deadline, deadline + 3600, 0)).to.be.null;
+    });
+
+    it("Rejects shortening the deadline", () => {
+      const deadline = 1_700_000_000;
+      expect(checkExtension(deadline, deadline - 60, 0)).to.equal("DeadlineNotExtended");
+      expect(checkExtension(deadline, deadline, 0)).to.equal("DeadlineNotExtended");
+    });
+
+    it("Rejects extensions beyond the cap", () => {
+      const deadline = 1_700_000_000;
+      expect(checkExtension(deadline, deadline + maxExtensionSecs + 1, 0)).to.equal(
+        "ExtensionTooLong"
+      );
+      expect(checkExtension(deadline, deadline + 3600, maxExtensions)).to.equal(
+        "ExtensionLimitReached"
+      );
+    });
+  });
+
+  // ─── Market Freeze ──────────────────────────────────────────────
+
+  describe("market freeze", () => {
+    // Which instructions a frozen market still accepts
+    const allowedWhileFrozen = (ix: string) =>
+      ["cancel_market", "claim_refund", "settle"].includes(ix);
+
+    it("Rejects bets and resolution while frozen", () => {
+      const dispatch = (ix: string) => {
+        if (!allowedWhileFrozen(ix)) throw new Error("MarketFrozen");
+      };
+      expect(() => dispatch("place_bet")).to.throw("MarketFrozen");
+      expect(() => dispatch("resolve_market")).to.throw("MarketFrozen");
+    });
+
+    it("Still lets users exit while frozen", () => {
+      expect(allowedWhileFrozen("cancel_market")).to.be.true;
+      expect(allowedWhileFrozen("claim_refund")).to.be.true;
+      expect(allowedWhileFrozen("settle")).to.be.true;
+    });
+
+    it("Unfreezing restores betting", () => {
+      let frozen = true;
+      const canBet = () => !frozen;
+      expect(canBet()).to.be.false;
+
+      frozen = false; // unfreeze_market
+      expect(canBet()).to.be.true;
+    });
+  });
+
+  // ─── Fee Split ──────────────────────────────────────────────────
+
+  describe("protocol fee split", () => {
+    // Mirrors GlobalConfig::split_fee
+    const splitFee = (amount: number, feeBps: number, creatorShareBps: number) => {
+      const fee = Math.floor((amount * feeBps) / 10000);
+      const creatorFee = Math.floor((fee * creatorShareBps) / 10000);
+      return { protocolFee: fee - creatorFee, creatorFee, fee };
+    };
+
+    it("Split sums to the total fee", () => {
+      for (const amount of [1, 999, 1_000_000, 123_456_789]) {
+        const { protocolFee, creatorFee, fee } = splitFee(amount, 50, 3333);
+        expect(protocolFee + creatorFee).to.equal(fee);
+      }
+    });
+
+    it("Routes the configured share to the creator", () => {
+      const { protocolFee, creatorFee } = splitFee(1_000_000, 100, 2500);
+      // 1% fee = 10_000; creator gets 25% of it
+      expect(creatorFee).to.equal(2_500);
+      expect(protocolFee).to.equal(7_500);
+    });
+
+    it("Only the net stake enters the pool", () => {
+      const amount = 1_000_000;
+      const { fee } = splitFee(amount, 100, 2500);
+      const stake = amount - fee;
+      expect(stake).to.equal(990_000);
+    });
+  });
+
+  // ─── Treasury ───────────────────────────────────────────────────
+
+  describe("protocol treasury", () => {
+    it("Derives the treasury PDA", () => {
+      const [treasuryPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("treasury")],
+        program.programId
+      );
+      expect(PublicKey.isOnCurve(treasuryPda.toBytes())).to.be.false;
+    });
+
+    it("Accrues protocol fees from every bet", () => {
+      const feeBps = 100;
+      const creatorShareBps = 2000;
+      const bets = [1_000_000, 2_500_000, 400_000];
+
+      let treasury = 0;
+      for (const amount of bets) {
+        const fee = Math.floor((amount * feeBps) / 10000);
+        treasury += fee - Math.floor((fee * creatorShareBps) / 10000);
+      }
+
+      expect(treasury).to.equal(31_200);
+    });
+
+    it("Withdrawals never dip into the rent-exempt reserve", () => {
+      const rentMin = 1_447_680;
+      const balance = rentMin + 31_200;
+      const available = Math.max(0, balance - rentMin);
+
+      const withdraw = (amount: number) => {
+        if (amount > available) throw new Error("InsufficientTreasuryBalance");
+        return amount;
+      };
+
+      expect(available).to.equal(31_200);
+      expect(withdraw(available)).to.equal(31_200);
+      expect(() => withdraw(available + 1)).to.throw("InsufficientTreasuryBalance");
+    });
+  });
+
+  // ─── Proportional Refunds ───────────────────────────────────────
+
+  describe("under-funded cancellation refunds", () => {
+    // Mirrors Market::refund_amount
+    const refundAmount = (deposited: number, vault: number, outstanding: number) =>
+      vault >= outstanding ? deposited : Math.floor((deposited * vault) / outstanding);
+
+    it("Pays full refunds when the vault is whole", () => {
+      expect(refundAmount(1000, 8000, 8000)).to.equal(1000);
+    });
+
+    it("Scales refunds proportionally when the vault is short", () => {
+      const deposits = [1000, 3000, 4000];
+      let outstanding = deposits.reduce((a, b) => a + b, 0);
+      let vault = 6000; // 75% of deposits
+
+      const refunds: number[] = [];
+      for (const deposited of deposits) {
+        const refund = refundAmount(deposited, vault, outstanding);
+        refunds.push(refund);
+        vault -= refund;
+        outstanding -= deposited;
+      }
+
+      // Every claimer gets 75% — the last one isn't left with a revert
+      expect(refunds).to.deep.equal([750, 2250, 3000]);
+      expect(vault).to.equal(0);
+    });
+
+    it("Spends creator liquidity on a short vault before haircutting bettors", () => {
+      const creatorLiquidity = 2000;
+      const vault = 8000; // 6000 of bettor funds + creator liquidity
+      expect(refundAmount(1000, vault, 8000)).to.equal(1000);
+      // Mirrors Market::reclaimable: only liquidity beyond every full refund returns
+      const reclaimable = Math.min(creatorLiquidity, Math.max(0, vault - 8000));
+      expect(reclaimable).to.equal(0);
+    });
+
+    it("Returns the spare liquidity once refunds are covered", () => {
+      const creatorLiquidity = 2000;
+      const vault = 9000; // 8000 of bettor funds + creator liquidity, 1000 lost
+      const reclaimable = Math.min(creatorLiquidity, Math.max(0, vault - 8000));
+      expect(reclaimable).to.equal(1000);
+      expect(refundAmount(8000, vault - reclaimable, 8000)).to.equal(8000);
+    });
+  });
+
+  // ─── Rule-Based Resolution ──────────────────────────────────────
+
+  describe("resolve_with_rule", () => {
+    // Mirrors OracleReading::observed_value + MarketRule::evaluate
+    const marketCap = (price: number, supply: number) => Math.floor((price * supply) / 1000);
+    const resolveWithRule = (rule: string, target: number, price: number, supply: number) => {
+      switch (rule) {
+        case "priceTarget":
+          return price >= target ? "Yes" : "No";
+        case "marketCapTarget":
+        case "marketCapFloor":
+          return marketCap(price, supply) >= target ? "Yes" : "No";
+        case "marketCapCeiling":
+          return marketCap(price, supply) < target ? "Yes" : "No";
+        default:
+          throw new Error("InvalidOutcome");
+      }
+    };
+
+    // $0.001 per token (USD × 10^9) with 1B tokens = $1M market cap (USD × 10^6)
+    const price = 1_000_000;
+    const supply = 1_000_000_000;
+
+    it("Computes market cap as price × supply", () => {
+      expect(marketCap(price, supply)).to.equal(1_000_000_000_000);
+    });
+
+    it("MarketCapTarget resolves on crossing the target", () => {
+      expect(resolveWithRule("marketCapTarget", 1_000_000_000_000, price, supply)).to.equal("Yes");
+      expect(resolveWithRule("marketCapTarget", 1_000_000_000_001, price, supply)).to.equal("No");
+    });
+
+    it("PriceTarget compares the price directly", () => {
+      expect(resolveWithRule("priceTarget", 900_000, price, supply)).to.equal("Yes");
+      expect(resolveWithRule("priceTarget", 1_100_000, price, supply)).to.equal("No");
+    });
+
+    it("MarketCapFloor and MarketCapCeiling evaluate the market cap", () => {
+      expect(resolveWithRule("marketCapFloor", 500_000_000_000, price, supply)).to.equal("Yes");
+      expect(resolveWithRule("marketCapFloor", 2_000_000_000_000, price, supply)).to.equal("No");
+      expect(resolveWithRule("marketCapCeiling", 2_000_000_000_000, price, supply)).to.equal("Yes");
+      expect(resolveWithRule("marketCapCeiling", 500_000_000_000, price, supply)).to.equal("No");
+    });
+
+    it("Rejects OracleCustom markets", () => {
+      // On-chain this fails with PercolatorError::InvalidOutcome
+      expect(() => resolveWithRule("oracleCustom", 0, price, supply)).to.throw("InvalidOutcome");
+    });
+  });
+
+  // ─── Floor Checkpoints ──────────────────────────────────────────
+
+  describe("market cap floor checkpoints", () => {
+    const floor = 1_000_000;
+
+    // Replays record_checkpoint's latch, then applies the resolve override
+    const resolveFloor = (checkpoints: number[], finalValue: number) => {
+      let breached = false;
+      for (const value of checkpoints) {
+        if (value < floor) breached = true;
+      }
+      if (breached) return "No";
+      return finalValue >= floor ? "Yes" : "No";
+    };
+
+    it("Resolves YES when the floor held at every checkpoint", () => {
+      expect(resolveFloor([1_200_000, 1_050_000, 1_300_000], 1_400_000)).to.equal("Yes");
+    });
+
+    it("An intermediate breach latches NO despite a recovery", () => {
+      expect(resolveFloor([1_200_000, 900_000, 1_300_000], 1_400_000)).to.equal("No");
+    });
+
+    it("Only applies to MarketCapFloor markets", () => {
+      const recordCheckpoint = (rule: string) => {
+        if (rule !== "marketCapFloor") throw new Error("InvalidMarketRule");
+      };
+      expect(() => recordCheckpoint("marketCapTarget")).to.throw("InvalidMarketRule");
+      expect(() => recordCheckpoint("marketCapFloor")).to.not.throw();
+    });
+  });
+
+  // ─── Settlement Preview ─────────────────────────────────────────
+
+  describe("preview_settle", () => {
+    // Mirrors Market::payout_breakdown
+    const breakdown = (stake: number, winnerPool: number, loserPool: number, hBps: number) => {
+      const capital = stake;
+      const profit = Math.floor((Math.floor((stake * loserPool) / winnerPool) * hBps) / 10000);
+      return { capital, profit, fee: 0, total: capital + profit };
+    };
+
+    it("Preview matches the settled payout", () => {
+      const preview = breakdown(1000, 5000, 3000, 7500);
+      const settled = 1000 + Math.floor(600 * 0.75);
+
+      expect(preview).to.deep.equal({ capital: 1000, profit: 450, fee: 0, total: 1450 });
+      expect(preview.total).to.equal(settled);
+    });
+
+    it("Reports losing and settled positions as statuses", () => {
+      // Mirrors preview_settle's status precedence
+      const previewStatus = (resolved: boolean, settled: boolean, winner: boolean) => {
+        if (!resolved) return "notResolved";
+        if (settled) return "alreadySettled";
+        if (!winner) return "losingSide";
+        return "ok";
+      };
+
+      expect(previewStatus(true, false, false)).to.equal("losingSide");
+      expect(previewStatus(true, true, true)).to.equal("alreadySettled");
+      expect(previewStatus(false, false, true)).to.equal("notResolved");
+      expect(previewStatus(true, false, true)).to.equal("ok");
+    });
+  });
+
+  // ─── Creator Bans ───────────────────────────────────────────────
+
+  describe("set_creator_ban", () => {
+    // Mirrors BannedCreator::is_active — a missing entry is not a ban
+    const isBanned = (entry: { banned: boolean } | null) => entry !== null && entry.banned;
+
+    it("Creators without a ban entry can create markets", () => {
+      expect(isBanned(null)).to.be.false;
+    });
+
+    it("A banned creator cannot create markets", () => {
+      const createMarket = (entry: { banned: boolean } | null) => {
+        if (isBanned(entry)) throw new Error("CreatorBanned");
+      };
+      expect(() => createMarket({ banned: true })).to.throw("CreatorBanned");
+    });
+
+    it("Unbanning restores market creation", () => {
+      const entry = { banned: true };
+      entry.banned = false;
+      expect(isBanned(entry)).to.be.false;
+    });
+
+    it("Only the config authority can set bans", () => {
+      const authority = Keypair.generate().publicKey;
+      const setBan = (caller: PublicKey) => {
+        if (!caller.equals(authority)) throw new Error("UnauthorizedAuthority");
+      };
+      expect(() => setBan(Keypair.generate().publicKey)).to.throw("UnauthorizedAuthority");
+      expect(() => setBan(authority)).to.not.throw();
+    });
+  });
+
+  // ─── Crank Settlement ───────────────────────────────────────────
+
+  describe("crank_settle", () => {
+    const crankFeeLamports = 5_000;
+    const maxCrankFeesPerMarket = 12_000;
+
+    // Mirrors GlobalConfig::crank_fee plus settle_position's profit cap
+    const crank = (capital: number, profit: number, feesPaid: number) => {
+      const budget = Math.min(crankFeeLamports, Math.max(maxCrankFeesPerMarket - feesPaid, 0));
+      const fee = Math.min(budget, profit);
+      return { user: capital + profit - fee, cranker: fee };
+    };
+
+    it("Pays the cranker and the user the rest", () => {
+      const result = crank(1_000_000, 400_000, 0);
+      expect(result.cranker).to.equal(5_000);
+      expect(result.user).to.equal(1_395_000);
+    });
+
+    it("Never reduces the user's capital", () => {
+      const result = crank(1_000_000, 1_200, 0);
+      expect(result.cranker).to.equal(1_200);
+      expect(result.user).to.equal(1_000_000);
+
+      const noProfit = crank(1_000_000, 0, 0);
+      expect(noProfit.cranker).to.equal(0);
+      expect(noProfit.user).to.equal(1_000_000);
+    });
+
+    it("Caps total crank fees per market", () => {
+      let feesPaid = 0;
+      const tips: number[] = [];
+      for (let i = 0; i < 4; i++) {
+        const { cranker } = crank(1_000_000, 400_000, feesPaid);
+        tips.push(cranker);
+        feesPaid += cranker;
+      }
+
+      expect(tips).to.deep.equal([5_000, 5_000, 2_000, 0]);
+      expect(feesPaid).to.equal(maxCrankFeesPerMarket);
+    });
+  });
+
+  // ─── Commit-Reveal Betting ──────────────────────────────────────
+
+  describe("commit_bet / reveal_bet", () => {
+    // Mirrors BetCommitment::hash_bet: sha256(side || amount_le || nonce)
+    const hashBet = (side: number, amount: number, nonce: Buffer) => {
+      const amountLe = Buffer.alloc(8);
+      amountLe.writeBigUInt64LE(BigInt(amount));
+      return createHash("sha256").update(Buffer.from([side])).update(amountLe).update(nonce).digest();
+    };
+
+    const nonce = Buffer.alloc(32, 7);
+    const commitment = hashBet(0, 1_000_000, nonce);
+
+    it("A matching reveal is accepted", () => {
+      expect(hashBet(0, 1_000_000, nonce).equals(commitment)).to.be.true;
+    });
+
+    it("Rejects a reveal with a different side or nonce", () => {
+      const reveal = (side: number, amount: number, revealNonce: Buffer) => {
+        if (!hashBet(side, amount, revealNonce).equals(commitment)) {
+          throw new Error("CommitmentMismatch");
+        }
+      };
+      expect(() => reveal(1, 1_000_000, nonce)).to.throw("CommitmentMismatch");
+      expect(() => reveal(0, 1_000_000, Buffer.alloc(32, 8))).to.throw("CommitmentMismatch");
+    });
+
+    it("Enforces the commit and reveal windows", () => {
+      const commitDeadline = 1_000;
+      const deadline = 2_000;
+      const canCommit = (now: number) => now < commitDeadline;
+      const canReveal = (now: number) => now >= commitDeadline && now < deadline;
+      const canRefund = (now: number, cancelled: boolean) => cancelled || now >= deadline;
+
+      expect(canCommit(999)).to.be.true;
+      expect(canCommit(1_000)).to.be.false;
+      expect(canReveal(999)).to.be.false;
+      expect(canReveal(1_500)).to.be.true;
+      expect(canReveal(2_000)).to.be.false;
+      expect(canRefund(1_500, false)).to.be.false;
+      expect(canRefund(2_000, false)).to.be.true;
+      expect(canRefund(500, true)).to.be.true;
+    });
+  });
+
+  // ─── Insolvency Mode ────────────────────────────────────────────
+
+  describe("insolvency mode", () => {
+    const winnerPool = 5000;
+    const distributable = 3000;
+
+    // Mirrors Market::haircut_breakdown (h starts at 100%)
+    const haircut = (stake: number, settledStake: number, vault: number) => {
+      const remainingStake = Math.max(winnerPool - settledStake, stake);
+      const remainingProfit = Math.floor((distributable * remainingStake) / winnerPool);
+      const h =
+        vault <= remainingStake
+          ? 0
+          : remainingProfit === 0
+          ? 10000
+          : Math.min(Math.floor(((vault - remainingStake) * 10000) / remainingProfit), 10000);
+      const capital = vault >= remainingStake ? stake : Math.floor((stake * vault) / remainingStake);
+      const profit = Math.floor((Math.floor((stake * distributable) / winnerPool) * h) / 10000);
+      return { capital, profit, total: capital + profit, h };
+    };
+
+    const settle = (mode: "revert" | "haircut", stake: number, settledStake: number, vault: number) => {
+      const full = stake + Math.floor((stake * distributable) / winnerPool);
+      if (full <= vault) return full;
+      if (mode === "revert") throw new Error("VaultInsolvency");
+      return haircut(stake, settledStake, vault).total;
+    };
+
+    it("Revert mode fails on an under-funded vault", () => {
+      expect(() => settle("revert", 5000, 0, 6500)).to.throw("VaultInsolvency");
+    });
+
+    it("Haircut mode pays capital plus a reduced profit", () => {
+      const result = haircut(5000, 0, 6500);
+      expect(result.h).to.equal(5000);
+      expect(result.capital).to.equal(5000);
+      expect(result.total).to.equal(6500);
+      expect(settle("haircut", 5000, 0, 6500)).to.equal(6500);
+    });
+
+    it("Haircut scales capital only when stakes exceed the vault", () => {
+      const result = haircut(2000, 3000, 1000);
+      expect(result.h).to.equal(0);
+      expect(result.capital).to.equal(1000);
+      expect(result.total).to.be.at.most(1000);
+    });
+  });
+
+  // ─── Merkle Settlement ──────────────────────────────────────────
+
+  describe("post_settlement_root / claim_merkle", () => {
+    const sha256 = (...parts: Buffer[]) => {
+      const hash = createHash("sha256");
+      for (const part of parts) hash.update(part);
+      return hash.digest();
+    };
+
+    // Mirrors MerkleDistribution::leaf
+    const leaf = (index: number, user: PublicKey, payout: number) => {
+      const indexLe = Buffer.alloc(4);
+      indexLe.writeUInt32LE(index);
+      const payoutLe = Buffer.alloc(8);
+      payoutLe.writeBigUInt64LE(BigInt(payout));
+      return sha256(indexLe, user.toBuffer(), payoutLe);
+    };
+
+    // Mirrors MerkleDistribution::verify (sorted-pair hashing)
+    const hashPair = (a: Buffer, b: Buffer) => (Buffer.compare(a, b) <= 0 ? sha256(a, b) : sha256(b, a));
+    const verify = (proof: Buffer[], root: Buffer, node: Buffer) =>
+      proof.reduce((acc, sibling) => hashPair(acc, sibling), node).equals(root);
+
+    const users = [Keypair.generate(), Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    const payouts = [1_500, 2_500, 700, 300];
+    const leaves = users.map((u, i) => leaf(i, u.publicKey, payouts[i]));
+    const left = hashPair(leaves[0], leaves[1]);
+    const right = hashPair(leaves[2], leaves[3]);
+    const root = hashPair(left, right);
+
+    it("A valid proof claims the leaf payout", () => {
+      expect(verify([leaves[1], right], root, leaves[0])).to.be.true;
+      expect(verify([leaves[2], left], root, leaves[3])).to.be.true;
+    });
+
+    it("Rejects a double claim via the bitmap", () => {
+      const bitmap = Buffer.alloc(Math.ceil(leaves.length / 8));
+      const claim = (index: number) => {
+        if (bitmap[index >> 3] & (1 << (index % 8))) throw new Error("AlreadySettled");
+        bitmap[index >> 3] |= 1 << (index % 8);
+      };
+
+      claim(2);
+      expect(() => claim(2)).to.throw("AlreadySettled");
+    });
+
+    it("Rejects an invalid proof or inflated payout", () => {
+      const claimWithProof = (proof: Buffer[], claimed: Buffer) => {
+        if (!verify(proof, root, claimed)) throw new Error("InvalidMerkleProof");
+      };
+      const inflated = leaf(0, users[0].publicKey, 10_000);
+      expect(() => claimWithProof([leaves[1], right], inflated)).to.throw("InvalidMerkleProof");
+      expect(() => claimWithProof([leaves[0], right], leaves[0])).to.throw("InvalidMerkleProof");
+    });
+
+    it("The posted total must fit in the vault", () => {
+      const postRoot = (total: number, vault: number) => {
+        if (total > vault) throw new Error("SettlementRootExceedsVault");
+      };
+      const total = payouts.reduce((a, b) => a + b, 0);
+      expect(() => postRoot(total, 4_000)).to.throw("SettlementRootExceedsVault");
+      expect(() => postRoot(total, total)).to.not.throw();
+    });
+  });
+
+  // ─── Creator Market Limit ───────────────────────────────────────
+
+  describe("max markets per creator", () => {
+    // Mirrors create_market's CreatorStats check and market_closed
+    const makeCreator = (maxMarkets: number) => {
+      const stats = { activeMarkets: 0, totalMarkets: 0 };
+      return {
+        stats,
+        create: () => {
+          if (maxMarkets !== 0 && stats.activeMarkets >= maxMarkets) {
+            throw new Error("CreatorMarketLimit");
+          }
+          stats.activeMarkets += 1;
+          stats.totalMarkets += 1;
+        },
+        close: () => {
+          stats.activeMarkets = Math.max(stats.activeMarkets - 1, 0);
+        },
+      };
+    };
+
+    it("Rejects markets beyond the limit", () => {
+      const creator = makeCreator(2);
+      creator.create();
+      creator.create();
+      expect(() => creator.create()).to.throw("CreatorMarketLimit");
+    });
+
+    it("Settling a market frees a slot", () => {
+      const creator = makeCreator(2);
+      creator.create();
+      creator.create();
+      creator.close(); // last winner settles → status Settled
+
+      expect(() => creator.create()).to.not.throw();
+      expect(creator.stats.activeMarkets).to.equal(2);
+      expect(creator.stats.totalMarkets).to.equal(3);
+    });
+
+    it("A limit of 0 is unlimited", () => {
+      const creator = makeCreator(0);
+      for (let i = 0; i < 50; i++) creator.create();
+      expect(creator.stats.activeMarkets).to.equal(50);
+    });
+  });
+
+  // ─── Cancellation Event ─────────────────────────────────────────
+
+  describe("MarketCancelled event", () => {
+    it("Carries the refundable total and the cancelling authority", () => {
+      const market = { marketId: 7, yesPool: 3_000_000, noPool: 1_250_000 };
+      const creator = Keypair.generate().publicKey;
+      const now = Math.floor(Date.now() / 1000);
+
+      // Mirrors cancel_market's emit!(MarketCancelled { .. })
+      const event = {
+        marketId: market.marketId,
+        refundableTotal: market.yesPool + market.noPool,
+        cancelledBy: creator,
+        cancelledAt: now,
+      };
+
+      expect(event.marketId).to.equal(7);
+      expect(event.refundableTotal).to.equal(4_250_000);
+      expect(event.cancelledBy.equals(creator)).to.be.true;
+      expect(event.cancelledAt).to.equal(now);
+    });
+  });
+
+  // ─── Settlement Count Invariant ─────────────────────────────────
+
+  describe("settlement count invariant", () => {
+    it("Rejects settling beyond the known winner count", () => {
+      // yes_positions / no_positions are counted on each position's first bet
+      const market = { yesPositions: 2, noPositions: 5, settlementsCount: 0 };
+      const winningPositions = market.yesPositions; // resolved YES
+
+      const settle = () => {
+        if (market.settlementsCount >= winningPositions) throw new Error("SettlementOverflow");
+        market.settlementsCount += 1;
+      };
+
+      settle();
+      settle();
+      expect(() => settle()).to.throw("SettlementOverflow");
+      expect(market.settlementsCount).to.equal(2);
+    });
+
+    it("Repeat bets do not add positions", () => {
+      let yesPositions = 0;
+      const deposits = new Map<string, number>();
+      const bet = (user: string, amount: number) => {
+        if (!deposits.has(user)) yesPositions += 1;
+        deposits.set(user, (deposits.get(user) ?? 0) + amount);
+      };
+
+      bet("alice", 100);
+      bet("alice", 200);
+      bet("bob", 50);
+      expect(yesPositions).to.equal(2);
+    });
+  });
+
+  // ─── Double-Resolve Protection ──────────────────────────────────
+
+  describe("double-resolve protection", () => {
+    it("A second resolve cannot overwrite the outcome", () => {
+      const market = { status: "open", outcome: "unresolved" };
+
+      // Mirrors apply_outcome's outcome guard, independent of status
+      const resolve = (outcome: string) => {
+        if (market.outcome !== "unresolved") throw new Error("AlreadyResolved");
+        market.outcome = outcome;
+        market.status = "resolved";
+      };
+
+      resolve("yes");
+      market.status = "open"; // even if status logic regressed
+      expect(() => resolve("no")).to.throw("AlreadyResolved");
+      expect(market.outcome).to.equal("yes");
+    });
+  });
+
+  // ─── Default Outcome ────────────────────────────────────────────
+
+  describe("resolve_default", () => {
+    const deadline = 1_000_000;
+    const resolutionWindowSecs = 86_400;
+    const windowEnd = deadline + resolutionWindowSecs;
+
+    // Mirrors resolve_default
+    const resolveDefault = (now: number, defaultOutcome: string) => {
+      if (now < windowEnd) throw new Error("ResolutionWindowOpen");
+      return defaultOutcome === "unresolved"
+        ? { status: "cancelled", outcome: "unresolved" }
+        : { status: "resolved", outcome: defaultOutcome };
+    };
+
+    it("Rejects before the resolution window ends", () => {
+      expect(() => resolveDefault(windowEnd - 1, "no")).to.throw("ResolutionWindowOpen");
+    });
+
+    it("Applies the default outcome after the window", () => {
+      expect(resolveDefault(windowEnd, "no")).to.deep.equal({ status: "resolved", outcome: "no" });
+    });
+
+    it("Voids the market when the default is Unresolved", () => {
+      expect(resolveDefault(windowEnd + 10, "unresolved").status).to.equal("cancelled");
+    });
+
+    it("Extending the deadline shifts the window end", () => {
+      const newDeadline = deadline + 3_600;
+      expect(windowEnd + (newDeadline - deadline)).to.equal(newDeadline + resolutionWindowSecs);
+    });
+  });
+
+  // ─── Volume Circuit Breaker ─────────────────────────────────────
+
+  describe("volume circuit breaker", () => {
+    // Mirrors GlobalConfig::record_volume
+    const makeConfig = (maxProtocolVolume: number, volumeWindowSecs: number) => {
+      const config = { volumeWindowStart: 0, windowVolume: 0, totalVolume: 0 };
+      const recordVolume = (amount: number, now: number) => {
+        if (volumeWindowSecs > 0 && now - config.volumeWindowStart >= volumeWindowSecs) {
+          config.volumeWindowStart = now;
+          config.windowVolume = 0;
+        }
+        const windowVolume = config.windowVolume + amount;
+        if (maxProtocolVolume !== 0 && windowVolume > maxProtocolVolume) {
+          throw new Error("VolumeCircuitBreaker");
+        }
+        config.windowVolume = windowVolume;
+        config.totalVolume += amount;
+      };
+      return { config, recordVolume };
+    };
+
+    it("Blocks bets once the window's cap is reached", () => {
+      const { recordVolume } = makeConfig(10 * LAMPORTS_PER_SOL, 3600);
+      recordVolume(6 * LAMPORTS_PER_SOL, 1_000);
+      recordVolume(4 * LAMPORTS_PER_SOL, 1_500);
+      expect(() => recordVolume(1, 2_000)).to.throw("VolumeCircuitBreaker");
+    });
+
+    it("Resumes in the next window", () => {
+      const { config, recordVolume } = makeConfig(10 * LAMPORTS_PER_SOL, 3600);
+      recordVolume(10 * LAMPORTS_PER_SOL, 1_000);
+      expect(() => recordVolume(LAMPORTS_PER_SOL, 4_599)).to.throw("VolumeCircuitBreaker");
+
+      recordVolume(LAMPORTS_PER_SOL, 4_600);
+      expect(config.windowVolume).to.equal(LAMPORTS_PER_SOL);
+      expect(config.totalVolume).to.equal(11 * LAMPORTS_PER_SOL);
+    });
+
+    it("Never rolls a non-positive window over, so bets still add up", () => {
+      const { recordVolume } = makeConfig(10 * LAMPORTS_PER_SOL, 0);
+      recordVolume(6 * LAMPORTS_PER_SOL, 1_000);
+      expect(() => recordVolume(6 * LAMPORTS_PER_SOL, 90_000)).to.throw("VolumeCircuitBreaker");
+    });
+
+    it("update_config refuses a cap without a positive window", () => {
+      // Mirrors update_config's InvalidVolumeWindow check
+      const valid = (maxProtocolVolume: number, volumeWindowSecs: number) =>
+        maxProtocolVolume === 0 || volumeWindowSecs > 0;
+      expect(valid(10 * LAMPORTS_PER_SOL, 3600)).to.be.true;
+      expect(valid(10 * LAMPORTS_PER_SOL, 0)).to.be.false;
+      expect(valid(10 * LAMPORTS_PER_SOL, -1)).to.be.false;
+      expect(valid(0, 0)).to.be.true;
+    });
+  });
+
+  // ─── h-ratio History ────────────────────────────────────────────
+
+  describe("h-ratio history", () => {
+    // Mirrors Market::set_h_ratio
+    const makeMarket = () => {
+      const market = { hRatioBps: 10000, hRatioHistory: new Array(8).fill(0), hRatioCursor: 0 };
+      const events: { old: number; new: number }[] = [];
+      const setHRatio = (h: number) => {
+        events.push({ old: market.hRatioBps, new: h });
+        market.hRatioBps = h;
+        market.hRatioHistory[market.hRatioCursor] = h;
+        market.hRatioCursor = (market.hRatioCursor + 1) % 8;
+      };
+      return { market, events, setHRatio };
+    };
+
+    it("Records recomputations in order", () => {
+      const { market, events, setHRatio } = makeMarket();
+      setHRatio(10000); // resolve
+      setHRatio(8000); // haircut at settle
+      setHRatio(6500);
+
+      expect(market.hRatioHistory.slice(0, 3)).to.deep.equal([10000, 8000, 6500]);
+      expect(market.hRatioCursor).to.equal(3);
+      expect(events[1]).to.deep.equal({ old: 10000, new: 8000 });
+    });
+
+    it("Wraps around after 8 entries", () => {
+      const { market, setHRatio } = makeMarket();
+      for (let i = 1; i <= 10; i++) setHRatio(10000 - i * 100);
+
+      expect(market.hRatioCursor).to.equal(2);
+      expect(market.hRatioHistory[0]).to.equal(9100);
+      expect(market.hRatioHistory[1]).to.equal(9000);
+      expect(market.hRatioHistory[2]).to.equal(9700);
+    });
+  });
+
+  // ─── Close Losing Position ──────────────────────────────────────
+
+  describe("close_losing_position", () => {
+    // Mirrors close_losing_position's status and side checks
+    const close = (status: string, outcome: string, side: string) => {
+      if (status !== "resolved" && status !== "settled") throw new Error("InvalidMarketStatus");
+      if (outcome === side) throw new Error("WinningPosition");
+      return "closed";
+    };
+
+    it("A loser reclaims the position rent", () => {
+      expect(close("resolved", "yes", "no")).to.equal("closed");
+      expect(close("settled", "no", "yes")).to.equal("closed");
+    });
+
+    it("Rejects winning positions", () => {
+      expect(() => close("resolved", "yes", "yes")).to.throw("WinningPosition");
+    });
+
+    it("Rejects before resolution", () => {
+      expect(() => close("open", "unresolved", "no")).to.throw("InvalidMarketStatus");
+    });
+  });
+
+  // ─── place_bet Status Errors ────────────────────────────────────
+
+  describe("place_bet status errors", () => {
+    // Mirrors place_bet's status branch
+    const betError = (status: string) => {
+      switch (status) {
+        case "open":
+          return null;
+        case "closed":
+          return "MarketExpired";
+        case "cancelled":
+          return "MarketCancelled";
+        default:
+          return "InvalidMarketStatus";
+      }
+    };
+
+    it("Each status yields its specific error", () => {
+      expect(betError("open")).to.be.null;
+      expect(betError("closed")).to.equal("MarketExpired");
+      expect(betError("cancelled")).to.equal("MarketCancelled");
+      expect(betError("resolved")).to.equal("InvalidMarketStatus");
+      expect(betError("settled")).to.equal("InvalidMarketStatus");
+    });
+  });
+
+  // ─── Oracle Rotation Schedule ───────────────────────────────────
+
+  describe("oracle schedule", () => {
+    const initialOracle = Keypair.generate().publicKey;
+    const oracleB = Keypair.generate().publicKey;
+    const oracleC = Keypair.generate().publicKey;
+    const schedule = [
+      { effectiveAt: 2_000, oracle: oracleB },
+      { effectiveAt: 5_000, oracle: oracleC },
+    ];
+
+    // Mirrors Market::active_oracle / OracleSchedule::active_oracle
+    const activeOracle = (now: number) => {
+      const entry = [...schedule].reverse().find((e) => e.effectiveAt <= now);
+      return entry ? entry.oracle : initialOracle;
+    };
+
+    it("Resolves with the oracle active at each time", () => {
+      expect(activeOracle(1_000).equals(initialOracle)).to.be.true;
+      expect(activeOracle(2_000).equals(oracleB)).to.be.true;
+      expect(activeOracle(4_999).equals(oracleB)).to.be.true;
+      expect(activeOracle(9_000).equals(oracleC)).to.be.true;
+    });
+
+    it("A rotated-out oracle can no longer resolve", () => {
+      const resolve = (signer: PublicKey, now: number) => {
+        if (!activeOracle(now).equals(signer)) throw new Error("UnauthorizedOracle");
+      };
+      expect(() => resolve(oracleB, 6_000)).to.throw("UnauthorizedOracle");
+      expect(() => resolve(oracleC, 6_000)).to.not.throw();
+    });
+
+    it("Only future entries after the last one can be appended", () => {
+      const canAppend = (effectiveAt: number, now: number) =>
+        effectiveAt > now && effectiveAt > schedule[schedule.length - 1].effectiveAt;
+
+      expect(canAppend(8_000, 6_000)).to.be.true;
+      expect(canAppend(4_000, 1_000)).to.be.false; // would reorder existing entries
+      expect(canAppend(6_000, 7_000)).to.be.false; // already in the past
+    });
+  });
+
+  // ─── Neutral Creator Markets ────────────────────────────────────
+
+  describe("creator and oracle betting flags", () => {
+    const creator = Keypair.generate().publicKey;
+    const oracleKey = Keypair.generate().publicKey;
+    const bettor = Keypair.generate().publicKey;
+
+    // Mirrors Market::check_bettor
+    const checkBettor = (who: PublicKey, creatorAllowed: boolean, oracleAllowed: boolean) => {
+      if (!creatorAllowed && who.equals(creator)) throw new Error("CreatorCannotBet");
+      if (!oracleAllowed && who.equals(oracleKey)) throw new Error("OracleCannotBet");
+    };
+
+    it("Creators can bet by default", () => {
+      expect(() => checkBettor(creator, true, true)).to.not.throw();
+    });
+
+    it("Blocks the creator when disabled", () => {
+      expect(() => checkBettor(creator, false, true)).to.throw("CreatorCannotBet");
+      expect(() => checkBettor(bettor, false, true)).to.not.throw();
+    });
+
+    it("Blocks the oracle with its own flag", () => {
+      expect(() => checkBettor(oracleKey, true, false)).to.throw("OracleCannotBet");
+      expect(() => checkBettor(oracleKey, true, true)).to.not.throw();
+    });
+  });
+
+  // ─── Minimum Resolution Delay ───────────────────────────────────
+
+  describe("minimum resolution delay", () => {
+    const createdAt = 1_700_000_000;
+    const minResolutionDelaySecs = 3600;
+
+    // Mirrors Market::check_resolution_delay
+    const resolve = (now: number) => {
+      if (now < createdAt + minResolutionDelaySecs) throw new Error("ResolvedTooSoon");
+      return "resolved";
+    };
+
+    it("Rejects resolution before the minimum delay", () => {
+      expect(() => resolve(createdAt + 60)).to.throw("ResolvedTooSoon");
+    });
+
+    it("Allows resolution once the delay has passed", () => {
+      expect(resolve(createdAt + minResolutionDelaySecs)).to.equal("resolved");
+    });
+  });
+
+  // ─── Target Lock ────────────────────────────────────────────────
+
+  describe("update_target", () => {
+    // Mirrors update_target's empty-pool check
+    const updateTarget = (market: { yesPool: number; noPool: number; targetValue: number }, target: number) => {
+      if (market.yesPool + market.noPool > 0) throw new Error("MarketHasBets");
+      market.targetValue = target;
+    };
+
+    it("Updates the target on an empty market", () => {
+      const market = { yesPool: 0, noPool: 0, targetValue: 1_000_000 };
+      updateTarget(market, 2_000_000);
+      expect(market.targetValue).to.equal(2_000_000);
+    });
+
+    it("Blocks updates after the first bet", () => {
+      const market = { yesPool: 0, noPool: 500, targetValue: 1_000_000 };
+      expect(() => updateTarget(market, 2_000_000)).to.throw("MarketHasBets");
+      expect(market.targetValue).to.equal(1_000_000);
+    });
+  });
+
+  // ─── Total Claimable ────────────────────────────────────────────
+
+  describe("total_claimable", () => {
+    type Entry = {
+      status: string;
+      outcome: string;
+      side: string;
+      deposited: number;
+      settled: boolean;
+      payout?: number; // Market::calculate_payout
+      refund?: number; // Market::refund_amount
+    };
+
+    // Mirrors total_claimable's per-position rules
+    const totalClaimable = (entries: Entry[]) =>
+      entries.reduce((total, e) => {
+        if (e.settled) return total;
+        if (e.status === "resolved" && e.outcome === e.side) return total + (e.payout ?? 0);
+        if (e.status === "cancelled") return total + (e.refund ?? 0);
+        return total;
+      }, 0);
+
+    it("Sums winners and cancelled refunds, skipping losers", () => {
+      const total = totalClaimable([
+        { status: "resolved", outcome: "yes", side: "yes", deposited: 1000, settled: false, payout: 1450 },
+        { status: "resolved", outcome: "yes", side: "no", deposited: 800, settled: false },
+        { status: "cancelled", outcome: "unresolved", side: "no", deposited: 600, settled: false, refund: 600 },
+      ]);
+      expect(total).to.equal(2050);
+    });
+
+    it("Excludes already-settled positions", () => {
+      const total = totalClaimable([
+        { status: "resolved", outcome: "no", side: "no", deposited: 1000, settled: true, payout: 1200 },
+        { status: "cancelled", outcome: "unresolved", side: "yes", deposited: 500, settled: false, refund: 400 },
+      ]);
+      expect(total).to.equal(400);
+    });
+  });
+
+  // ─── Restore Solvency ───────────────────────────────────────────
+
+  describe("restore_solvency", () => {
+    const winnerPool = 5000;
+    const loserPool = 3000;
+
+    // Mirrors Market::outstanding_claims
+    const outstandingClaims = (settledStake: number) => {
+      const remainingStake = winnerPool - settledStake;
+      return remainingStake + Math.floor((loserPool * remainingStake) / winnerPool);
+    };
+
+    it("Raises h back to 100% once the top-up covers all claims", () => {
+      let vault = 6000;
+      let hRatioBps = Math.floor((vault * 10000) / (winnerPool + loserPool));
+      expect(hRatioBps).to.equal(7500);
+
+      vault += 2000; // restore_solvency(2000)
+      if (vault >= outstandingClaims(0)) hRatioBps = 10000;
+      expect(hRatioBps).to.equal(10000);
+    });
+
+    it("A partial top-up leaves h unchanged", () => {
+      const vault = 6000 + 500;
+      let hRatioBps = 7500;
+      if (vault >= outstandingClaims(0)) hRatioBps = 10000;
+      expect(hRatioBps).to.equal(7500);
+    });
+
+    it("Only counts claims not yet settled", () => {
+      // 2000 of stake already settled → 3000 capital + 1800 profit outstanding
+      expect(outstandingClaims(2000)).to.equal(4800);
+    });
+  });
+
+  // ─── Settle Return Data ─────────────────────────────────────────
+
+  describe("settle return data", () => {
+    it("Deserializes the packed payout breakdown", () => {
+      // PayoutBreakdown { capital, profit, fee, total } — four little-endian u64s
+      const expected = { capital: 1000, profit: 450, fee: 0, total: 1450 };
+      const returnData = Buffer.alloc(32);
+      returnData.writeBigUInt64LE(BigInt(expected.capital), 0);
+      returnData.writeBigUInt64LE(BigInt(expected.profit), 8);
+      returnData.writeBigUInt64LE(BigInt(expected.fee), 16);
+      returnData.writeBigUInt64LE(BigInt(expected.total), 24);
+
+      const decoded = {
+        capital: Number(returnData.readBigUInt64LE(0)),
+        profit: Number(returnData.readBigUInt64LE(8)),
+        fee: Number(returnData.readBigUInt64LE(16)),
+        total: Number(returnData.readBigUInt64LE(24)),
+      };
+
+      // Matches the payout computed from the pools (stake 1000, pools 5000/3000, h 75%)
+      const computed = 1000 + Math.floor((Math.floor((1000 * 3000) / 5000) * 7500) / 10000);
+      expect(decoded).to.deep.equal(expected);
+      expect(decoded.total).to.equal(computed);
+    });
+  });
+
+  // ─── Resolution Extension ───────────────────────────────────────
+
+  describe("request_resolution_extension", () => {
+    const maxResolutionExtensionSecs = 3 * 86400;
+
+    // Mirrors request_resolution_extension
+    const makeMarket = () => {
+      const market = { resolutionWindowEnd: 2_000_000, resolutionExtended: false };
+      const extend = (now: number, secs: number) => {
+        if (market.resolutionExtended) throw new Error("ResolutionAlreadyExtended");
+        if (now >= market.resolutionWindowEnd) throw new Error("ResolutionWindowEnded");
+        if (secs > maxResolutionExtensionSecs) throw new Error("ExtensionTooLong");
+        market.resolutionWindowEnd += secs;
+        market.resolutionExtended = true;
+      };
+      return { market, extend };
+    };
+
+    it("Allows one extension within the limit", () => {
+      const { market, extend } = makeMarket();
+      extend(1_900_000, 86400);
+      expect(market.resolutionWindowEnd).to.equal(2_086_400);
+    });
+
+    it("Rejects a second request", () => {
+      const { extend } = makeMarket();
+      extend(1_900_000, 86400);
+      expect(() => extend(1_950_000, 3600)).to.throw("ResolutionAlreadyExtended");
+    });
+
+    it("Rejects extensions beyond the configured maximum", () => {
+      const { extend } = makeMarket();
+      expect(() => extend(1_900_000, maxResolutionExtensionSecs + 1)).to.throw("ExtensionTooLong");
+    });
+  });
+
+  // ─── Post-Deadline Cancellation ─────────────────────────────────
+
+  describe("cancelling after the deadline", () => {
+    const deadline = 1_700_000_000;
+
+    // Mirrors cancel_market's deadline rule
+    const cancel = (now: number, status: string, authorityCoSigned: boolean) => {
+      const pastDeadline = status === "closed" || now >= deadline;
+      if (pastDeadline && !authorityCoSigned) throw new Error("CancelAfterDeadline");
+      return "cancelled";
+    };
+
+    it("The creator can cancel before the deadline", () => {
+      expect(cancel(deadline - 60, "open", false)).to.equal("cancelled");
+    });
+
+    it("Rejects a creator-only cancel after the deadline", () => {
+      expect(() => cancel(deadline + 60, "open", false)).to.throw("CancelAfterDeadline");
+      expect(() => cancel(deadline - 60, "closed", false)).to.throw("CancelAfterDeadline");
+    });
+
+    it("Allows it with the protocol authority co-signing", () => {
+      expect(cancel(deadline + 60, "closed", true)).to.equal("cancelled");
+    });
+  });
+
+  // ─── Vault Rent Floor ───────────────────────────────────────────
+
+  describe("assert_vault_solvent", () => {
+    const rentMin = 890_880; // Rent::minimum_balance(0)
+
+    // Mirrors assert_vault_solvent, run after each vault debit
+    const debit = (vault: number, amount: number) => {
+      const balance = vault - amount;
+      if (balance !== 0 && balance < rentMin) throw new Error("VaultInsolvency");
+      return balance;
+    };
+
+    it("Catches a debit that leaves the vault below rent exemption", () => {
+      expect(() => debit(5_000_000, 4_500_000)).to.throw("VaultInsolvency");
+    });
+
+    it("Allows debits that keep the vault rent-exempt", () => {
+      expect(debit(5_000_000, 4_000_000)).to.equal(1_000_000);
+    });
+
+    it("Allows draining the vault completely", () => {
+      expect(debit(5_000_000, 5_000_000)).to.equal(0);
+    });
+  });
+
+  // ─── Create And Bet ─────────────────────────────────────────────
+
+  describe("create_and_bet", () => {
+    const feeBps = 100;
+    const creatorShareBps = 2_000;
+
+    // Mirrors create_and_bet: create_market, then the creator's place_bet
+    const createAndBet = (side: "yes" | "no", amount: number) => {
+      const fee = Math.floor((amount * feeBps) / 10_000);
+      const creatorFee = Math.floor((fee * creatorShareBps) / 10_000);
+      const stake = amount - fee;
+      const market = { status: "open", yesPool: 0, noPool: 0, totalFeesCollected: 0 };
+      if (side === "yes") market.yesPool += stake;
+      else market.noPool += stake;
+      market.totalFeesCollected += creatorFee;
+      const position = { user: "creator", side, stake };
+      return { market, position, treasury: fee - creatorFee };
+    };
+
+    it("Creates the market and the creator's position in one call", () => {
+      const { market, position } = createAndBet("yes", 1_000_000_000);
+      expect(market.status).to.equal("open");
+      expect(position.user).to.equal("creator");
+      expect(position.stake).to.equal(990_000_000);
+    });
+
+    it("Sets the pools from the seed bet", () => {
+      const { market } = createAndBet("no", 1_000_000_000);
+      expect(market.noPool).to.equal(990_000_000);
+      expect(market.yesPool).to.equal(0);
+    });
+
+    it("Routes the protocol share of the fee to the treasury", () => {
+      const { treasury } = createAndBet("yes", 1_000_000_000);
+      expect(treasury).to.equal(8_000_000);
+    });
+  });
+
+  // ─── Scalar Resolution ──────────────────────────────────────────
+
+  describe("scalar markets", () => {
+    const lower = 1_000_000;
+    const upper = 5_000_000;
+    const yesPool = 3_000_000_000;
+    const noPool = 1_000_000_000;
+
+    // Mirrors MarketRule::scalar_share_bps
+    const yesShareBps = (v: number) => {
+      if (v <= lower) return 0;
+      if (v >= upper) return 10_000;
+      return Math.floor(((v - lower) * 10_000) / (upper - lower));
+    };
+
+    // Mirrors Market::calculate_payout for Outcome::Scalar (h = 100%)
+    const payout = (side: "yes" | "no", stake: number, v: number) => {
+      const share = side === "yes" ? yesShareBps(v) : 10_000 - yesShareBps(v);
+      const sidePool = side === "yes" ? yesPool : noPool;
+      const sideShare = Math.floor(((yesPool + noPool) * share) / 10_000);
+      return Math.floor((stake * sideShare) / sidePool);
+    };
+
+    it("Pays everything to NO below the range", () => {
+      expect(yesShareBps(500_000)).to.equal(0);
+      expect(payout("yes", yesPool, 500_000)).to.equal(0);
+      expect(payout("no", noPool, 500_000)).to.equal(yesPool + noPool);
+    });
+
+    it("Pays everything to YES above the range", () => {
+      expect(yesShareBps(9_000_000)).to.equal(10_000);
+      expect(payout("yes", yesPool, 9_000_000)).to.equal(yesPool + noPool);
+      expect(payout("no", noPool, 9_000_000)).to.equal(0);
+    });
+
+    it("Splits the pool proportionally mid-range", () => {
+      // v at 75% of the range → YES gets 75% of the 4 SOL pool
+      const v = 4_000_000;
+      expect(yesShareBps(v)).to.equal(7_500);
+      expect(payout("yes", 1_500_000_000, v)).to.equal(1_500_000_000);
+      expect(payout("no", 500_000_000, v)).to.equal(500_000_000);
+    });
+
+    it("Never pays out more than the pool", () => {
+      const v = 2_345_678;
+      const total = payout("yes", yesPool, v) + payout("no", noPool, v);
+      expect(total).to.be.at.most(yesPool + noPool);
+    });
+
+    it("Rejects a range with lower >= upper", () => {
+      const validate = (lo: number, hi: number) => {
+        if (lo >= hi) throw new Error("InvalidScalarRange");
+      };
+      expect(() => validate(upper, lower)).to.throw("InvalidScalarRange");
+    });
+  });
+
+  // ─── Settle Ordering ────────────────────────────────────────────
+
+  describe("settle_order", () => {
+    const stakes = [1_000_000_000, 2_000_000_000, 500_000_000, 1_500_000_000, 1_000_000_000];
+    const winnerPool = stakes.reduce((a, b) => a + b, 0);
+    const profitPool = 3_000_000_000;
+    const startVault = winnerPool + profitPool - 1_000_000_000; // 1 SOL short
+
+    // Mirrors settle_position in Haircut mode with a settle_order guard
+    const newMarket = () => ({ vault: startVault, settledStake: 0, settlementsCount: 0, h: 10_000 });
+    const settle = (m: ReturnType<typeof newMarket>, stake: number, order: number) => {
+      if (order !== m.settlementsCount) throw new Error("StaleSettleOrder");
+      const remainingStake = winnerPool - m.settledStake;
+      const remainingProfit = Math.floor((profitPool * remainingStake) / winnerPool);
+      const fresh = Math.min(10_000, Math.floor(((m.vault - remainingStake) * 10_000) / remainingProfit));
+      m.h = Math.min(m.h, fresh);
+      const payout = stake + Math.floor((Math.floor((stake * profitPool) / winnerPool) * m.h) / 10_000);
+      m.vault -= payout;
+      m.settledStake += stake;
+      m.settlementsCount += 1;
+      return payout;
+    };
+
+    it("Rejects a settle built against a stale snapshot", () => {
+      const m = newMarket();
+      settle(m, stakes[0], 0);
+      expect(() => settle(m, stakes[1], 0)).to.throw("StaleSettleOrder");
+    });
+
+    it("Pays the same total whether five winners settle in order or interleaved", () => {
+      const inOrder = newMarket();
+      const paidInOrder = stakes.reduce((sum, s) => sum + settle(inOrder, s, inOrder.settlementsCount), 0);
+
+      // Five settles built at once against order 0; losers re-read and retry
+      const interleaved = newMarket();
+      const pending = [3, 0, 4, 1, 2];
+      let paidInterleaved = 0;
+      const snapshot = 0;
+      for (const i of pending) {
+        let order = snapshot;
+        for (;;) {
+          try {
+            paidInterleaved += settle(interleaved, stakes[i], order);
+            break;
+          } catch {
+            order = interleaved.settlementsCount;
+          }
+        }
+      }
+
+      expect(paidInOrder).to.be.at.most(startVault);
+      expect(paidInterleaved).to.be.at.most(startVault);
+      expect(paidInterleaved).to.equal(paidInOrder);
+      expect(interleaved.settlementsCount).to.equal(5);
+    });
+  });
+
+  // ─── Config Shutdown ────────────────────────────────────────────
+
+  describe("close_config", () => {
+    // Mirrors GlobalConfig::has_active_markets as checked by close_config
+    const closeConfig = (totalMarkets: number, terminalMarkets: number) => {
+      if (terminalMarkets < totalMarkets) throw new Error("MarketsStillActive");
+      return "closed";
+    };
+
+    it("Rejects shutdown while any market is active", () => {
+      expect(() => closeConfig(5, 4)).to.throw("MarketsStillActive");
+    });
+
+    it("Closes once every market is Settled or Cancelled", () => {
+      const statuses = ["settled", "cancelled", "settled"];
+      const terminal = statuses.filter((s) => s === "settled" || s === "cancelled").length;
+      expect(closeConfig(statuses.length, terminal)).to.equal("closed");
+    });
+
+    it("Closes a config that never created a market", () => {
+      expect(closeConfig(0, 0)).to.equal("closed");
+    });
+  });
+
+  // ─── Question Length Limit ──────────────────────────────────────
+
+  describe("max_question_len", () => {
+    // Mirrors GlobalConfig::question_limit as checked by create_market
+    const validate = (question: string, maxQuestionLen: number) => {
+      const limit = maxQuestionLen === 0 ? 256 : maxQuestionLen;
+      if (Buffer.byteLength(question) > limit) throw new Error("QuestionTooLong");
+      return true;
+    };
+
+    it("Accepts a question within the configured limit", () => {
+      expect(validate("Will BONK hit $1B?", 64)).to.equal(true);
+    });
+
+    it("Rejects a question beyond the configured limit", () => {
+      expect(() => validate("x".repeat(65), 64)).to.throw("QuestionTooLong");
+    });
+
+    it("Falls back to the 256-byte ceiling when unset", () => {
+      expect(validate("x".repeat(256), 0)).to.equal(true);
+      expect(() => validate("x".repeat(257), 0)).to.throw("QuestionTooLong");
+    });
+  });
+
+  // ─── Off-chain Question ─────────────────────────────────────────
+
+  describe("question_hash / question_uri", () => {
+    const fullText =
+      "Resolves YES if the 24h VWAP across the top three venues exceeds $1.00 " +
+      "at any point before the deadline, excluding wicks under one minute.";
+    const uri = "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+    // Mirrors create_market storing the params verbatim
+    const create = (questionHash: Buffer, questionUri: string) => {
+      if (Buffer.byteLength(questionUri) > 128) throw new Error("QuestionUriTooLong");
+      return Object.freeze({ questionHash: Buffer.from(questionHash), questionUri });
+    };
+
+    it("Round-trips the hash and uri", () => {
+      const hash = createHash("sha256").update(fullText).digest();
+      const market = create(hash, uri);
+      expect(market.questionUri).to.equal(uri);
+      // A UI fetches the text from the uri and checks it against the hash
+      expect(createHash("sha256").update(fullText).digest().equals(market.questionHash)).to.equal(true);
+      expect(createHash("sha256").update(fullText + " ").digest().equals(market.questionHash)).to.equal(false);
+    });
+
+    it("Rejects a uri longer than 128 bytes", () => {
+      expect(() => create(Buffer.alloc(32), "ipfs://" + "a".repeat(122))).to.throw("QuestionUriTooLong");
+    });
+
+    it("Is immutable after creation", () => {
+      // No instruction writes question_hash or question_uri after create_market
+      const market = create(Buffer.alloc(32, 1), uri);
+      expect(() => {
+        (market as any).questionUri = "ipfs://other";
+      }).to.throw();
+      expect(market.questionUri).to.equal(uri);
+    });
+  });
+
+  // ─── Batch Market Creation ──────────────────────────────────────
+
+  describe("create_markets_batch", () => {
+    const MAX_BATCH_MARKETS = 4;
+
+    // Mirrors create_markets_batch: each group runs create_market in order
+    const createBatch = (
+      config: { nextMarketId: number; totalMarkets: number },
+      stats: { activeMarkets: number },
+      questions: string[],
+      maxMarketsPerCreator = 0,
+    ) => {
+      if (questions.length === 0 || questions.length > MAX_BATCH_MARKETS) throw new Error("InvalidBatchSize");
+      const next = { ...config };
+      const nextStats = { ...stats };
+      const markets = questions.map((question) => {
+        if (maxMarketsPerCreator !== 0 && nextStats.activeMarkets >= maxMarketsPerCreator) {
+          throw new Error("CreatorMarketLimit");
+        }
+        const market = { marketId: next.nextMarketId, question };
+        next.nextMarketId += 1;
+        next.totalMarkets += 1;
+        nextStats.activeMarkets += 1;
+        return market;
+      });
+      // Atomic: state only changes if every market was created
+      Object.assign(config, next);
+      Object.assign(stats, nextStats);
+      return markets;
+    };
+
+    it("Creates three markets with sequential ids", () => {
+      const config = { nextMarketId: 7, totalMarkets: 7 };
+      const markets = createBatch(config, { activeMarkets: 0 }, ["Game 1", "Game 2", "Game 3"]);
+      expect(markets.map((m) => m.marketId)).to.deep.equal([7, 8, 9]);
+      expect(config.nextMarketId).to.equal(10);
+      expect(config.totalMarkets).to.equal(10);
+    });
+
+    it("Applies the per-creator limit across the batch, atomically", () => {
+      const config = { nextMarketId: 0, totalMarkets: 0 };
+      const stats = { activeMarkets: 1 };
+      expect(() => createBatch(config, stats, ["A", "B", "C"], 3)).to.throw("CreatorMarketLimit");
+      expect(config.nextMarketId).to.equal(0);
+      expect(stats.activeMarkets).to.equal(1);
+    });
+
+    it("Rejects empty and oversized batches", () => {
+      const config = { nextMarketId: 0, totalMarkets: 0 };
+      expect(() => createBatch(config, { activeMarkets: 0 }, [])).to.throw("InvalidBatchSize");
+      expect(() => createBatch(config, { activeMarkets: 0 }, ["1", "2", "3", "4", "5"])).to.throw("InvalidBatchSize");
+    });
+  });
+
+  // ─── Market Templates ───────────────────────────────────────────
+
+  describe("market templates", () => {
+    const template = {
+      rule: { marketCapTarget: {} },
+      oracle: "oracle-1",
+      targetValue: 10_000_000,
+      durationSecs: 86_400,
+      resolutionWindowSecs: 3_600,
+      defaultOutcome: { unresolved: {} },
+      insolvencyMode: { haircut: {} },
+      creatorBettingAllowed: false,
+      oracleBettingAllowed: true,
+    };
+
+    // Mirrors create_from_template building CreateMarketParams
+    const createFromTemplate = (t: typeof template, question: string, tokenMint: string, now: number) => ({
+      question,
+      tokenMint,
+      rule: t.rule,
+      oracle: t.oracle,
+      targetValue: t.targetValue,
+      deadline: now + t.durationSecs,
+      resolutionWindowEnd: now + t.durationSecs + t.resolutionWindowSecs,
+      defaultOutcome: t.defaultOutcome,
+      insolvencyMode: t.insolvencyMode,
+      creatorBettingDisabled: !t.creatorBettingAllowed,
+      oracleBettingDisabled: !t.oracleBettingAllowed,
+    });
+
+    it("Inherits every setting except the question and token mint", () => {
+      const now = 1_700_000_000;
+      const market = createFromTemplate(template, "Will WIF flip BONK?", "wif-mint", now);
+      expect(market.question).to.equal("Will WIF flip BONK?");
+      expect(market.tokenMint).to.equal("wif-mint");
+      expect(market.rule).to.deep.equal(template.rule);
+      expect(market.oracle).to.equal(template.oracle);
+      expect(market.targetValue).to.equal(template.targetValue);
+      expect(market.deadline).to.equal(now + 86_400);
+      expect(market.resolutionWindowEnd).to.equal(now + 90_000);
+      expect(market.insolvencyMode).to.deep.equal({ haircut: {} });
+      expect(market.creatorBettingDisabled).to.equal(true);
+    });
+
+    it("Picks up template updates for later markets", () => {
+      const updated = { ...template, targetValue: 20_000_000 };
+      expect(createFromTemplate(updated, "q", "mint", 0).targetValue).to.equal(20_000_000);
+    });
+
+    it("Rejects a template with a non-positive duration", () => {
+      const upsert = (durationSecs: number) => {
+        if (durationSecs <= 0) throw new Error("DeadlineInPast");
+      };
+      expect(() => upsert(0)).to.throw("DeadlineInPast");
+    });
+  });
+
+  // ─── Conditional Markets ────────────────────────────────────────
+
+  describe("conditional markets", () => {
+    type Status = "open" | "closed" | "resolved" | "settled" | "cancelled";
+    type Outcome = "unresolved" | "yes" | "no";
+
+    // Mirrors Market::parent_condition_met + void_if_parent_failed in resolve
+    const resolveChild = (parent: { status: Status; outcome: Outcome }, required: Outcome, outcome: Outcome) => {
+      if (parent.status === "open" || parent.status === "closed") {
+        throw new Error("ParentNotResolved");
+      }
+      const met = parent.status !== "cancelled" && parent.outcome === required;
+      return met ? { status: "resolved", outcome } : { status: "cancelled", outcome: "unresolved" };
+    };
+
+    it("Cancels the child when the parent resolves the wrong way", () => {
+      const child = resolveChild({ status: "resolved", outcome: "no" }, "yes", "yes");
+      expect(child.status).to.equal("cancelled");
+    });
+
+    it("Allows resolution when the parent resolved the required way", () => {
+      const child = resolveChild({ status: "settled", outcome: "yes" }, "yes", "no");
+      expect(child).to.deep.equal({ status: "resolved", outcome: "no" });
+    });
+
+    it("Cancels the child when the parent was cancelled", () => {
+      expect(resolveChild({ status: "cancelled", outcome: "unresolved" }, "yes", "yes").status).to.equal("cancelled");
+    });
+
+    it("Rejects resolution before the parent resolves", () => {
+      expect(() => resolveChild({ status: "open", outcome: "unresolved" }, "yes", "yes")).to.throw("ParentNotResolved");
+    });
+  });
+
+  // ─── Early-bird Bonus ───────────────────────────────────────────
+
+  describe("early-bird bonus", () => {
+    const bonusBps = 5_000; // +50% subsidy-share weight
+    const windowSecs = 3_600;
+    const createdAt = 1_700_000_000;
+    const loserPool = 2_000_000_000;
+    const subsidy = 1_000_000_000;
+
+    // Mirrors record_bet's bonus_stake
+    const bonusFor = (stake: number, betAt: number) =>
+      betAt < createdAt + windowSecs ? Math.floor((stake * bonusBps) / 10_000) : 0;
+
+    // Mirrors Market::payout_breakdown (h = 100%)
+    const payout = (stake: number, bonus: number, winnerPool: number, bonusPool: number) => {
+      const profit =
+        Math.floor((stake * loserPool) / winnerPool) +
+        Math.floor(((stake + bonus) * subsidy) / (winnerPool + bonusPool));
+      return { capital: stake, profit };
+    };
+
+    const stake = 1_000_000_000;
+    const earlyBonus = bonusFor(stake, createdAt + 60);
+    const lateBonus = bonusFor(stake, createdAt + windowSecs + 60);
+    const winnerPool = 2 * stake;
+    const bonusPool = earlyBonus + lateBonus;
+
+    it("Grants the bonus only inside the window", () => {
+      expect(earlyBonus).to.equal(500_000_000);
+      expect(lateBonus).to.equal(0);
+    });
+
+    it("Pays the early bettor more profit for an equal deposit", () => {
+      const early = payout(stake, earlyBonus, winnerPool, bonusPool);
+      const late = payout(stake, lateBonus, winnerPool, bonusPool);
+      expect(early.profit).to.be.greaterThan(late.profit);
+      // 1.5 : 1 split of the subsidy, equal split of the loser pool
+      expect(early.profit).to.equal(1_000_000_000 + 600_000_000);
+      expect(late.profit).to.equal(1_000_000_000 + 400_000_000);
+    });
+
+    it("Keeps capital at the actual deposit", () => {
+      expect(payout(stake, earlyBonus, winnerPool, bonusPool).capital).to.equal(stake);
+    });
+
+    it("Never distributes more than loser pool + subsidy", () => {
+      const early = payout(stake, earlyBonus, winnerPool, bonusPool);
+      const late = payout(stake, lateBonus, winnerPool, bonusPool);
+      expect(early.profit + late.profit).to.be.at.most(loserPool + subsidy);
+    });
+  });
+
+  // ─── h-ratio Floor ──────────────────────────────────────────────
+
+  describe("h_ratio_floor_bps", () => {
+    const floorBps = 5_000;
+    const totalClaims = 10_000_000_000; // stakes + matched subsidy
+
+    // Mirrors compute_h_ratio and Market::floor_shortfall_for
+    const hRatio = (vault: number) => (vault >= totalClaims ? 10_000 : Math.floor((vault * 10_000) / totalClaims));
+    const shortfall = (vault: number) => Math.max(0, Math.ceil((totalClaims * floorBps) / 10_000) - vault);
+
+    // Mirrors the floor check in settle_position
+    const settle = (market: { floorShortfall: number }) => {
+      if (market.floorShortfall > 0) throw new Error("HRatioBelowFloor");
+      return "settled";
+    };
+
+    it("Blocks settlement when the computed h is below the floor", () => {
+      const vault = 3_000_000_000;
+      expect(hRatio(vault)).to.equal(3_000);
+      const market = { floorShortfall: shortfall(vault) };
+      expect(market.floorShortfall).to.equal(2_000_000_000);
+      expect(() => settle(market)).to.throw("HRatioBelowFloor");
+    });
+
+    it("Unblocks once restore_solvency lifts h to the floor", () => {
+      let vault = 3_000_000_000;
+      const market = { floorShortfall: shortfall(vault) };
+      vault += 1_000_000_000; // partial top-up
+      market.floorShortfall = shortfall(vault);
+      expect(() => settle(market)).to.throw("HRatioBelowFloor");
+      vault += 1_000_000_000;
+      market.floorShortfall = shortfall(vault);
+      expect(hRatio(vault)).to.equal(floorBps);
+      expect(settle(market)).to.equal("settled");
+    });
+
+    it("Does nothing when h is already above the floor", () => {
+      expect(shortfall(8_000_000_000)).to.equal(0);
+    });
+  });
+
+  // ─── Time-weighted Profit ───────────────────────────────────────
+
+  describe("time-weighted profit shares", () => {
+    const deadline = 1_700_086_400;
+    const loserPool = 3_000;
+
+    // Mirrors record_bet's weighted_stake
+    const weight = (deposit: number, betAt: number) => deposit * Math.max(0, deadline - betAt);
+
+    // Mirrors Market::payout_breakdown for a time_weighted market (h = 100%)
+    const payout = (deposit: number, weighted: number, weightedPool: number) => ({
+      capital: deposit,
+      profit: Math.floor((weighted * loserPool) / weightedPool),
+    });
+
+    const deposit = 1_000;
+    const early = weight(deposit, deadline - 72_000); // 20h before the deadline
+    const late = weight(deposit, deadline - 14_400); // 4h before
+    const pool = early + late;
+
+    it("Pays the earlier bettor more profit for an equal deposit", () => {
+      const a = payout(deposit, early, pool);
+      const b = payout(deposit, late, pool);
+      expect(a.profit).to.be.greaterThan(b.profit);
+      expect(a.profit).to.equal(2_500);
+      expect(b.profit).to.equal(500);
+    });
+
+    it("Keeps capital unweighted", () => {
+      expect(payout(deposit, late, pool).capital).to.equal(deposit);
+    });
+
+    it("Distributes no more than the loser pool", () => {
+      expect(payout(deposit, early, pool).profit + payout(deposit, late, pool).profit).to.be.at.most(loserPool);
+    });
+
+    it("Rejects combining time weighting with an early-bird bonus", () => {
+      const validate = (timeWeighted: boolean, earlyBirdBonusBps: number) => {
+        if (timeWeighted && earlyBirdBonusBps > 0) throw new Error("InvalidScoringMode");
+      };
+      expect(() => validate(true, 1_000)).to.throw("InvalidScoringMode");
+    });
+  });
+
+  // ─── Settle Many ────────────────────────────────────────────────
+
+  describe("settle_many", () => {
+    const MAX_SETTLE_MANY = 6;
+
+    type Group = { market: string; status: string; owner: string; settled: boolean; stake: number; winnerPool: number; loserPool: number };
+
+    // Mirrors settle_many::handler over (market, position, vault, creator_stats) groups
+    const settleMany = (user: string, groups: Group[]) => {
+      if (groups.length === 0 || groups.length > MAX_SETTLE_MANY) throw new Error("InvalidBatchSize");
+      let total = 0;
+      for (const g of groups) {
+        if (g.status !== "resolved") throw new Error("InvalidMarketStatus");
+        if (g.owner !== user) throw new Error("NoPosition");
+        if (g.settled) throw new Error("AlreadySettled");
+        total += g.stake + Math.floor((g.stake * g.loserPool) / g.winnerPool);
+        g.settled = true;
+      }
+      return total;
+    };
+
+    const group = (market: string): Group => ({
+      market, status: "resolved", owner: "alice", settled: false, stake: 1_000, winnerPool: 2_000, loserPool: 1_000,
+    });
+
+    it("Settles positions across two resolved markets in one call", () => {
+      const a = group("A");
+      const b = { ...group("B"), loserPool: 4_000 };
+      expect(settleMany("alice", [a, b])).to.equal(1_500 + 3_000);
+      expect(a.settled && b.settled).to.equal(true);
+    });
+
+    it("Rejects a repeated group", () => {
+      const a = group("A");
+      expect(() => settleMany("alice", [a, a])).to.throw("AlreadySettled");
+    });
+
+    it("Rejects another user's position", () => {
+      expect(() => settleMany("bob", [group("A")])).to.throw("NoPosition");
+    });
+
+    it("Rejects an unresolved market", () => {
+      expect(() => settleMany("alice", [{ ...group("A"), status: "open" }])).to.throw("InvalidMarketStatus");
+    });
+
+    it("Caps the number of groups", () => {
+      const groups = Array.from({ length: MAX_SETTLE_MANY + 1 }, (_, i) => group(`M${i}`));
+      expect(() => settleMany("alice", groups)).to.throw("InvalidBatchSize");
+    });
+
+    // Mirrors settle_many's seed checks on each group's position and stats keys
+    const checkGroupKeys = (market: PublicKey, marketCreator: PublicKey, user: PublicKey, position: PublicKey, stats: PublicKey) => {
+      const [statsPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("creator_stats"), marketCreator.toBuffer()],
+        program.programId
+      );
+      if (!stats.equals(statsPda)) throw new Error("ConstraintSeeds");
+      const [positionPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("position"), market.toBuffer(), user.toBuffer()],
+        program.programId
+      );
+      if (!position.equals(positionPda)) throw new Error("ConstraintSeeds");
+    };
+
+    it("Rejects an empty stand-in for the creator stats or another position account", () => {
+      const market = Keypair.generate().publicKey;
+      const user = bettorYes.publicKey;
+      const [position] = PublicKey.findProgramAddressSync(
+        [Buffer.from("position"), market.toBuffer(), user.toBuffer()],
+        program.programId
+      );
+      const [stats] = PublicKey.findProgramAddressSync(
+        [Buffer.from("creator_stats"), creator.publicKey.toBuffer()],
+        program.programId
+      );
+      checkGroupKeys(market, creator.publicKey, user, position, stats);
+      // A fresh keypair is empty like an uncreated PDA, but would skip close_market
+      const emptyStandIn = Keypair.generate().publicKey;
+      expect(() => checkGroupKeys(market, creator.publicKey, user, position, emptyStandIn)).to.throw("ConstraintSeeds");
+      expect(() => checkGroupKeys(market, creator.publicKey, user, stats, stats)).to.throw("ConstraintSeeds");
+    });
+  });
+
+  // ─── Token Mint Validation ──────────────────────────────────────
+
+  describe("token mint validation", () => {
+    const TOKEN_PROGRAM = TOKEN_PROGRAM_ID.toBase58();
+    const MINT_LEN = 82;
+
+    type Info = { key: string; owner: string; dataLen: number; initialized: boolean };
+
+    // Mirrors create_market's token_mint checks (is_initialized_mint)
+    const validate = (paramsMint: string, info: Info) => {
+      if (info.key !== paramsMint) throw new Error("InvalidTokenMint");
+      if (info.owner !== TOKEN_PROGRAM || info.dataLen !== MINT_LEN || !info.initialized) {
+        throw new Error("InvalidTokenMint");
+      }
+    };
+
+    const mint = Keypair.generate().publicKey.toBase58();
+
+    it("Accepts an initialized SPL mint", () => {
+      const info = { key: mint, owner: TOKEN_PROGRAM, dataLen: MINT_LEN, initialized: true };
+      expect(() => validate(mint, info)).to.not.throw();
+    });
+
+    it("Rejects a garbage pubkey with no account data", () => {
+      const garbage = Keypair.generate().publicKey.toBase58();
+      const info = { key: garbage, owner: SystemProgram.programId.toBase58(), dataLen: 0, initialized: false };
+      expect(() => validate(garbage, info)).to.throw("InvalidTokenMint");
+    });
+
+    it("Rejects the default pubkey", () => {
+      const zero = PublicKey.default.toBase58();
+      const info = { key: zero, owner: SystemProgram.programId.toBase58(), dataLen: 0, initialized: false };
+      expect(() => validate(zero, info)).to.throw("InvalidTokenMint");
+    });
+
+    it("Rejects an account that does not match params.token_mint", () => {
+      const info = { key: mint, owner: TOKEN_PROGRAM, dataLen: MINT_LEN, initialized: true };
+      expect(() => validate(Keypair.generate().publicKey.toBase58(), info)).to.throw("InvalidTokenMint");
+    });
+  });
+
+  // ─── Outstanding Claims ─────────────────────────────────────────
+
+  describe("outstanding_claims", () => {
+    // Resolved YES: winners hold 2,000 (two bettors of 1,000), losers 1,000
+    const winnerPool = 2_000;
+    const loserPool = 1_000;
+    const hRatioBps = 8_000;
+
+    // Mirrors Market::owed_at_h
+    const owedAtH = (settledStake: number) => {
+      const remainingStake = winnerPool - settledStake;
+      const profit = Math.floor((remainingStake * loserPool) / winnerPool);
+      return remainingStake + Math.floor((profit * hRatioBps) / 10_000);
+    };
+
+    // Mirrors Market::payout_breakdown for one 1,000 stake
+    const payout = 1_000 + Math.floor((Math.floor((1_000 * loserPool) / winnerPool) * hRatioBps) / 10_000);
+
+    it("Reports capital plus profit after h before any settlement", () => {
+      expect(owedAtH(0)).to.equal(2_000 + 800);
+    });
+
+    it("Drops by the settled payout after one settlement", () => {
+      const before = owedAtH(0);
+      const after = owedAtH(1_000);
+      expect(before - after).to.equal(payout);
+      expect(after).to.equal(1_400);
+    });
+
+    it("Flags under-collateralization against the vault balance", () => {
+      expect(owedAtH(0) > 2_500).to.equal(true);
+      expect(owedAtH(0) > 2_800).to.equal(false);
+    });
+  });
+
+  // ─── Same-Slot Resolution Guard ─────────────────────────────────
+
+  describe("same-slot resolution guard", () => {
+    // Mirrors record_bet storing last_bet_slot and Market::check_bet_slot
+    const market = { lastBetSlot: 0 };
+    const placeBet = (slot: number) => { market.lastBetSlot = slot; };
+    const resolve = (slot: number) => {
+      if (slot === market.lastBetSlot) throw new Error("ResolveSameSlotAsBet");
+      return "resolved";
+    };
+
+    it("Blocks resolution in the slot of the latest bet", () => {
+      placeBet(1_000);
+      expect(() => resolve(1_000)).to.throw("ResolveSameSlotAsBet");
+    });
+
+    it("Allows resolution from the next slot", () => {
+      placeBet(1_000);
+      expect(resolve(1_001)).to.equal("resolved");
+    });
+
+    it("Tracks only the latest bet", () => {
+      placeBet(1_000);
+      placeBet(1_005);
+      expect(resolve(1_000)).to.equal("resolved");
+      expect(() => resolve(1_005)).to.throw("ResolveSameSlotAsBet");
+    });
+  });
+
+  // ─── Rounding Mode ──────────────────────────────────────────────
+
+  describe("rounding mode", () => {
+    // Three winners of 1,000 each share a 1,000 loser pool
+    const winnerPool = 3_000;
+    const loserPool = 1_000;
+    const stakes = [1_000, 1_000, 1_000];
+
+    // Mirrors RoundingMode::div
+    const div = (mode: string, n: number, d: number) =>
+      mode === "floor" ? Math.floor(n / d) : Math.floor((n + Math.floor(d / 2)) / d);
+
+    // Mirrors Market::position_profit_claim across sequential settlements
+    const settleAll = (mode: string, pool: number, stakeList: number[]) => {
+      let settledProfit = 0;
+      return stakeList.map((stake) => {
+        const claim = Math.min(div(mode, stake * pool, winnerPool), pool - settledProfit);
+        settledProfit += claim;
+        return claim;
+      });
+    };
+
+    const sum = (xs: number[]) => xs.reduce((a, b) => a + b, 0);
+
+    it("Floor leaves dust in the vault", () => {
+      const profits = settleAll("floor", loserPool, stakes);
+      expect(profits).to.deep.equal([333, 333, 333]);
+      expect(sum(profits)).to.equal(999);
+    });
+
+    it("Nearest rounds each share up from a half", () => {
+      // 2,000 / 3 = 666.67 rounds to 667
+      expect(settleAll("nearest", 2_000, stakes)).to.deep.equal([667, 667, 666]);
+    });
+
+    it("Caps the final settler so the total never exceeds the pool", () => {
+      const profits = settleAll("nearest", 2_000, stakes);
+      expect(sum(profits)).to.equal(2_000);
+      expect(sum(settleAll("floor", 2_000, stakes))).to.equal(1_998);
+    });
+
+    it("Distributes at least as much under nearest as under floor", () => {
+      for (const pool of [1_000, 2_000, 2_999]) {
+        const nearest = sum(settleAll("nearest", pool, stakes));
+        expect(nearest).to.be.at.least(sum(settleAll("floor", pool, stakes)));
+        expect(nearest).to.be.at.most(pool);
+      }
+    });
+  });
+
+  // ─── Oracle Heartbeat ───────────────────────────────────────────
+
+  describe("oracle heartbeat", () => {
+    const heartbeatTimeoutSecs = 3_600;
+    const createdAt = 1_700_000_000;
+
+    // Mirrors Market::check_heartbeat as called by resolve_with_rule
+    const checkHeartbeat = (lastHeartbeat: number, now: number, timeout = heartbeatTimeoutSecs) => {
+      if (timeout !== 0 && now - lastHeartbeat > timeout) throw new Error("OracleHeartbeatStale");
+    };
+
+    it("Blocks automated resolution after a missed heartbeat", () => {
+      expect(() => checkHeartbeat(createdAt, createdAt + 7_200)).to.throw("OracleHeartbeatStale");
+    });
+
+    it("Allows resolution after a fresh heartbeat", () => {
+      const lastHeartbeat = createdAt + 6_000; // heartbeat (or checkpoint) posted
+      expect(() => checkHeartbeat(lastHeartbeat, createdAt + 7_200)).to.not.throw();
+    });
+
+    it("Skips the check when the timeout is 0", () => {
+      expect(() => checkHeartbeat(createdAt, createdAt + 1_000_000, 0)).to.not.throw();
+    });
+  });
+
+  // ─── Frozen Cash-Out ────────────────────────────────────────────
+
+  describe("frozen cash-out", () => {
+    const newMarket = () => ({ frozen: false, yesPool: 3_000, yesPositions: 2, vault: 3_000 });
+
+    // Mirrors place_bet's frozen constraint
+    const placeBet = (market: ReturnType<typeof newMarket>, stake: number) => {
+      if (market.frozen) throw new Error("MarketFrozen");
+      market.yesPool += stake;
+      market.vault += stake;
+    };
+
+    // Mirrors cash_out::handler
+    const cashOut = (market: ReturnType<typeof newMarket>, deposited: number) => {
+      if (!market.frozen) throw new Error("MarketNotFrozen");
+      market.yesPool -= deposited;
+      market.yesPositions -= 1;
+      market.vault -= deposited;
+      return deposited;
+    };
+
+    it("Blocks new bets while frozen", () => {
+      const market = newMarket();
+      market.frozen = true;
+      expect(() => placeBet(market, 1_000)).to.throw("MarketFrozen");
+    });
+
+    it("Refunds the full deposit with no exit fee while frozen", () => {
+      const market = newMarket();
+      market.frozen = true;
+      expect(cashOut(market, 1_000)).to.equal(1_000);
+      expect(market.yesPool).to.equal(2_000);
+      expect(market.yesPositions).to.equal(1);
+      expect(market.vault).to.equal(2_000);
+    });
+
+    it("Is unavailable on a market that is not frozen", () => {
+      expect(() => cashOut(newMarket(), 1_000)).to.throw("MarketNotFrozen");
+    });
+  });
+
+  // ─── Hedge Markets ──────────────────────────────────────────────
+
+  describe("hedge markets", () => {
+    // Mirrors MarketRule::inverse
+    const inverse = (rule: string): string | null =>
+      rule === "marketCapTarget" ? "marketCapCeiling"
+        : rule === "marketCapCeiling" ? "marketCapTarget"
+        : null;
+
+    // Mirrors MarketRule::evaluate for the two invertible rules
+    const evaluate = (rule: string, observed: number, target: number) =>
+      rule === "marketCapCeiling" ? observed < target : observed >= target;
+
+    const parent = {
+      key: Keypair.generate().publicKey.toBase58(),
+      rule: "marketCapTarget",
+      targetValue: 1_000_000,
+      deadline: 1_700_003_600,
+    };
+
+    // Mirrors spawn_hedge_market::handler
+    const spawn = (hedged: typeof parent) => {
+      const rule = inverse(hedged.rule);
+      if (rule === null) throw new Error("InvalidMarketRule");
+      return { rule, targetValue: hedged.targetValue, deadline: hedged.deadline, hedgeOf: hedged.key };
+    };
+
+    it("Links the hedge to its parent", () => {
+      const hedge = spawn(parent);
+      expect(hedge.hedgeOf).to.equal(parent.key);
+      expect(hedge.rule).to.equal("marketCapCeiling");
+      expect(hedge.deadline).to.equal(parent.deadline);
+    });
+
+    it("Resolves opposite to the parent for any observation", () => {
+      const hedge = spawn(parent);
+      for (const observed of [0, 999_999, 1_000_000, 5_000_000]) {
+        expect(evaluate(hedge.rule, observed, hedge.targetValue))
+          .to.equal(!evaluate(parent.rule, observed, parent.targetValue));
+      }
+    });
+
+    it("Rejects rules without an inverse", () => {
+      expect(() => spawn({ ...parent, rule: "oracleCustom" })).to.throw("InvalidMarketRule");
+    });
+  });
+
+  // ─── Checked Payout Math ────────────────────────────────────────
+
+  describe("checked payout math", () => {
+    // Products are tracked by bit width: a × b fits in u128 iff the widths sum
+    // to at most 128 (conservative, as the on-chain checked_mul is exact)
+    const U64_BITS = 64;
+    const I64_BITS = 63;
+
+    // Mirrors the checked_mul in Market::profit_claim
+    const checkedMul = (aBits: number, bBits: number) => {
+      if (aBits + bBits > 128) throw new Error("Overflow");
+      return aBits + bBits;
+    };
+
+    it("Handles maximal u64 stake × u64 loser pool without overflow", () => {
+      expect(checkedMul(U64_BITS, U64_BITS)).to.equal(128);
+    });
+
+    it("Surfaces an overflowing time-weighted product as Overflow, not a zero payout", () => {
+      // weighted_stake = deposit × committed_secs can reach u64 × i64
+      const weightedBits = U64_BITS + I64_BITS;
+      // distributable = loser_pool + subsidy can exceed u64
+      const distributableBits = U64_BITS + 1;
+      expect(() => checkedMul(weightedBits, distributableBits)).to.throw("Overflow");
+    });
+
+    it("Rejects a profit that does not fit in u64 lamports", () => {
+      // Mirrors to_u64 in payout_breakdown
+      const toU64 = (bits: number) => {
+        if (bits > U64_BITS) throw new Error("Overflow");
+        return bits;
+      };
+      expect(() => toU64(U64_BITS + 1)).to.throw("Overflow");
+      expect(toU64(U64_BITS)).to.equal(U64_BITS);
+    });
+  });
+
+  // ─── Cleanup Positions ──────────────────────────────────────────
+
+  describe("cleanup positions", () => {
+    const RENT = 1_900_000;
+
+    type Pair = { status: string; outcome: string; side: string; settled: boolean; open: boolean };
+
+    // Mirrors cleanup_positions::handler over (market, position) pairs
+    const cleanup = (pairs: Pair[]) => {
+      let reclaimed = 0;
+      for (const p of pairs) {
+        const lost = (p.status === "resolved" || p.status === "settled") && p.side !== p.outcome;
+        if (!p.settled && !lost) continue;
+        reclaimed += RENT;
+        p.open = false;
+      }
+      return reclaimed;
+    };
+
+    it("Closes a mix of settled and losing positions in one call", () => {
+      const settledWinner = { status: "resolved", outcome: "yes", side: "yes", settled: true, open: true };
+      const loser = { status: "resolved", outcome: "yes", side: "no", settled: false, open: true };
+      const refunded = { status: "cancelled", outcome: "unresolved", side: "no", settled: true, open: true };
+      expect(cleanup([settledWinner, loser, refunded])).to.equal(3 * RENT);
+      expect([settledWinner, loser, refunded].every((p) => !p.open)).to.equal(true);
+    });
+
+    it("Leaves still-claimable winning positions open", () => {
+      const winner = { status: "resolved", outcome: "yes", side: "yes", settled: false, open: true };
+      const loser = { status: "resolved", outcome: "yes", side: "no", settled: false, open: true };
+      expect(cleanup([winner, loser])).to.equal(RENT);
+      expect(winner.open).to.equal(true);
+    });
+
+    it("Leaves positions in unresolved markets open", () => {
+      const pending = { status: "open", outcome: "unresolved", side: "no", settled: false, open: true };
+      expect(cleanup([pending])).to.equal(0);
+      expect(pending.open).to.equal(true);
+    });
+  });
+
+  // ─── Settlement Progress Events ─────────────────────────────────
+
+  describe("settlement progress events", () => {
+    // Three YES winners of 1,000 each against a 1,500 NO pool
+    const stakes = [1_000, 1_000, 1_000];
+    const winnerPool = 3_000;
+    const loserPool = 1_500;
+
+    // Mirrors settle_position's SettlementProgress emission
+    const settleAll = () => {
+      const market = { settlementsCount: 0, settledAmount: 0, settledStake: 0, vault: winnerPool + loserPool };
+      const events: { settlementsCount: number; winningPositions: number; settledAmount: number; vaultBalance: number; marketSettled: boolean }[] = [];
+      for (const stake of stakes) {
+        const payout = stake + Math.floor((stake * loserPool) / winnerPool);
+        market.vault -= payout;
+        market.settledAmount += payout;
+        market.settledStake += stake;
+        market.settlementsCount += 1;
+        events.push({
+          settlementsCount: market.settlementsCount,
+          winningPositions: stakes.length,
+          settledAmount: market.settledAmount,
+          vaultBalance: market.vault,
+          marketSettled: market.settledStake >= winnerPool,
+        });
+      }
+      return events;
+    };
+
+    it("Emits one event per settle with monotonically increasing progress", () => {
+      const events = settleAll();
+      expect(events.length).to.equal(3);
+      for (let i = 1; i < events.length; i++) {
+        expect(events[i].settlementsCount).to.be.greaterThan(events[i - 1].settlementsCount);
+        expect(events[i].settledAmount).to.be.greaterThan(events[i - 1].settledAmount);
+        expect(events[i].vaultBalance).to.be.lessThan(events[i - 1].vaultBalance);
+      }
+    });
+
+    it("Emits on the final settle that moves the market to Settled", () => {
+      const events = settleAll();
+      const last = events[events.length - 1];
+      expect(last.marketSettled).to.equal(true);
+      expect(last.settlementsCount).to.equal(last.winningPositions);
+      expect(events.slice(0, -1).every((e) => !e.marketSettled)).to.equal(true);
+    });
+  });
+
+  // ─── No-Loss Markets ────────────────────────────────────────────
+
+  describe("no-loss markets", () => {
+    // YES wins: two YES bettors (1,000 and 3,000), one NO bettor (2,000)
+    const yesStakes = [1_000, 3_000];
+    const noStake = 2_000;
+    const prize = 400;
+    const winnerPool = 4_000;
+
+    // Mirrors Market::payout_breakdown with loser_profit_pool = 0 and the
+    // whole prize applied as subsidy
+    const winnerPayout = (stake: number) => ({
+      capital: stake,
+      profit: Math.floor((stake * prize) / winnerPool),
+    });
+
+    // Mirrors close_losing_position in a NoLoss market
+    const loserRefund = (deposited: number) => deposited;
+
+    it("Returns the loser's full deposit", () => {
+      expect(loserRefund(noStake)).to.equal(2_000);
+    });
+
+    it("Splits only the prize among winners", () => {
+      const payouts = yesStakes.map(winnerPayout);
+      expect(payouts.map((p) => p.profit)).to.deep.equal([100, 300]);
+      expect(payouts.reduce((sum, p) => sum + p.profit, 0)).to.equal(prize);
+      expect(payouts.map((p) => p.capital)).to.deep.equal(yesStakes);
+    });
+
+    it("Drains the vault exactly", () => {
+      const vault = yesStakes[0] + yesStakes[1] + noStake + prize;
+      const paid = yesStakes.map(winnerPayout).reduce((sum, p) => sum + p.capital + p.profit, 0)
+        + loserRefund(noStake);
+      expect(paid).to.equal(vault);
+    });
+
+    it("Rejects a no-loss market without a prize", () => {
+      const validate = (kind: string, creatorSubsidy: number) => {
+        if (kind === "noLoss" && creatorSubsidy === 0) throw new Error("NoLossWithoutPrize");
+      };
+      expect(() => validate("noLoss", 0)).to.throw("NoLossWithoutPrize");
+      expect(() => validate("noLoss", prize)).to.not.throw();
+    });
+  });
+
+  // ─── Fee Collector Handover ─────────────────────────────────────
+
+  describe("fee collector handover", () => {
+    const authority = Keypair.generate().publicKey.toBase58();
+    const oldCollector = Keypair.generate().publicKey.toBase58();
+    const newCollector = Keypair.generate().publicKey.toBase58();
+
+    const newConfig = () => ({ authority, feeCollector: oldCollector, pendingFeeCollector: null as string | null });
+
+    // Mirrors propose_fee_collector
+    const propose = (config: ReturnType<typeof newConfig>, signer: string, proposed: string) => {
+      if (signer !== config.authority) throw new Error("UnauthorizedAuthority");
+      config.pendingFeeCollector = proposed;
+    };
+
+    // Mirrors accept_fee_collector
+    const accept = (config: ReturnType<typeof newConfig>, signer: string) => {
+      if (config.pendingFeeCollector !== signer) throw new Error("NotPendingFeeCollector");
+      config.feeCollector = signer;
+      config.pendingFeeCollector = null;
+    };
+
+    it("Records a proposal without changing the collector", () => {
+      const config = newConfig();
+      propose(config, authority, newCollector);
+      expect(config.pendingFeeCollector).to.equal(newCollector);
+      expect(config.feeCollector).to.equal(oldCollector);
+    });
+
+    it("Switches the collector when the proposed wallet accepts", () => {
+      const config = newConfig();
+      propose(config, authority, newCollector);
+      accept(config, newCollector);
+      expect(config.feeCollector).to.equal(newCollector);
+      expect(config.pendingFeeCollector).to.equal(null);
+    });
+
+    it("Rejects acceptance by a different signer", () => {
+      const config = newConfig();
+      propose(config, authority, newCollector);
+      expect(() => accept(config, Keypair.generate().publicKey.toBase58())).to.throw("NotPendingFeeCollector");
+      expect(config.feeCollector).to.equal(oldCollector);
+    });
+
+    it("Rejects a proposal from a non-authority", () => {
+      expect(() => propose(newConfig(), newCollector, newCollector)).to.throw("UnauthorizedAuthority");
+    });
+  });
+
+  // ─── Effective Payout ───────────────────────────────────────────
+
+  describe("effective payout", () => {
+    // One YES winner of 1,000 among 2,000 winning stake; 1,000 loser pool
+    const position = { deposited: 1_000 };
+    const winnerPool = 2_000;
+    const loserPool = 1_000;
+    const crankFee = 50;
+
+    // Mirrors Market::settlement_breakdown in Haircut mode (nothing settled yet)
+    const settlementBreakdown = (vault: number) => {
+      const fullProfit = Math.floor((position.deposited * loserPool) / winnerPool);
+      if (position.deposited + fullProfit <= vault) {
+        return { capital: position.deposited, profit: fullProfit, h: 10_000 };
+      }
+      const remainingProfit = loserPool;
+      const h = Math.min(10_000, Math.floor(((vault - winnerPool) * 10_000) / remainingProfit));
+      return { capital: position.deposited, profit: Math.floor((fullProfit * h) / 10_000), h };
+    };
+
+    // Mirrors compute_effective_payout::handler
+    const quote = (vault: number, viaCrank: boolean) => {
+      const b = settlementBreakdown(vault);
+      const gross = b.capital + b.profit;
+      const fee = viaCrank ? Math.min(crankFee, b.profit) : 0;
+      return { grossPayout: gross, hAppliedBps: b.h, settlementFee: fee, netPayout: gross - fee };
+    };
+
+    // Mirrors settle_position: the same breakdown, then the crank fee off profit
+    const settle = (vault: number, viaCrank: boolean) => {
+      const b = settlementBreakdown(vault);
+      const fee = viaCrank ? Math.min(crankFee, b.profit) : 0;
+      return b.capital + b.profit - fee;
+    };
+
+    it("Matches settle on a solvent vault", () => {
+      const q = quote(3_000, false);
+      expect(q).to.deep.equal({ grossPayout: 1_500, hAppliedBps: 10_000, settlementFee: 0, netPayout: 1_500 });
+      expect(q.netPayout).to.equal(settle(3_000, false));
+    });
+
+    it("Matches a haircut settle on a short vault", () => {
+      const q = quote(2_600, false);
+      expect(q.hAppliedBps).to.equal(6_000);
+      expect(q.grossPayout).to.equal(1_300);
+      expect(q.netPayout).to.equal(settle(2_600, false));
+    });
+
+    it("Deducts the crank fee when quoting a crank settle", () => {
+      const q = quote(2_600, true);
+      expect(q.settlementFee).to.equal(crankFee);
+      expect(q.netPayout).to.equal(q.grossPayout - crankFee);
+      expect(q.netPayout).to.equal(settle(2_600, true));
+    });
+  });
+
+  // ─── Global Stats ───────────────────────────────────────────────
+
+  describe("global stats", () => {
+    const FEE_BPS = 50;
+
+    // Mirrors the GlobalConfig/Treasury counters touched by create_market,
+    // place_bet and terminal transitions
+    const config = { totalMarkets: 0, totalVolume: 0, terminalMarkets: 0, nextMarketId: 0 };
+    const treasury = { totalCollected: 0 };
+
+    const createMarket = () => {
+      config.nextMarketId += 1;
+      config.totalMarkets += 1;
+    };
+    const placeBet = (amount: number) => {
+      config.totalVolume += amount;
+      treasury.totalCollected += Math.floor((amount * FEE_BPS) / 10_000);
+    };
+    const closeMarket = () => { config.terminalMarkets += 1; };
+
+    // Mirrors get_global_stats::handler
+    const getGlobalStats = () => ({
+      totalMarkets: config.totalMarkets,
+      totalVolume: config.totalVolume,
+      totalFees: treasury.totalCollected,
+      activeMarkets: config.totalMarkets - config.terminalMarkets,
+      nextMarketId: config.nextMarketId,
+    });
+
+    it("Matches config and treasury state after several markets and bets", () => {
+      createMarket();
+      createMarket();
+      createMarket();
+      placeBet(100_000);
+      placeBet(300_000);
+      closeMarket();
+
+      expect(getGlobalStats()).to.deep.equal({
+        totalMarkets: 3,
+        totalVolume: 400_000,
+        totalFees: 2_000,
+        activeMarkets: 2,
+        nextMarketId: 3,
+      });
+    });
+  });
+
+  // ─── Distinct Oracle ────────────────────────────────────────────
+
+  describe("distinct oracle", () => {
+    const creator = Keypair.generate().publicKey.toBase58();
+    const oracle = Keypair.generate().publicKey.toBase58();
+
+    // Mirrors create_market's require_distinct_oracle check
+    const validate = (requireDistinctOracle: boolean, paramsOracle: string) => {
+      if (requireDistinctOracle && paramsOracle === creator) throw new Error("OracleSameAsCreator");
+    };
+
+    it("Rejects the creator as oracle when the flag is on", () => {
+      expect(() => validate(true, creator)).to.throw("OracleSameAsCreator");
+    });
+
+    it("Accepts a separate oracle when the flag is on", () => {
+      expect(() => validate(true, oracle)).to.not.throw();
+    });
+
+    it("Accepts a self-hosted oracle when the flag is off (default)", () => {
+      expect(() => validate(false, creator)).to.not.throw();
+    });
+  });
+
+  // ─── Imbalance Guard ────────────────────────────────────────────
+
+  describe("imbalance guard", () => {
+    // Mirrors Market::check_imbalance
+    const checkImbalance = (maxImbalanceBps: number, own: number, other: number, stake: number) => {
+      if (maxImbalanceBps >= 10_000 || other === 0) return;
+      const after = own + stake;
+      if (after * 10_000 > (after + other) * maxImbalanceBps) throw new Error("MarketTooImbalanced");
+    };
+
+    it("Accepts a bet that keeps the market within the limit", () => {
+      // YES 6,000 / NO 4,000, +1,000 YES → 7,000 / 11,000 = 63.6% ≤ 80%
+      expect(() => checkImbalance(8_000, 6_000, 4_000, 1_000)).to.not.throw();
+    });
+
+    it("Rejects a bet that breaches the limit", () => {
+      // +11,000 YES → 17,000 / 21,000 = 80.95% > 80%
+      expect(() => checkImbalance(8_000, 6_000, 4_000, 11_000)).to.throw("MarketTooImbalanced");
+    });
+
+    it("Applies the same cap to the thin side once it would flip the market", () => {
+      expect(() => checkImbalance(8_000, 4_000, 6_000, 100_000)).to.throw("MarketTooImbalanced");
+      expect(() => checkImbalance(8_000, 4_000, 6_000, 10_000)).to.not.throw();
+    });
+
+    it("Does nothing when disabled with 10000", () => {
+      expect(() => checkImbalance(10_000, 6_000, 4_000, 1_000_000)).to.not.throw();
+    });
+
+    it("Lets the first bets open the market", () => {
+      expect(() => checkImbalance(8_000, 0, 0, 5_000)).to.not.throw();
+    });
+  });
+
+  // ─── Refund By Position ─────────────────────────────────────────
+
+  describe("refund by position", () => {
+    // Mirrors claim_refund_by_position::handler
+    const claimRefundByPosition = (
+      position: { deposited: number; settled: boolean },
+      tokenAccount: { amount: number } | null,
+      vaultDistributable: number,
+      outstandingDeposits: number,
+      mintSupply = 0
+    ) => {
+      if (position.settled) throw new Error("AlreadySettled");
+      if (tokenAccount !== null && tokenAccount.amount > 0) throw new Error("PositionTokensHeld");
+      // Transferred tokens stay redeemable, so the supply must exclude this position
+      if (mintSupply > Math.max(outstandingDeposits - position.deposited, 0)) {
+        throw new Error("PositionTokensHeld");
+      }
+      const refund = vaultDistributable >= outstandingDeposits
+        ? position.deposited
+        : Math.floor((position.deposited * vaultDistributable) / outstandingDeposits);
+      position.settled = true;
+      return refund;
+    };
+
+    it("Refunds the full deposit after the token account was closed", () => {
+      const position = { deposited: 2 * LAMPORTS_PER_SOL, settled: false };
+      const refund = claimRefundByPosition(position, null, 10 * LAMPORTS_PER_SOL, 10 * LAMPORTS_PER_SOL);
+      expect(refund).to.equal(2 * LAMPORTS_PER_SOL);
+      expect(position.settled).to.be.true;
+    });
+
+    it("Accepts an empty token account", () => {
+      const position = { deposited: 1_000_000, settled: false };
+      expect(claimRefundByPosition(position, { amount: 0 }, 5_000_000, 5_000_000)).to.equal(1_000_000);
+    });
+
+    it("Rejects while position tokens are still held", () => {
+      const position = { deposited: 1_000_000, settled: false };
+      expect(() => claimRefundByPosition(position, { amount: 1_000_000 }, 5_000_000, 5_000_000))
+        .to.throw("PositionTokensHeld");
+      expect(position.settled).to.be.false;
+    });
+
+    it("Rejects once the tokens were transferred to another holder", () => {
+      const position = { deposited: 1_000_000, settled: false };
+      // Another 4M of deposits plus the 1M moved out: 5M still in circulation
+      expect(() => claimRefundByPosition(position, null, 5_000_000, 5_000_000, 5_000_000))
+        .to.throw("PositionTokensHeld");
+      // With the transferred tokens burned, only the other deposits remain
+      expect(claimRefundByPosition(position, null, 5_000_000, 5_000_000, 4_000_000)).to.equal(1_000_000);
+    });
+
+    it("Rejects a second refund", () => {
+      const position = { deposited: 1_000_000, settled: false };
+      claimRefundByPosition(position, null, 5_000_000, 5_000_000);
+      expect(() => claimRefundByPosition(position, null, 5_000_000, 5_000_000)).to.throw("AlreadySettled");
+    });
+
+    it("Scales like claim_refund when the vault is short", () => {
+      const position = { deposited: 1_000_000, settled: false };
+      expect(claimRefundByPosition(position, null, 4_000_000, 5_000_000)).to.equal(800_000);
+    });
+  });
+
+  // ─── Oracle Quorum ──────────────────────────────────────────────
+
+  describe("oracle quorum", () => {
+    // Mirrors Market::readings_agree
+    const readingsAgree = (a: number, b: number, toleranceBps: number) =>
+      Math.abs(a - b) * 10_000 <= Math.max(a, b) * toleranceBps;
+
+    // Mirrors the quorum step of resolve_with_rule::handler
+    const resolveWithQuorum = (
+      market: { status: string; oracleDisagreement: boolean; toleranceBps: number; target: number },
+      primary: number,
+      secondary: number
+    ) => {
+      const agree = readingsAgree(primary, secondary, market.toleranceBps);
+      market.oracleDisagreement = !agree;
+      if (!agree) return;
+      market.status = primary >= market.target ? "resolvedYes" : "resolvedNo";
+    };
+
+    it("Resolves when both feeds agree within tolerance", () => {
+      const market = { status: "closed", oracleDisagreement: false, toleranceBps: 100, target: 1_000_000 };
+      // 1,200,000 vs 1,195,000: gap 0.42% ≤ 1%
+      resolveWithQuorum(market, 1_200_000, 1_195_000);
+      expect(market.status).to.equal("resolvedYes");
+      expect(market.oracleDisagreement).to.be.false;
+    });
+
+    it("Blocks and flags when the feeds disagree", () => {
+      const market = { status: "closed", oracleDisagreement: false, toleranceBps: 100, target: 1_000_000 };
+      // 1,200,000 vs 900,000: gap 25% > 1%
+      resolveWithQuorum(market, 1_200_000, 900_000);
+      expect(market.status).to.equal("closed");
+      expect(market.oracleDisagreement).to.be.true;
+    });
+
+    it("Clears the flag once the feeds converge", () => {
+      const market = { status: "closed", oracleDisagreement: false, toleranceBps: 100, target: 1_000_000 };
+      resolveWithQuorum(market, 1_200_000, 900_000);
+      resolveWithQuorum(market, 950_000, 955_000);
+      expect(market.status).to.equal("resolvedNo");
+      expect(market.oracleDisagreement).to.be.false;
+    });
+
+    it("Requires identical readings at zero tolerance", () => {
+      expect(readingsAgree(1_000, 1_000, 0)).to.be.true;
+      expect(readingsAgree(1_000, 999, 0)).to.be.false;
+    });
+
+    it("Rejects a secondary oracle equal to the primary", () => {
+      const setSecondary = (primary: PublicKey, secondary: PublicKey) => {
+        if (secondary.equals(primary)) throw new Error("InvalidSecondaryOracle");
+      };
+      const oracle = Keypair.generate().publicKey;
+      expect(() => setSecondary(oracle, oracle)).to.throw("InvalidSecondaryOracle");
+      expect(() => setSecondary(oracle, Keypair.generate().publicKey)).to.not.throw();
+    });
+  });
+
+  // ─── Seed Commitment ────────────────────────────────────────────
+
+  describe("seed commitment", () => {
+    // Mirrors Market::required_seed
+    const requiredSeed = (expectedMaxPool: number, minSeedRatioBps: number) =>
+      Math.ceil((expectedMaxPool * minSeedRatioBps) / 10_000);
+
+    // Mirrors the seed check in create_market::create
+    const checkSeed = (creatorSubsidy: number, expectedMaxPool: number, minSeedRatioBps: number) => {
+      if (creatorSubsidy < requiredSeed(expectedMaxPool, minSeedRatioBps)) throw new Error("SeedTooSmall");
+    };
+
+    // Mirrors the seeded-market check in resolve::apply_outcome
+    const checkResolution = (minSeedRatioBps: number, hRatioBps: number, floorBps: number) => {
+      if (minSeedRatioBps > 0 && hRatioBps < floorBps) throw new Error("SeededMarketBelowFloor");
+    };
+
+    it("Scales the required seed with the expected pool", () => {
+      expect(requiredSeed(10 * LAMPORTS_PER_SOL, 500)).to.equal(0.5 * LAMPORTS_PER_SOL);
+      expect(requiredSeed(100 * LAMPORTS_PER_SOL, 500)).to.equal(5 * LAMPORTS_PER_SOL);
+      expect(requiredSeed(100 * LAMPORTS_PER_SOL, 1_000)).to.equal(10 * LAMPORTS_PER_SOL);
+    });
+
+    it("Rounds the requirement up", () => {
+      expect(requiredSeed(1, 1)).to.equal(1);
+    });
+
+    it("Rejects an under-seeded market", () => {
+      expect(() => checkSeed(4 * LAMPORTS_PER_SOL, 100 * LAMPORTS_PER_SOL, 500)).to.throw("SeedTooSmall");
+      expect(() => checkSeed(5 * LAMPORTS_PER_SOL, 100 * LAMPORTS_PER_SOL, 500)).to.not.throw();
+    });
+
+    it("Needs no seed without a commitment", () => {
+      expect(() => checkSeed(0, 100 * LAMPORTS_PER_SOL, 0)).to.not.throw();
+    });
+
+    it("Blocks resolving a seeded market below its floor", () => {
+      expect(() => checkResolution(500, 8_500, 9_000)).to.throw("SeededMarketBelowFloor");
+      expect(() => checkResolution(500, 9_000, 9_000)).to.not.throw();
+      // Unseeded markets resolve and wait for restore_solvency instead
+      expect(() => checkResolution(0, 8_500, 9_000)).to.not.throw();
+    });
+  });
+
+  // ─── Admin Force Cancel ─────────────────────────────────────────
+
+  describe("admin force cancel", () => {
+    // Mirrors admin_force_cancel::handler
+    const forceCancel = (market: {
+      status: string;
+      settlementsCount: number;
+      creatorSubsidy: number;
+      subsidyApplied: number;
+      merkleSettlement?: boolean;
+    }) => {
+      if (market.status === "settled" || market.status === "cancelled") {
+        throw new Error("InvalidMarketStatus");
+      }
+      if (market.merkleSettlement && market.settlementsCount > 0) throw new Error("PayoutsAlreadyMade");
+      if (market.status === "resolved") {
+        market.creatorSubsidy += market.subsidyApplied;
+        market.subsidyApplied = 0;
+      }
+      market.status = "cancelled";
+    };
+
+    it("Force-cancels a resolved but unsettled market", () => {
+      const market = { status: "resolved", settlementsCount: 0, creatorSubsidy: 0, subsidyApplied: 200_000 };
+      forceCancel(market);
+      expect(market.status).to.equal("cancelled");
+      // Matched subsidy returns to escrow so refunds don't pay it out
+      expect(market.creatorSubsidy).to.equal(200_000);
+      expect(market.subsidyApplied).to.equal(0);
+    });
+
+    it("Force-cancels an open market", () => {
+      const market = { status: "open", settlementsCount: 0, creatorSubsidy: 100_000, subsidyApplied: 0 };
+      forceCancel(market);
+      expect(market.status).to.equal("cancelled");
+      expect(market.creatorSubsidy).to.equal(100_000);
+    });
+
+    it("Refuses once Merkle claims have paid out", () => {
+      const market = {
+        status: "resolved",
+        settlementsCount: 1,
+        creatorSubsidy: 0,
+        subsidyApplied: 200_000,
+        merkleSettlement: true,
+      };
+      expect(() => forceCancel(market)).to.throw("PayoutsAlreadyMade");
+      expect(market.status).to.equal("resolved");
+    });
+
+    it("Refuses settled and cancelled markets", () => {
+      for (const status of ["settled", "cancelled"]) {
+        const market = { status, settlementsCount: 0, creatorSubsidy: 0, subsidyApplied: 0 };
+        expect(() => forceCancel(market)).to.throw("InvalidMarketStatus");
+      }
+    });
+
+    it("Only the config authority may force-cancel", () => {
+      const authority = Keypair.generate().publicKey;
+      const creator = Keypair.generate().publicKey;
+      const forceCancel = (signer: PublicKey) => {
+        if (!signer.equals(authority)) throw new Error("UnauthorizedAuthority");
+      };
+      expect(() => forceCancel(creator)).to.throw("UnauthorizedAuthority");
+      expect(() => forceCancel(authority)).to.not.throw();
+    });
+  });
+
+  // ─── Bet Memo ───────────────────────────────────────────────────
+
+  describe("bet memo", () => {
+    const toMemo = (tag: string) => {
+      const memo = new Array(16).fill(0);
+      Buffer.from(tag).forEach((b, i) => { if (i < 16) memo[i] = b; });
+      return memo;
+    };
+    const fromMemo = (memo: number[]) => Buffer.from(memo.filter((b) => b !== 0)).toString();
+
+    // Mirrors the memo handling in place_bet::handler
+    const placeBet = (position: { deposited: number; memo: number[] }, stake: number, memo: number[] | null) => {
+      const firstBet = position.deposited === 0;
+      position.deposited += stake;
+      if (firstBet) position.memo = memo ?? new Array(16).fill(0);
+      // BetPlaced always carries the stored memo
+      return { stake, memo: position.memo };
+    };
+
+    it("Round-trips the memo on the first bet", () => {
+      const position = { deposited: 0, memo: new Array(16).fill(0) };
+      const event = placeBet(position, 1_000_000, toMemo("campaign-42"));
+      expect(fromMemo(position.memo)).to.equal("campaign-42");
+      expect(fromMemo(event.memo)).to.equal("campaign-42");
+    });
+
+    it("Ignores the memo on later bets", () => {
+      const position = { deposited: 0, memo: new Array(16).fill(0) };
+      placeBet(position, 1_000_000, toMemo("campaign-42"));
+      const event = placeBet(position, 500_000, toMemo("other"));
+      expect(fromMemo(position.memo)).to.equal("campaign-42");
+      expect(fromMemo(event.memo)).to.equal("campaign-42");
+      expect(position.deposited).to.equal(1_500_000);
+    });
+
+    it("Zeroes the memo when none is given", () => {
+      const position = { deposited: 0, memo: new Array(16).fill(0) };
+      placeBet(position, 1_000_000, null);
+      expect(position.memo.every((b) => b === 0)).to.be.true;
+    });
+  });
+
+  // ─── Blended Resolution ─────────────────────────────────────────
+
+  describe("blended resolution", () => {
+    // Mirrors Market::scalar_breakdown, used for blended outcomes
+    const blendedPayout = (
+      yesPool: number, noPool: number, yesWeightBps: number, hRatioBps: number,
+      side: "yes" | "no", stake: number
+    ) => {
+      const [sidePool, shareBps] = side === "yes" ? [yesPool, yesWeightBps] : [noPool, 10_000 - yesWeightBps];
+      if (sidePool === 0) return { capital: 0, profit: 0, total: 0 };
+      const sideShare = Math.floor(((yesPool + noPool) * shareBps) / 10_000);
+      const gross = Math.floor((stake * sideShare) / sidePool);
+      const capital = Math.min(gross, stake);
+      const profit = Math.floor(((gross - capital) * hRatioBps) / 10_000);
+      return { capital, profit, total: capital + profit };
+    };
+
+    it("Splits a 7000/3000 blend of the pool between the sides", () => {
+      // 4 SOL YES, 6 SOL NO: YES gets 7 SOL, NO gets 3 SOL
+      const yes = blendedPayout(4_000_000, 6_000_000, 7_000, 10_000, "yes", 4_000_000);
+      const no = blendedPayout(4_000_000, 6_000_000, 7_000, 10_000, "no", 6_000_000);
+      expect(yes.total).to.equal(7_000_000);
+      expect(no.total).to.equal(3_000_000);
+      expect(yes.total + no.total).to.equal(10_000_000);
+    });
+
+    it("Pays capital back plus weighted profit to the favoured side", () => {
+      const yes = blendedPayout(4_000_000, 6_000_000, 7_000, 10_000, "yes", 1_000_000);
+      expect(yes.capital).to.equal(1_000_000);
+      expect(yes.profit).to.equal(750_000);
+    });
+
+    it("Returns part of the stake as capital to the other side", () => {
+      const no = blendedPayout(4_000_000, 6_000_000, 7_000, 10_000, "no", 1_000_000);
+      expect(no.capital).to.equal(500_000);
+      expect(no.profit).to.equal(0);
+    });
+
+    it("Gives each holder a share proportional to their stake", () => {
+      const small = blendedPayout(4_000_000, 6_000_000, 7_000, 10_000, "yes", 1_000_000);
+      const large = blendedPayout(4_000_000, 6_000_000, 7_000, 10_000, "yes", 3_000_000);
+      expect(large.total).to.equal(3 * small.total);
+    });
+
+    it("Applies h to profit only", () => {
+      const yes = blendedPayout(4_000_000, 6_000_000, 7_000, 8_000, "yes", 1_000_000);
+      expect(yes.capital).to.equal(1_000_000);
+      expect(yes.profit).to.equal(600_000);
+    });
+
+    it("Matches a hard YES/NO at the extremes", () => {
+      expect(blendedPayout(4_000_000, 6_000_000, 10_000, 10_000, "yes", 4_000_000).total).to.equal(10_000_000);
+      expect(blendedPayout(4_000_000, 6_000_000, 10_000, 10_000, "no", 6_000_000).total).to.equal(0);
+    });
+
+    it("Rejects a weight above 10000", () => {
+      const setWeight = (yesWeightBps: number) => {
+        if (yesWeightBps > 10_000) throw new Error("InvalidBasisPoints");
+      };
+      expect(() => setWeight(10_001)).to.throw("InvalidBasisPoints");
+      expect(() => setWeight(10_000)).to.not.throw();
+    });
+  });
+
+  // ─── Auto-Cancel on Drained Vault ───────────────────────────────
+
+  describe("auto-cancel on drained vault", () => {
+    // Mirrors Market::compute_h_ratio for a YES outcome
+    const computeH = (vault: number, yesPool: number, noPool: number, subsidyApplied: number) => {
+      if (yesPool === 0) return 10_000;
+      const totalClaims = yesPool + noPool + subsidyApplied;
+      return vault >= totalClaims ? 10_000 : Math.floor((vault * 10_000) / totalClaims);
+    };
+
+    // Mirrors the h = 0 branch of resolve::apply_outcome
+    const resolveYes = (market: { vault: number; yesPool: number; noPool: number; matched: number }) => {
+      const h = computeH(market.vault, market.yesPool, market.noPool, market.matched);
+      if (h === 0) {
+        return { status: "cancelled", outcome: "unresolved", creatorSubsidy: market.matched, subsidyApplied: 0 };
+      }
+      return { status: "resolved", outcome: "yes", creatorSubsidy: 0, subsidyApplied: market.matched, h };
+    };
+
+    // Mirrors Market::refund_amount
+    const refund = (deposited: number, vault: number, outstanding: number, escrowed: number) => {
+      const distributable = Math.max(0, vault - escrowed);
+      return distributable >= outstanding ? deposited : Math.floor((deposited * distributable) / outstanding);
+    };
+
+    it("Cancels instead of resolving when the vault is drained", () => {
+      const result = resolveYes({ vault: 500, yesPool: 6 * LAMPORTS_PER_SOL, noPool: 4 * LAMPORTS_PER_SOL, matched: 0 });
+      expect(result.status).to.equal("cancelled");
+      expect(result.outcome).to.equal("unresolved");
+    });
+
+    it("Refunds what is left pro rata after the auto-cancel", () => {
+      // 500,000 lamports left against 10 SOL of deposits
+      const outstanding = 10 * LAMPORTS_PER_SOL;
+      expect(refund(6 * LAMPORTS_PER_SOL, 500_000, outstanding, 0)).to.equal(300_000);
+      expect(refund(4 * LAMPORTS_PER_SOL, 500_000, outstanding, 0)).to.equal(200_000);
+    });
+
+    it("Moves the matched subsidy back to escrow", () => {
+      const result = resolveYes({ vault: 0, yesPool: 1_000_000, noPool: 1_000_000, matched: 100_000 });
+      expect(result.creatorSubsidy).to.equal(100_000);
+      expect(result.subsidyApplied).to.equal(0);
+    });
+
+    it("Still resolves with a small but non-zero h", () => {
+      // 0.02% of claims: h = 2bps
+      const result = resolveYes({ vault: 2_000_000, yesPool: 6 * LAMPORTS_PER_SOL, noPool: 4 * LAMPORTS_PER_SOL, matched: 0 });
+      expect(result.status).to.equal("resolved");
+      expect(result.h).to.equal(2);
+    });
+  });
+
+  // ─── Creator Sweep of Unclaimed Winnings ────────────────────────
+
+  describe("creator sweep of unclaimed winnings", () => {
+    const RENT = 890_880;
+
+    // Mirrors creator_sweep_unclaimed::handler
+    const sweep = (
+      market: { status: string; claimDeadline: number; owedAtH: number; sweptUnclaimed: number },
+      vault: number,
+      now: number
+    ) => {
+      if (market.status !== "resolved") throw new Error("InvalidMarketStatus");
+      if (market.claimDeadline === 0 || now < market.claimDeadline) {
+        throw new Error("ClaimDeadlineNotReached");
+      }
+      const amount = Math.min(market.owedAtH, Math.max(0, vault - RENT));
+      market.sweptUnclaimed = amount;
+      market.status = "settled";
+      return amount;
+    };
+
+    const now = Math.floor(Date.now() / 1000);
+
+    it("Sweeps unsettled winners' share after the claim deadline", () => {
+      const market = { status: "resolved", claimDeadline: now - 60, owedAtH: 3 * LAMPORTS_PER_SOL, sweptUnclaimed: 0 };
+      // Vault also holds 1 SOL of creator liquidity and rent
+      const amount = sweep(market, 4 * LAMPORTS_PER_SOL + RENT, now);
+      expect(amount).to.equal(3 * LAMPORTS_PER_SOL);
+      expect(market.sweptUnclaimed).to.equal(3 * LAMPORTS_PER_SOL);
+      expect(market.status).to.equal("settled");
+    });
+
+    it("Rejects a sweep before the claim deadline", () => {
+      const market = { status: "resolved", claimDeadline: now + 3600, owedAtH: LAMPORTS_PER_SOL, sweptUnclaimed: 0 };
+      expect(() => sweep(market, 2 * LAMPORTS_PER_SOL, now)).to.throw("ClaimDeadlineNotReached");
+      expect(market.status).to.equal("resolved");
+    });
+
+    it("Rejects a sweep when no claim deadline was set", () => {
+      const market = { status: "resolved", claimDeadline: 0, owedAtH: LAMPORTS_PER_SOL, sweptUnclaimed: 0 };
+      expect(() => sweep(market, 2 * LAMPORTS_PER_SOL, now)).to.throw("ClaimDeadlineNotReached");
+    });
+
+    it("Never takes the vault's rent", () => {
+      const market = { status: "resolved", claimDeadline: now - 60, owedAtH: 3 * LAMPORTS_PER_SOL, sweptUnclaimed: 0 };
+      expect(sweep(market, LAMPORTS_PER_SOL, now)).to.equal(LAMPORTS_PER_SOL - RENT);
+    });
+
+    it("Blocks settling once swept", () => {
+      const market = { status: "resolved", claimDeadline: now - 60, owedAtH: LAMPORTS_PER_SOL, sweptUnclaimed: 0 };
+      sweep(market, 2 * LAMPORTS_PER_SOL, now);
+      const settle = (m: { status: string }) => {
+        if (m.status !== "resolved") throw new Error("InvalidMarketStatus");
+      };
+      expect(() => settle(market)).to.throw("InvalidMarketStatus");
+    });
+  });
+
+  // ─── Price Exponent Normalization ───────────────────────────────
+
+  describe("price exponent normalization", () => {
+    // Mirrors OracleReading::price_at
+    const priceAt = (price: number, feedExponent: number, exponent: number) => {
+      const shift = feedExponent - exponent;
+      return shift >= 0 ? price * 10 ** shift : Math.floor(price / 10 ** -shift);
+    };
+
+    // Mirrors MarketRule::PriceTarget evaluation in resolve_with_rule
+    const resolvePriceTarget = (target: number, targetExponent: number, price: number, feedExponent: number) =>
+      priceAt(price, feedExponent, targetExponent) >= target ? "yes" : "no";
+
+    // $1.50 target at USD × 10^9
+    const TARGET = 1_500_000_000;
+
+    it("Resolves an exponent -9 feed against a -9 target unchanged", () => {
+      expect(priceAt(1_600_000_000, -9, -9)).to.equal(1_600_000_000);
+      expect(resolvePriceTarget(TARGET, -9, 1_600_000_000, -9)).to.equal("yes");
+      expect(resolvePriceTarget(TARGET, -9, 1_400_000_000, -9)).to.equal("no");
+    });
+
+    it("Scales an exponent -8 feed up to a -9 target", () => {
+      // $1.60 at 10^8 = 160,000,000
+      expect(priceAt(160_000_000, -8, -9)).to.equal(1_600_000_000);
+      expect(resolvePriceTarget(TARGET, -9, 160_000_000, -8)).to.equal("yes");
+      expect(resolvePriceTarget(TARGET, -9, 140_000_000, -8)).to.equal("no");
+    });
+
+    it("Would resolve wrongly without normalization", () => {
+      // Raw -8 price compared directly: $1.60 looks like $0.16
+      expect(160_000_000 >= TARGET).to.be.false;
+    });
+
+    it("Truncates when the target scale is coarser", () => {
+      expect(priceAt(1_599_999_999, -9, -6)).to.equal(1_599_999);
+    });
+
+    it("Rejects exponents outside -18..=0", () => {
+      const checkExpo = (e: number) => {
+        if (e < -18 || e > 0) throw new Error("InvalidPriceExponent");
+      };
+      expect(() => checkExpo(2)).to.throw("InvalidPriceExponent");
+      expect(() => checkExpo(-19)).to.throw("InvalidPriceExponent");
+      expect(() => checkExpo(-8)).to.not.throw();
+    });
+  });
+
+  // ─── Batch Refund ───────────────────────────────────────────────
+
+  describe("batch refund", () => {
+    const MAX_BATCH_REFUND = 5;
+
+    type Position = { market: string; user: string; deposited: number; settled: boolean; payout: number };
+
+    // Mirrors batch_refund::handler with claim_refund::refund_position
+    const batchRefund = (
+      market: { key: string; yesPool: number; noPool: number; refundedStake: number; vault: number },
+      groups: { position: Position; signed: boolean; tokens: number }[]
+    ) => {
+      if (groups.length === 0 || groups.length > MAX_BATCH_REFUND) throw new Error("InvalidBatchSize");
+      let total = 0;
+      for (const { position, signed, tokens } of groups) {
+        if (position.market !== market.key) throw new Error("NoPosition");
+        if (position.settled) throw new Error("AlreadySettled");
+        if (!signed) throw new Error("AccountNotSigner");
+        if (tokens < position.deposited) throw new Error("InsufficientFunds");
+
+        const outstanding = market.yesPool + market.noPool - market.refundedStake;
+        const refund = market.vault >= outstanding
+          ? position.deposited
+          : Math.floor((position.deposited * market.vault) / outstanding);
+        market.vault -= refund;
+        market.refundedStake += position.deposited;
+        position.settled = true;
+        position.payout = refund;
+        total += refund;
+      }
+      return total;
+    };
+
+    const makeGroups = (marketKey: string, deposits: number[]) =>
+      deposits.map((deposited, i) => ({
+        position: { market: marketKey, user: `user${i}`, deposited, settled: false, payout: 0 },
+        signed: true,
+        tokens: deposited,
+      }));
+
+    it("Refunds five bettors in one call", () => {
+      const deposits = [1, 2, 3, 4, 5].map((n) => n * 100_000);
+      const market = { key: "m", yesPool: 900_000, noPool: 600_000, refundedStake: 0, vault: 1_500_000 };
+      const groups = makeGroups("m", deposits);
+      expect(batchRefund(market, groups)).to.equal(1_500_000);
+      groups.forEach((g, i) => {
+        expect(g.position.settled).to.be.true;
+        expect(g.position.payout).to.equal(deposits[i]);
+      });
+      expect(market.vault).to.equal(0);
+    });
+
+    it("Scales every refund equally when the vault is short", () => {
+      const deposits = [1, 2, 3, 4, 5].map((n) => n * 100_000);
+      const market = { key: "m", yesPool: 900_000, noPool: 600_000, refundedStake: 0, vault: 1_200_000 };
+      const groups = makeGroups("m", deposits);
+      batchRefund(market, groups);
+      groups.forEach((g, i) => expect(g.position.payout).to.equal(deposits[i] * 0.8));
+    });
+
+    it("Rejects a position from another market", () => {
+      const market = { key: "m", yesPool: 100_000, noPool: 0, refundedStake: 0, vault: 100_000 };
+      expect(() => batchRefund(market, makeGroups("other", [100_000]))).to.throw("NoPosition");
+    });
+
+    it("Rejects an already refunded position", () => {
+      const market = { key: "m", yesPool: 200_000, noPool: 0, refundedStake: 0, vault: 200_000 };
+      const groups = makeGroups("m", [100_000]);
+      batchRefund(market, groups);
+      expect(() => batchRefund(market, groups)).to.throw("AlreadySettled");
+    });
+
+    it("Rejects a user who did not sign", () => {
+      const market = { key: "m", yesPool: 100_000, noPool: 0, refundedStake: 0, vault: 100_000 };
+      const groups = makeGroups("m", [100_000]);
+      groups[0].signed = false;
+      expect(() => batchRefund(market, groups)).to.throw("AccountNotSigner");
+    });
+
+    it("Rejects more than five positions", () => {
+      const market = { key: "m", yesPool: 600_000, noPool: 0, refundedStake: 0, vault: 600_000 };
+      expect(() => batchRefund(market, makeGroups("m", new Array(6).fill(100_000)))).to.throw("InvalidBatchSize");
+    });
+  });
+
+  // ─── Resolution Source ──────────────────────────────────────────
+
+  describe("resolution source", () => {
+    // Mirrors the source each handler passes to resolve::apply_outcome
+    const SOURCE_BY_PATH: Record<string, string> = {
+      resolveMarket: "manualOracle",
+      resolveScalar: "manualOracle",
+      resolveBlended: "manualOracle",
+      resolveWithRule: "oracleRule",
+      resolveDefault: "default",
+    };
+
+    // Mirrors apply_outcome stamping `resolved_via` and emitting MarketResolved
+    const resolve = (market: { resolvedVia: string; events: any[] }, path: string, outcome: string) => {
+      const source = SOURCE_BY_PATH[path];
+      market.resolvedVia = source;
+      market.events.push({ name: "MarketResolved", outcome, resolvedVia: source });
+    };
+
+    it("Starts unresolved", () => {
+      const market = { resolvedVia: "unresolved", events: [] as any[] };
+      expect(market.resolvedVia).to.equal("unresolved");
+    });
+
+    for (const [path, source] of Object.entries(SOURCE_BY_PATH)) {
+      it(`Stamps ${source} from ${path}`, () => {
+        const market = { resolvedVia: "unresolved", events: [] as any[] };
+        resolve(market, path, "yes");
+        expect(market.resolvedVia).to.equal(source);
+        expect(market.events[0].name).to.equal("MarketResolved");
+        expect(market.events[0].resolvedVia).to.equal(source);
+      });
+    }
+
+    it("Distinguishes trust-minimized from discretionary resolutions", () => {
+      const trustMinimized = Object.entries(SOURCE_BY_PATH)
+        .filter(([, source]) => source === "oracleRule")
+        .map(([path]) => path);
+      expect(trustMinimized).to.deep.equal(["resolveWithRule"]);
+    });
+  });
+
+  // ─── Position Reconciliation ────────────────────────────────────
+
+  describe("position reconciliation", () => {
+    // Mirrors reconcile_position::handler for one side
+    const reconcile = (
+      market: { slack: number },
+      position: { deposited: number; bonusStake: number },
+      balance: number
+    ) => {
+      const current = position.deposited;
+      if (balance < current) {
+        position.bonusStake = Math.floor((position.bonusStake * balance) / current);
+        position.deposited = balance;
+        market.slack += current - balance;
+      } else {
+        const taken = Math.min(balance - current, market.slack);
+        market.slack -= taken;
+        position.deposited += taken;
+      }
+    };
+
+    it("Lowers the seller's position after a partial transfer out", () => {
+      const market = { slack: 0 };
+      const seller = { deposited: 1_000_000, bonusStake: 100_000 };
+      reconcile(market, seller, 600_000);
+      expect(seller.deposited).to.equal(600_000);
+      expect(seller.bonusStake).to.equal(60_000);
+      expect(market.slack).to.equal(400_000);
+    });
+
+    it("Raises the buyer's position after a transfer in", () => {
+      const market = { slack: 0 };
+      const seller = { deposited: 1_000_000, bonusStake: 0 };
+      const buyer = { deposited: 0, bonusStake: 0 };
+      reconcile(market, seller, 600_000);
+      reconcile(market, buyer, 400_000);
+      expect(buyer.deposited).to.equal(400_000);
+      expect(market.slack).to.equal(0);
+      // The side's total claim is unchanged
+      expect(seller.deposited + buyer.deposited).to.equal(1_000_000);
+    });
+
+    it("Caps a raise at the freed slack", () => {
+      const market = { slack: 0 };
+      const buyer = { deposited: 500_000, bonusStake: 0 };
+      // Seller has not reconciled yet: nothing freed
+      reconcile(market, buyer, 900_000);
+      expect(buyer.deposited).to.equal(500_000);
+      const seller = { deposited: 800_000, bonusStake: 0 };
+      reconcile(market, seller, 400_000);
+      reconcile(market, buyer, 900_000);
+      expect(buyer.deposited).to.equal(900_000);
+      expect(market.slack).to.equal(0);
+    });
+
+    it("Leaves a matching position unchanged", () => {
+      const market = { slack: 0 };
+      const position = { deposited: 700_000, bonusStake: 0 };
+      reconcile(market, position, 700_000);
+      expect(position.deposited).to.equal(700_000);
+      expect(market.slack).to.equal(0);
+    });
+
+    it("Completes settlement despite unclaimed slack", () => {
+      // Mirrors the Settled check in settle::settle_position
+      const winnerPool = 1_000_000;
+      const settledStake = 600_000;
+      const winnerSlack = 400_000;
+      expect(settledStake + winnerSlack >= winnerPool).to.be.true;
+    });
+
+    it("Rejects reconciling onto the other side", () => {
+      const reconcile = (position: { side: string }, side: string) => {
+        if (position.side !== side) throw new Error("PositionSideMismatch");
+      };
+      expect(() => reconcile({ side: "yes" }, "no")).to.throw("PositionSideMismatch");
+    });
+  });
+
+  // ─── Refund by Token Balance ────────────────────────────────────
+
+  describe("refund by token balance", () => {
+    // Mirrors Market::refund_stake
+    const refundStake = (refundByTokenBalance: boolean, deposited: number, tokenBalance: number) =>
+      refundByTokenBalance ? tokenBalance : deposited;
+
+    // Mirrors claim_refund::handler: burn `stake` tokens, refund against it
+    const claimRefund = (
+      market: { refundByTokenBalance: boolean; vault: number; outstanding: number },
+      deposited: number,
+      tokenBalance: number
+    ) => {
+      const stake = refundStake(market.refundByTokenBalance, deposited, tokenBalance);
+      if (tokenBalance < stake) throw new Error("InsufficientFunds");
+      const refund = market.vault >= market.outstanding
+        ? stake
+        : Math.floor((stake * market.vault) / market.outstanding);
+      market.vault -= refund;
+      market.outstanding -= stake;
+      return { burned: stake, refund };
+    };
+
+    it("Refunds a user's received tokens along with their own", () => {
+      const market = { refundByTokenBalance: true, vault: 3_000_000, outstanding: 3_000_000 };
+      // Deposited 1,000,000 and was sent 500,000 more tokens
+      const { burned, refund } = claimRefund(market, 1_000_000, 1_500_000);
+      expect(burned).to.equal(1_500_000);
+      expect(refund).to.equal(1_500_000);
+    });
+
+    it("Pays the sender only for the tokens they kept", () => {
+      const market = { refundByTokenBalance: true, vault: 3_000_000, outstanding: 3_000_000 };
+      claimRefund(market, 1_000_000, 1_500_000);
+      const sender = claimRefund(market, 2_000_000, 1_500_000);
+      expect(sender.refund).to.equal(1_500_000);
+      expect(market.vault).to.equal(0);
+      expect(market.outstanding).to.equal(0);
+    });
+
+    it("Keeps deposit-based refunds without the flag", () => {
+      const market = { refundByTokenBalance: false, vault: 3_000_000, outstanding: 3_000_000 };
+      const { burned, refund } = claimRefund(market, 1_000_000, 1_500_000);
+      expect(burned).to.equal(1_000_000);
+      expect(refund).to.equal(1_000_000);
+    });
+
+    it("Scales balance refunds when the vault is short", () => {
+      const market = { refundByTokenBalance: true, vault: 2_400_000, outstanding: 3_000_000 };
+      expect(claimRefund(market, 1_000_000, 1_500_000).refund).to.equal(1_200_000);
+    });
+
+    it("Refuses refunds by position alone", () => {
+      const claimRefundByPosition = (m: { refundByTokenBalance: boolean }) => {
+        if (m.refundByTokenBalance) throw new Error("RefundByTokenBalance");
+      };
+      expect(() => claimRefundByPosition({ refundByTokenBalance: true })).to.throw("RefundByTokenBalance");
+      expect(() => claimRefundByPosition({ refundByTokenBalance: false })).to.not.throw();
+    });
+  });
+
+  // ─── Minimum Pool per Side ──────────────────────────────────────
+
+  describe("minimum pool per side", () => {
+    // Mirrors Market::is_side_too_thin
+    const isSideTooThin = (minPoolPerSide: number, yesPool: number, noPool: number) =>
+      minPoolPerSide > 0 && (yesPool < minPoolPerSide || noPool < minPoolPerSide);
+
+    // Mirrors the depth check in resolve::apply_outcome
+    const resolve = (minPoolPerSide: number, yesPool: number, noPool: number) => {
+      if (isSideTooThin(minPoolPerSide, yesPool, noPool)) throw new Error("SideTooThin");
+      return "resolved";
+    };
+
+    // Mirrors resolve_default::handler choosing between resolve and cancel
+    const resolveDefault = (minPoolPerSide: number, yesPool: number, noPool: number) =>
+      isSideTooThin(minPoolPerSide, yesPool, noPool) ? "cancelled" : resolve(minPoolPerSide, yesPool, noPool);
+
+    it("Blocks resolution when one side is below the minimum", () => {
+      expect(() => resolve(LAMPORTS_PER_SOL, 5 * LAMPORTS_PER_SOL, 0.1 * LAMPORTS_PER_SOL)).to.throw("SideTooThin");
+    });
+
+    it("Resolves when both sides meet the minimum", () => {
+      expect(resolve(LAMPORTS_PER_SOL, 5 * LAMPORTS_PER_SOL, LAMPORTS_PER_SOL)).to.equal("resolved");
+    });
+
+    it("Applies no minimum at 0", () => {
+      expect(resolve(0, 5 * LAMPORTS_PER_SOL, 0)).to.equal("resolved");
+    });
+
+    it("Routes a thin market to cancellation after the window", () => {
+      expect(resolveDefault(LAMPORTS_PER_SOL, 5 * LAMPORTS_PER_SOL, 1_000)).to.equal("cancelled");
+      expect(resolveDefault(LAMPORTS_PER_SOL, 5 * LAMPORTS_PER_SOL, 2 * LAMPORTS_PER_SOL)).to.equal("resolved");
+    });
+  });
+
+  // ─── Stray Lamport Rescue ───────────────────────────────────────
+
+  describe("stray lamport rescue", () => {
+    // Rent-exempt minimum for a Market-sized account (illustrative)
+    const MARKET_RENT = 5_000_000;
+
+    // Mirrors rescue_stray_lamports::handler
+    const rescue = (accounts: { market: number; vault: number; feeCollector: number }) => {
+      const amount = Math.max(0, accounts.market - MARKET_RENT);
+      if (amount === 0) throw new Error("ZeroAmount");
+      accounts.market -= amount;
+      accounts.feeCollector += amount;
+      return amount;
+    };
+
+    it("Rescues SOL sent directly to the Market account", () => {
+      const accounts = { market: MARKET_RENT, vault: 10 * LAMPORTS_PER_SOL, feeCollector: 0 };
+      // User mistakenly transfers 2 SOL to the Market PDA
+      accounts.market += 2 * LAMPORTS_PER_SOL;
+      expect(rescue(accounts)).to.equal(2 * LAMPORTS_PER_SOL);
+      expect(accounts.feeCollector).to.equal(2 * LAMPORTS_PER_SOL);
+    });
+
+    it("Keeps the Market account rent-exempt", () => {
+      const accounts = { market: MARKET_RENT + 1_234, vault: 0, feeCollector: 0 };
+      rescue(accounts);
+      expect(accounts.market).to.equal(MARKET_RENT);
+    });
+
+    it("Never touches the vault", () => {
+      const accounts = { market: MARKET_RENT + LAMPORTS_PER_SOL, vault: 10 * LAMPORTS_PER_SOL, feeCollector: 0 };
+      rescue(accounts);
+      expect(accounts.vault).to.equal(10 * LAMPORTS_PER_SOL);
+    });
+
+    it("Rejects when there is nothing stray", () => {
+      const accounts = { market: MARKET_RENT, vault: 0, feeCollector: 0 };
+      expect(() => rescue(accounts)).to.throw("ZeroAmount");
+    });
+  });
+
+  // ─── Deduplicated Market Creation ───────────────────────────────
+
+  describe("deduplicated market creation", () => {
+    const mintA = Keypair.generate().publicKey;
+    const mintB = Keypair.generate().publicKey;
+
+    // Mirrors MarketDedupe::key; MarketCapTarget and PriceTarget are unit
+    // variants, so their Borsh encoding is the variant index alone
+    const dedupeKey = (question: string, tokenMint: PublicKey, ruleIndex: number, targetValue: number) => {
+      const targetLe = Buffer.alloc(8);
+      targetLe.writeUIntLE(targetValue, 0, 6);
+      return createHash("sha256")
+        .update(Buffer.from(question))
+        .update(tokenMint.toBuffer())
+        .update(Buffer.from([ruleIndex]))
+        .update(targetLe)
+        .digest();
+    };
+
+    const dedupePda = (creatorKey: PublicKey, key: Buffer) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("market_dedupe"), creatorKey.toBuffer(), key],
+        program.programId
+      )[0].toBase58();
+
+    // Mirrors the `init` constraint on CreateMarketDeduped::dedupe
+    const createDeduped = (existing: Set<string>, creatorKey: PublicKey, key: Buffer) => {
+      const pda = dedupePda(creatorKey, key);
+      if (existing.has(pda)) throw new Error("AccountAlreadyInUse");
+      existing.add(pda);
+    };
+
+    it("Rejects a duplicate market under dedupe mode", () => {
+      const existing = new Set<string>();
+      createDeduped(existing, creator.publicKey, dedupeKey("Will X reach $1B?", mintA, 0, 1_000_000_000));
+      expect(() =>
+        createDeduped(existing, creator.publicKey, dedupeKey("Will X reach $1B?", mintA, 0, 1_000_000_000))
+      ).to.throw("AccountAlreadyInUse");
+    });
+
+    it("Allows markets differing in any keyed field", () => {
+      const existing = new Set<string>();
+      createDeduped(existing, creator.publicKey, dedupeKey("Will X reach $1B?", mintA, 0, 1_000_000_000));
+      createDeduped(existing, creator.publicKey, dedupeKey("Will X reach $2B?", mintA, 0, 1_000_000_000));
+      createDeduped(existing, creator.publicKey, dedupeKey("Will X reach $1B?", mintB, 0, 1_000_000_000));
+      createDeduped(existing, creator.publicKey, dedupeKey("Will X reach $1B?", mintA, 1, 1_000_000_000));
+      createDeduped(existing, creator.publicKey, dedupeKey("Will X reach $1B?", mintA, 0, 2_000_000_000));
+      expect(existing.size).to.equal(5);
+    });
+
+    it("Scopes duplicates to the creator", () => {
+      const existing = new Set<string>();
+      const key = dedupeKey("Will X reach $1B?", mintA, 0, 1_000_000_000);
+      createDeduped(existing, creator.publicKey, key);
+      createDeduped(existing, oracle.publicKey, key);
+      expect(existing.size).to.equal(2);
+    });
+  });
+
+  // ─── Resolution Preview ─────────────────────────────────────────
+
+  describe("resolution preview", () => {
+    const marketCap = (price: number, supply: number) => Math.floor((price * supply) / 1000);
+    const observe = (rule: string, price: number, supply: number) => {
+      switch (rule) {
+        case "priceTarget":
+          return price;
+        case "marketCapTarget":
+        case "marketCapFloor":
+        case "marketCapCeiling":
+          return marketCap(price, supply);
+        default:
+          throw new Error("InvalidOutcome");
+      }
+    };
+    const evaluate = (rule: string, observed: number, target: number) =>
+      rule === "marketCapCeiling" ? (observed < target ? "Yes" : "No") : observed >= target ? "Yes" : "No";
+
+    type Market = { rule: string; target: number; deadline: number; status: string; outcome: string | null };
+    type Reading = { price: number; supply: number; publishedAt: number };
+
+    // Mirrors preview_resolution::handler: no timing checks, no mutation
+    const preview = (market: Market, reading: Reading) => {
+      const observedValue = observe(market.rule, reading.price, reading.supply);
+      return {
+        outcome: evaluate(market.rule, observedValue, market.target),
+        observedValue,
+        targetValue: market.target,
+        publishedAt: reading.publishedAt,
+      };
+    };
+
+    // Mirrors resolve_with_rule::handler
+    const resolveWithRule = (market: Market, reading: Reading, now: number) => {
+      if (now < market.deadline) throw new Error("MarketNotExpired");
+      if (reading.publishedAt < market.deadline) throw new Error("StaleOracleReading");
+      market.outcome = evaluate(market.rule, observe(market.rule, reading.price, reading.supply), market.target);
+      market.status = "Resolved";
+    };
+
+    const deadline = 1_700_000_000;
+    const reading = { price: 1_000_000, supply: 1_000_000_000, publishedAt: deadline + 60 };
+
+    it("Matches the outcome resolve_with_rule applies", () => {
+      for (const [rule, target] of [
+        ["marketCapTarget", 1_000_000_000_000],
+        ["marketCapTarget", 1_000_000_000_001],
+        ["priceTarget", 900_000],
+        ["marketCapCeiling", 500_000_000_000],
+      ] as [string, number][]) {
+        const market = { rule, target, deadline, status: "Closed", outcome: null };
+        const previewed = preview(market, reading);
+        resolveWithRule(market, reading, deadline + 120);
+        expect(previewed.outcome).to.equal(market.outcome);
+      }
+    });
+
+    it("Returns the raw observed value and leaves the market untouched", () => {
+      const market = { rule: "marketCapTarget", target: 2_000_000_000_000, deadline, status: "Closed", outcome: null };
+      const previewed = preview(market, reading);
+      expect(previewed.observedValue).to.equal(1_000_000_000_000);
+      expect(previewed.outcome).to.equal("No");
+      expect(market.status).to.equal("Closed");
+      expect(market.outcome).to.equal(null);
+    });
+
+    it("Previews before the deadline, when resolution would be refused", () => {
+      const early = { ...reading, publishedAt: deadline - 3_600 };
+      const market = { rule: "priceTarget", target: 900_000, deadline, status: "Open", outcome: null };
+      expect(preview(market, early).outcome).to.equal("Yes");
+      expect(preview(market, early).publishedAt).to.be.lessThan(deadline);
+      expect(() => resolveWithRule(market, early, deadline - 60)).to.throw("MarketNotExpired");
+    });
+
+    it("Rejects OracleCustom markets", () => {
+      const market = { rule: "oracleCustom", target: 0, deadline, status: "Closed", outcome: null };
+      expect(() => preview(market, reading)).to.throw("InvalidOutcome");
+    });
+  });
+
+  // ─── Entry Odds ─────────────────────────────────────────────────
+
+  describe("entry odds", () => {
+    type Position = { deposited: number; entryYesPool: number; entryNoPool: number };
+
+    // Mirrors record_bet: entry pools are snapshotted on the first deposit only
+    const bet = (pools: { yes: number; no: number }, position: Position, side: "yes" | "no", stake: number) => {
+      pools[side] += stake;
+      if (position.deposited === 0) {
+        position.entryYesPool = pools.yes;
+        position.entryNoPool = pools.no;
+      }
+      position.deposited += stake;
+      // Mirrors the BetPlaced event fields
+      return { stake, entryYesPool: position.entryYesPool, entryNoPool: position.entryNoPool };
+    };
+
+    const impliedYes = (yes: number, no: number) => yes / (yes + no);
+
+    it("Captures the pools at the first bet", () => {
+      const pools = { yes: 3 * LAMPORTS_PER_SOL, no: 6 * LAMPORTS_PER_SOL };
+      const position = { deposited: 0, entryYesPool: 0, entryNoPool: 0 };
+      const event = bet(pools, position, "yes", LAMPORTS_PER_SOL);
+      expect(position.entryYesPool).to.equal(4 * LAMPORTS_PER_SOL);
+      expect(position.entryNoPool).to.equal(6 * LAMPORTS_PER_SOL);
+      expect(event.entryYesPool).to.equal(position.entryYesPool);
+      expect(impliedYes(position.entryYesPool, position.entryNoPool)).to.equal(0.4);
+    });
+
+    it("Leaves entry odds unchanged as pools move", () => {
+      const pools = { yes: 3 * LAMPORTS_PER_SOL, no: 6 * LAMPORTS_PER_SOL };
+      const position = { deposited: 0, entryYesPool: 0, entryNoPool: 0 };
+      bet(pools, position, "yes", LAMPORTS_PER_SOL);
+      // Other bettors pile in on NO
+      pools.no += 10 * LAMPORTS_PER_SOL;
+      expect(position.entryNoPool).to.equal(6 * LAMPORTS_PER_SOL);
+      expect(impliedYes(pools.yes, pools.no)).to.equal(0.2);
+    });
+
+    it("Keeps the first entry on a top-up", () => {
+      const pools = { yes: 3 * LAMPORTS_PER_SOL, no: 6 * LAMPORTS_PER_SOL };
+      const position = { deposited: 0, entryYesPool: 0, entryNoPool: 0 };
+      bet(pools, position, "yes", LAMPORTS_PER_SOL);
+      const event = bet(pools, position, "yes", 5 * LAMPORTS_PER_SOL);
+      expect(position.entryYesPool).to.equal(4 * LAMPORTS_PER_SOL);
+      expect(event.entryYesPool).to.equal(4 * LAMPORTS_PER_SOL);
+    });
+  });
+
+  // ─── Force Cancel After Partial Settlement ──────────────────────
+
+  describe("force cancel after partial settlement", () => {
+    type Market = {
+      yesPool: number;
+      noPool: number;
+      subsidyApplied: number;
+      creatorSubsidy: number;
+      settledStake: number;
+      settledAmount: number;
+      refundedStake: number;
+    };
+
+    // Mirrors Market::unpaid_subsidy (YES won)
+    const unpaidSubsidy = (m: Market) =>
+      m.yesPool === 0 ? m.subsidyApplied : Math.floor((m.subsidyApplied * (m.yesPool - m.settledStake)) / m.yesPool);
+
+    // Mirrors admin_force_cancel::handler for a Resolved market
+    const forceCancel = (m: Market) => {
+      m.creatorSubsidy += unpaidSubsidy(m);
+      m.subsidyApplied = 0;
+    };
+
+    // Mirrors Market::refund_amount
+    const refundAmount = (m: Market, stake: number, vault: number) => {
+      const outstanding = m.yesPool + m.noPool - m.refundedStake - m.settledStake;
+      if (outstanding === 0) return 0;
+      const distributable = Math.max(0, vault - m.creatorSubsidy);
+      return distributable >= outstanding ? stake : Math.floor((stake * distributable) / outstanding);
+    };
+
+    // YES (alice 4 SOL, bob 4 SOL) beat NO (carol 12 SOL); creator matched 2 SOL
+    const setup = () => {
+      const m: Market = {
+        yesPool: 8 * LAMPORTS_PER_SOL,
+        noPool: 12 * LAMPORTS_PER_SOL,
+        subsidyApplied: 2 * LAMPORTS_PER_SOL,
+        creatorSubsidy: 0,
+        settledStake: 0,
+        settledAmount: 0,
+        refundedStake: 0,
+      };
+      let vault = 22 * LAMPORTS_PER_SOL;
+      // Alice settles: 4 capital + half of (12 loser pool + 2 subsidy)
+      const alicePayout = 11 * LAMPORTS_PER_SOL;
+      vault -= alicePayout;
+      m.settledStake += 4 * LAMPORTS_PER_SOL;
+      m.settledAmount += alicePayout;
+      return { m, vault, alicePayout };
+    };
+
+    it("Returns only the unpaid part of the subsidy to the creator", () => {
+      const { m } = setup();
+      forceCancel(m);
+      expect(m.creatorSubsidy).to.equal(LAMPORTS_PER_SOL);
+    });
+
+    it("Shares the residual vault pro rata among unsettled bettors", () => {
+      const { m, vault } = setup();
+      forceCancel(m);
+      // 11 SOL left, 1 SOL reserved for the creator, 16 SOL of unsettled stake
+      const bob = refundAmount(m, 4 * LAMPORTS_PER_SOL, vault);
+      const carol = refundAmount(m, 12 * LAMPORTS_PER_SOL, vault);
+      expect(bob).to.equal(2.5 * LAMPORTS_PER_SOL);
+      expect(carol).to.equal(7.5 * LAMPORTS_PER_SOL);
+      expect(bob + carol + m.creatorSubsidy).to.equal(vault);
+    });
+
+    it("Does not claw back the settled winner", () => {
+      const { m, alicePayout } = setup();
+      forceCancel(m);
+      expect(m.settledAmount).to.equal(alicePayout);
+      // Alice's position is settled, so claim_refund refuses it
+      const claimRefund = (position: { settled: boolean }) => {
+        if (position.settled) throw new Error("AlreadySettled");
+      };
+      expect(() => claimRefund({ settled: true })).to.throw("AlreadySettled");
+    });
+
+    it("Refunds in full when nothing had settled", () => {
+      const m: Market = {
+        yesPool: 8 * LAMPORTS_PER_SOL,
+        noPool: 8 * LAMPORTS_PER_SOL,
+        subsidyApplied: 2 * LAMPORTS_PER_SOL,
+        creatorSubsidy: 0,
+        settledStake: 0,
+        settledAmount: 0,
+        refundedStake: 0,
+      };
+      forceCancel(m);
+      expect(m.creatorSubsidy).to.equal(2 * LAMPORTS_PER_SOL);
+      expect(refundAmount(m, 4 * LAMPORTS_PER_SOL, 18 * LAMPORTS_PER_SOL)).to.equal(4 * LAMPORTS_PER_SOL);
+    });
+  });
+
+  // ─── Settlement Incentive ───────────────────────────────────────
+
+  describe("settlement incentive", () => {
+    const WINDOW = 7 * 24 * 3600;
+    const BPS = 100; // 1% of profit
+
+    // Mirrors GlobalConfig::settlement_incentive
+    const incentive = (policy: string, profit: number, elapsed: number) => {
+      if (WINDOW <= 0 || policy === "none") return { bonus: 0, penalty: 0 };
+      if (policy === "earlyBonus") {
+        const remaining = Math.max(0, WINDOW - Math.max(0, elapsed));
+        return { bonus: Math.floor((profit * BPS * remaining) / WINDOW / 10_000), penalty: 0 };
+      }
+      return { bonus: 0, penalty: elapsed >= WINDOW ? Math.floor((profit * BPS) / 10_000) : 0 };
+    };
+
+    // Mirrors settle_position's incentive step
+    const settle = (policy: string, capital: number, profit: number, elapsed: number, surplus: number) => {
+      const { bonus, penalty } = incentive(policy, profit, elapsed);
+      const paidBonus = Math.min(bonus, surplus);
+      return { capital, profit: profit + paidBonus - penalty, toTreasury: penalty };
+    };
+
+    const capital = 4 * LAMPORTS_PER_SOL;
+    const profit = 2 * LAMPORTS_PER_SOL;
+
+    it("Pays an early settler more than a late one under EarlyBonus", () => {
+      const early = settle("earlyBonus", capital, profit, 0, LAMPORTS_PER_SOL);
+      const late = settle("earlyBonus", capital, profit, WINDOW, LAMPORTS_PER_SOL);
+      expect(early.profit).to.equal(profit + 20_000_000);
+      expect(late.profit).to.equal(profit);
+      // Halfway through the window the bonus has halved
+      expect(settle("earlyBonus", capital, profit, WINDOW / 2, LAMPORTS_PER_SOL).profit).to.equal(profit + 10_000_000);
+    });
+
+    it("Caps the bonus at the vault surplus", () => {
+      expect(settle("earlyBonus", capital, profit, 0, 5_000).profit).to.equal(profit + 5_000);
+      expect(settle("earlyBonus", capital, profit, 0, 0).profit).to.equal(profit);
+    });
+
+    it("Penalizes only settlers past the window under LatePenalty", () => {
+      const early = settle("latePenalty", capital, profit, WINDOW - 1, 0);
+      const late = settle("latePenalty", capital, profit, WINDOW, 0);
+      expect(early.profit).to.equal(profit);
+      expect(late.profit).to.equal(profit - 20_000_000);
+      expect(late.toTreasury).to.equal(20_000_000);
+    });
+
+    it("Never touches capital", () => {
+      for (const policy of ["none", "earlyBonus", "latePenalty"]) {
+        for (const elapsed of [0, WINDOW / 2, WINDOW * 2]) {
+          expect(settle(policy, capital, profit, elapsed, LAMPORTS_PER_SOL).capital).to.equal(capital);
+        }
+      }
+      // A position with no profit gets neither bonus nor penalty
+      expect(settle("latePenalty", capital, 0, WINDOW * 2, 0).toTreasury).to.equal(0);
+    });
+
+    it("Requires the treasury when a penalty is due", () => {
+      const { penalty } = incentive("latePenalty", profit, WINDOW);
+      const payPenalty = (treasuryPassed: boolean) => {
+        if (penalty > 0 && !treasuryPassed) throw new Error("TreasuryRequired");
+      };
+      expect(() => payPenalty(false)).to.throw("TreasuryRequired");
+      expect(() => payPenalty(true)).to.not.throw();
+    });
+  });
+
+  // ─── Amend Question ─────────────────────────────────────────────
+
+  describe("amend question", () => {
+    type Market = { creator: PublicKey; question: string; yesPool: number; noPool: number; commitDeadline: number };
+
+    // Mirrors amend_question::handler
+    const amendQuestion = (market: Market, signer: PublicKey, question: string, maxQuestionLen = 0) => {
+      if (!signer.equals(market.creator)) throw new Error("UnauthorizedCreator");
+      if (market.yesPool + market.noPool !== 0) throw new Error("MarketHasBets");
+      if (market.commitDeadline !== 0) throw new Error("MarketHasBets");
+      const limit = maxQuestionLen === 0 ? 256 : maxQuestionLen;
+      if (Buffer.byteLength(question) > limit) throw new Error("QuestionTooLong");
+      market.question = question;
+    };
+
+    const newMarket = (): Market => ({
+      creator: creator.publicKey,
+      question: "Will $PEPE reach $1M markte cap in 24h?",
+      yesPool: 0,
+      noPool: 0,
+      commitDeadline: 0,
+    });
+
+    it("Fixes a typo on a market with no bets", () => {
+      const market = newMarket();
+      amendQuestion(market, creator.publicKey, "Will $PEPE reach $1M market cap in 24h?");
+      expect(market.question).to.equal("Will $PEPE reach $1M market cap in 24h?");
+    });
+
+    it("Refuses once a bet has been placed", () => {
+      const market = newMarket();
+      market.noPool = LAMPORTS_PER_SOL;
+      expect(() => amendQuestion(market, creator.publicKey, "Something else?")).to.throw("MarketHasBets");
+      expect(market.question).to.equal("Will $PEPE reach $1M markte cap in 24h?");
+    });
+
+    it("Refuses commit-reveal markets, whose sealed bets are not in the pools", () => {
+      const market = newMarket();
+      market.commitDeadline = 1_000;
+      expect(() => amendQuestion(market, creator.publicKey, "Something else?")).to.throw("MarketHasBets");
+    });
+
+    it("Validates the new length against the config limit", () => {
+      const market = newMarket();
+      expect(() => amendQuestion(market, creator.publicKey, "x".repeat(65), 64)).to.throw("QuestionTooLong");
+      amendQuestion(market, creator.publicKey, "x".repeat(64), 64);
+      expect(market.question.length).to.equal(64);
+    });
+
+    it("Only the creator may amend", () => {
+      const market = newMarket();
+      expect(() => amendQuestion(market, oracle.publicKey, "Hijacked?")).to.throw("UnauthorizedCreator");
+    });
+  });
+
+  // ─── Creator Revenue ────────────────────────────────────────────
+
+  describe("creator revenue", () => {
+    const FEE_BPS = 100;
+    const CREATOR_SHARE_BPS = 2_000;
+    const RENT_MIN = 890_880;
+
+    type Accounts = { vault: number; treasury: number; creatorRevenuePda: number; creatorWallet: number };
+    type Market = { creator: PublicKey; creatorRevenue: number };
+
+    // Mirrors place_bet: the creator share accrues in the creator-revenue PDA
+    const placeBet = (market: Market, accounts: Accounts, amount: number) => {
+      const fee = Math.floor((amount * FEE_BPS) / 10_000);
+      const creatorFee = Math.floor((fee * CREATOR_SHARE_BPS) / 10_000);
+      accounts.vault += amount - fee;
+      accounts.treasury += fee - creatorFee;
+      accounts.creatorRevenuePda += creatorFee;
+      market.creatorRevenue += creatorFee;
+    };
+
+    // Mirrors withdraw_creator_revenue::handler
+    const withdraw = (market: Market, accounts: Accounts, signer: PublicKey) => {
+      if (!signer.equals(market.creator)) throw new Error("UnauthorizedCreator");
+      // The PDA keeps its rent minimum
+      const amount = Math.min(market.creatorRevenue, Math.max(0, accounts.creatorRevenuePda - RENT_MIN));
+      if (amount === 0) throw new Error("ZeroAmount");
+      accounts.creatorRevenuePda -= amount;
+      accounts.creatorWallet += amount;
+      market.creatorRevenue -= amount;
+      return amount;
+    };
+
+    // Mirrors create_market, which funds the revenue PDA's rent
+    const setup = () => ({
+      market: { creator: creator.publicKey, creatorRevenue: 0 },
+      accounts: { vault: 0, treasury: 0, creatorRevenuePda: RENT_MIN, creatorWallet: 0 },
+    });
+
+    it("Accrues the creator share of each bet fee", () => {
+      const { market, accounts } = setup();
+      placeBet(market, accounts, 10 * LAMPORTS_PER_SOL);
+      placeBet(market, accounts, 5 * LAMPORTS_PER_SOL);
+      // 1% fee on 15 SOL = 0.15 SOL; creator share 20% = 0.03 SOL
+      expect(market.creatorRevenue).to.equal(30_000_000);
+      expect(accounts.creatorRevenuePda).to.equal(RENT_MIN + 30_000_000);
+      expect(accounts.creatorWallet).to.equal(0);
+    });
+
+    it("Accepts fee shares below the rent minimum and leaves the rent on withdrawal", () => {
+      const { market, accounts } = setup();
+      placeBet(market, accounts, 10_000_000);
+      // 1% fee on 0.01 SOL = 100_000; creator share 20% = 20_000 lamports
+      expect(withdraw(market, accounts, creator.publicKey)).to.equal(20_000);
+      expect(accounts.creatorRevenuePda).to.equal(RENT_MIN);
+    });
+
+    it("Holds the rent back from a PDA created before it was funded", () => {
+      const { market, accounts } = setup();
+      accounts.creatorRevenuePda = 0;
+      placeBet(market, accounts, 10 * LAMPORTS_PER_SOL);
+      expect(withdraw(market, accounts, creator.publicKey)).to.equal(20_000_000 - RENT_MIN);
+      expect(market.creatorRevenue).to.equal(RENT_MIN);
+      expect(accounts.creatorRevenuePda).to.equal(RENT_MIN);
+    });
+
+    it("Withdraws from the revenue PDA and zeroes the counter", () => {
+      const { market, accounts } = setup();
+      placeBet(market, accounts, 10 * LAMPORTS_PER_SOL);
+      const vaultBefore = accounts.vault;
+      expect(withdraw(market, accounts, creator.publicKey)).to.equal(20_000_000);
+      expect(accounts.creatorWallet).to.equal(20_000_000);
+      expect(market.creatorRevenue).to.equal(0);
+      // Bettor funds are untouched
+      expect(accounts.vault).to.equal(vaultBefore);
+      expect(() => withdraw(market, accounts, creator.publicKey)).to.throw("ZeroAmount");
+    });
+
+    it("Only the creator may withdraw", () => {
+      const { market, accounts } = setup();
+      placeBet(market, accounts, LAMPORTS_PER_SOL);
+      expect(() => withdraw(market, accounts, oracle.publicKey)).to.throw("UnauthorizedCreator");
+    });
+  });
+
+  // ─── Market Status Transitions ──────────────────────────────────
+
+  describe("market status transitions", () => {
+    const STATUSES = ["open", "closed", "resolved", "cancelled", "settled"];
+
+    // Mirrors Market::can_transition
+    const ALLOWED: Record<string, string[]> = {
+      open: ["closed", "resolved", "cancelled"],
+      closed: ["resolved", "cancelled"],
+      resolved: ["settled", "cancelled"],
+      cancelled: [],
+      settled: [],
+    };
+    const canTransition = (from: string, to: string) => ALLOWED[from].includes(to);
+
+    // Mirrors Market::transition
+    const transition = (market: { status: string }, to: string) => {
+      if (!canTransition(market.status, to)) throw new Error("InvalidStatusTransition");
+      market.status = to;
+    };
+
+    it("Allows exactly the documented moves", () => {
+      const allowed: string[] = [];
+      for (const from of STATUSES) {
+        for (const to of STATUSES) {
+          if (canTransition(from, to)) allowed.push(`${from}->${to}`);
+        }
+      }
+      expect(allowed).to.deep.equal([
+        "open->closed",
+        "open->resolved",
+        "open->cancelled",
+        "closed->resolved",
+        "closed->cancelled",
+        "resolved->cancelled",
+        "resolved->settled",
+      ]);
+    });
+
+    it("Rejects every other move", () => {
+      let rejected = 0;
+      for (const from of STATUSES) {
+        for (const to of STATUSES) {
+          if (canTransition(from, to)) continue;
+          const market = { status: from };
+          expect(() => transition(market, to)).to.throw("InvalidStatusTransition");
+          expect(market.status).to.equal(from);
+          rejected++;
+        }
+      }
+      expect(rejected).to.equal(STATUSES.length * STATUSES.length - 7);
+    });
+
+    it("Treats Cancelled and Settled as terminal", () => {
+      expect(() => transition({ status: "cancelled" }, "resolved")).to.throw("InvalidStatusTransition");
+      expect(() => transition({ status: "settled" }, "cancelled")).to.throw("InvalidStatusTransition");
+    });
+
+    it("Never moves a market backwards", () => {
+      expect(canTransition("resolved", "open")).to.be.false;
+      expect(canTransition("closed", "open")).to.be.false;
+      expect(canTransition("settled", "resolved")).to.be.false;
+    });
+  });
+
+
+  // ─── Resolution Bounty ──────────────────────────────────────────
+
+  describe("resolution bounty", () => {
+    // Mirrors Market::bounty_share
+    function bountyShare(amount: number, bps: number): number {
+      return Math.floor((amount * bps) / 10_000);
+    }
+
+    it("pays the accrued bounty to the resolver without touching capital", () => {
+      const bps = 100; // 1%
+      const bets = [10 * LAMPORTS_PER_SOL, 30 * LAMPORTS_PER_SOL];
+      let bounty = 0;
+      let pool = 0;
+      let vault = 0;
+      for (const amount of bets) {
+        const b = bountyShare(amount, bps);
+        const stake = amount - b; // no protocol/creator fee in this sketch
+        bounty += b;
+        pool += stake;
+        vault += stake + b;
+      }
+
+      expect(bounty).to.equal(400_000_000);
+      // Mirrors apply_outcome: h ignores the bounty held in the vault
+      const hBalance = vault - bounty;
+      expect(hBalance).to.equal(pool);
+      const hRatioBps = Math.min(10_000, Math.floor((hBalance * 10_000) / pool));
+      expect(hRatioBps).to.equal(10_000);
+
+      // Resolver is paid, and the vault still covers every stake
+      const resolverGain = bounty;
+      vault -= bounty;
+      expect(resolverGain).to.equal(400_000_000);
+      expect(vault).to.equal(pool);
+    });
+
+    it("refunds the bounty pro rata when the market is cancelled", () => {
+      const yesPool = 9_900_000_000;
+      const noPool = 29_700_000_000;
+      const bounty = 400_000_000;
+      const vault = yesPool + noPool + bounty;
+
+      // Mirrors Market::refund_amount
+      const pool = yesPool + noPool;
+      const claim = (s: number) => s + Math.floor((bounty * s) / pool);
+      const owed = claim(pool);
+      expect(vault).to.be.at.least(owed);
+
+      expect(claim(yesPool)).to.equal(10 * LAMPORTS_PER_SOL);
+      expect(claim(noPool)).to.equal(30 * LAMPORTS_PER_SOL);
+    });
+
+    it("rejects a bounty above 5%", () => {
+      const setBounty = (bps: number) => {
+        if (bps > 500) throw new Error("InvalidBasisPoints");
+      };
+      expect(() => setBounty(501)).to.throw("InvalidBasisPoints");
+      expect(() => setBounty(500)).to.not.throw();
+    });
+  });
+
+
+  // ─── Invariant Verification ─────────────────────────────────────
+
+  describe("verify_invariants", () => {
+    // Mirrors verify_invariants::handler
+    function verify(yesSupply: number, noSupply: number, yesPool: number, noPool: number): boolean {
+      return yesSupply === yesPool && noSupply === noPool;
+    }
+
+    it("passes when supply tracks bets, fees and cash-outs", () => {
+      const feeBps = 100;
+      let yesPool = 0, noPool = 0, yesSupply = 0, noSupply = 0;
+
+      // Bets mint the net stake 1:1; the fee reaches neither pool nor mint
+      for (const [side, amount] of [["yes", 5 * LAMPORTS_PER_SOL], ["no", 3 * LAMPORTS_PER_SOL]] as const) {
+        const stake = amount - Math.floor((amount * feeBps) / 10_000);
+        if (side === "yes") { yesPool += stake; yesSupply += stake; }
+        else { noPool += stake; noSupply += stake; }
+      }
+
+      // A cash-out burns exactly the stake it takes out of the pool
+      const cashedOut = 2 * LAMPORTS_PER_SOL;
+      yesPool -= cashedOut;
+      yesSupply -= cashedOut;
+
+      expect(verify(yesSupply, noSupply, yesPool, noPool)).to.be.true;
+    });
+
+    it("fails on a drifted pool", () => {
+      const yesPool = 4 * LAMPORTS_PER_SOL;
+      const noPool = 3 * LAMPORTS_PER_SOL;
+      // A pool bumped without a matching mint
+      const drifted = yesPool + 1;
+
+      const checkInvariant = (yesSupply: number, noSupply: number) => {
+        if (!verify(yesPool, noPool, yesSupply, noSupply)) throw new Error("InvariantViolation");
+      };
+      expect(() => checkInvariant(drifted, noPool)).to.throw("InvariantViolation");
+      expect(() => checkInvariant(yesPool, noPool)).to.not.throw();
+    });
+  });
+
+
+  // ─── Trigger Resolution ─────────────────────────────────────────
+
+  describe("trigger_resolution", () => {
+    const deadline = 1_700_000_000;
+    const target = 1_000_000;
+
+    // Mirrors rule_outcome for a MarketCapTarget market
+    function trigger(now: number, publishedAt: number, observed: number, maxStaleness = 0): "Yes" | "No" {
+      if (now < deadline) throw new Error("MarketNotExpired");
+      // Mirrors GlobalConfig::check_reading_time
+      if (publishedAt < deadline) throw new Error("StaleOracleReading");
+      if (maxStaleness > 0 && publishedAt > deadline + maxStaleness) throw new Error("StaleOracleReading");
+      return observed >= target ? "Yes" : "No";
+    }
+
+    it("resolves from the stored feed after the deadline", () => {
+      expect(trigger(deadline + 60, deadline + 30, 1_500_000)).to.equal("Yes");
+      expect(trigger(deadline + 60, deadline + 30, 900_000)).to.equal("No");
+    });
+
+    it("rejects a trigger before the deadline", () => {
+      expect(() => trigger(deadline - 1, deadline - 10, 1_500_000)).to.throw("MarketNotExpired");
+    });
+
+    it("rejects a reading published before the deadline", () => {
+      expect(() => trigger(deadline + 60, deadline - 10, 1_500_000)).to.throw("StaleOracleReading");
+    });
+
+    it("rejects a reading published past the staleness bound", () => {
+      const now = deadline + 7 * 86_400;
+      expect(trigger(now, deadline + 300, 1_500_000, 300)).to.equal("Yes");
+      expect(() => trigger(now, deadline + 301, 1_500_000, 300)).to.throw("StaleOracleReading");
+      // 0 leaves it unbounded
+      expect(trigger(now, deadline + 86_400, 1_500_000)).to.equal("Yes");
+    });
+
+    it("only opts in rules the program can evaluate", () => {
+      const optIn = (rule: string) => {
+        if (["OracleCustom", "Scalar"].includes(rule)) throw new Error("InvalidMarketRule");
+      };
+      expect(() => optIn("MarketCapTarget")).to.not.throw();
+      expect(() => optIn("OracleCustom")).to.throw("InvalidMarketRule");
+    });
+  });
+
+
+  // ─── Settlement Receipts ────────────────────────────────────────
+
+  describe("settlement receipts", () => {
+    // Mirrors issue_receipt plus the receipt's `init` constraint
+    function settleWithReceipt(
+      receipts: Set<string>,
+      marketIssues: boolean,
+      user: string,
+      passReceipt: boolean,
+    ): void {
+      if (passReceipt !== marketIssues) throw new Error("SettlementReceiptMismatch");
+      if (!passReceipt) return;
+      if (receipts.has(user)) throw new Error("already in use");
+      receipts.add(user);
+    }
+
+    it("issues exactly one receipt per settlement", () => {
+      const receipts = new Set<string>();
+      settleWithReceipt(receipts, true, "alice", true);
+      settleWithReceipt(receipts, true, "bob", true);
+      expect(receipts.size).to.equal(2);
+
+      // A second settle fails on AlreadySettled, and the receipt PDA already exists
+      expect(() => settleWithReceipt(receipts, true, "alice", true)).to.throw("already in use");
+      expect(receipts.size).to.equal(2);
+    });
+
+    it("requires the receipt exactly when the market issues them", () => {
+      const receipts = new Set<string>();
+      expect(() => settleWithReceipt(receipts, true, "alice", false)).to.throw("SettlementReceiptMismatch");
+      expect(() => settleWithReceipt(receipts, false, "alice", true)).to.throw("SettlementReceiptMismatch");
+      settleWithReceipt(receipts, false, "alice", false);
+      expect(receipts.size).to.equal(0);
+    });
+
+    it("derives one receipt address per market and user", () => {
+      const market = Keypair.generate().publicKey;
+      const [alice] = PublicKey.findProgramAddressSync(
+        [Buffer.from("settlement_receipt"), market.toBuffer(), creator.publicKey.toBuffer()],
+        program.programId,
+      );
+      const [bob] = PublicKey.findProgramAddressSync(
+        [Buffer.from("settlement_receipt"), market.toBuffer(), oracle.publicKey.toBuffer()],
+        program.programId,
+      );
+      expect(alice.equals(bob)).to.be.false;
+    });
+  });
+
+
+  // ─── Maximum Market Duration ────────────────────────────────────
+
+  describe("max market duration", () => {
+    const now = 1_700_000_000;
+    const maxDuration = 365 * 24 * 60 * 60;
+
+    // Mirrors the deadline checks in create_market
+    function checkDeadline(deadline: number, max: number): void {
+      if (deadline <= now) throw new Error("DeadlineInPast");
+      if (max !== 0 && deadline > now + max) throw new Error("DeadlineTooFar");
+    }
+
+    it("accepts a deadline within the maximum", () => {
+      expect(() => checkDeadline(now + 30 * 24 * 60 * 60, maxDuration)).to.not.throw();
+      expect(() => checkDeadline(now + maxDuration, maxDuration)).to.not.throw();
+    });
+
+    it("rejects a deadline beyond the maximum", () => {
+      expect(() => checkDeadline(now + maxDuration + 1, maxDuration)).to.throw("DeadlineTooFar");
+      expect(() => checkDeadline(Number.MAX_SAFE_INTEGER, maxDuration)).to.throw("DeadlineTooFar");
+    });
+
+    it("leaves the deadline unbounded when the maximum is 0", () => {
+      expect(() => checkDeadline(now + 100 * maxDuration, 0)).to.not.throw();
+    });
+
+    it("holds extend_deadline to the same horizon", () => {
+      // Mirrors extend_deadline: each extension is checked against the clock
+      const extend = (deadline: number, newDeadline: number, at: number) => {
+        if (maxDuration !== 0 && newDeadline > at + maxDuration) throw new Error("DeadlineTooFar");
+        return newDeadline;
+      };
+      let deadline = now + maxDuration - 86_400;
+      deadline = extend(deadline, now + maxDuration, now);
+      expect(deadline).to.equal(now + maxDuration);
+      expect(() => extend(deadline, deadline + 86_400, now)).to.throw("DeadlineTooFar");
+      // A day later the horizon has moved by a day, and no further
+      expect(extend(deadline, deadline + 86_400, now + 86_400)).to.equal(now + maxDuration + 86_400);
+    });
+  });
+
+  // ─── Position NFTs ──────────────────────────────────────────────
+
+  describe("position NFTs", () => {
+    type Nft = { owner: string; supply: number; name: string; symbol: string; uri: string };
+
+    // Mirrors mint_position_nft in place_bet: one supply-1 NFT per position
+    function placeNftBet(nfts: Map<string, Nft>, user: string, side: "YES" | "NO", stake: number): void {
+      if (nfts.has(user)) throw new Error("PositionNftAlreadyMinted");
+      nfts.set(user, {
+        owner: user,
+        supply: 1,
+        name: `${side} ${stake}`,
+        symbol: "PERC",
+        uri: "ipfs://question",
+      });
+    }
+
+    // Mirrors burn_position_nft in settle
+    function settleNft(nfts: Map<string, Nft>, user: string, holder: string): void {
+      const nft = nfts.get(user);
+      if (!nft || nft.supply === 0) throw new Error("PositionNftMismatch");
+      if (nft.owner !== holder) throw new Error("owner does not match");
+      nft.supply = 0;
+    }
+
+    it("mints an NFT whose metadata records the side and stake", () => {
+      const nfts = new Map<string, Nft>();
+      placeNftBet(nfts, "alice", "YES", 995_000_000);
+      const nft = nfts.get("alice")!;
+      expect(nft.supply).to.equal(1);
+      expect(nft.name).to.equal("YES 995000000");
+      expect(nft.symbol).to.equal("PERC");
+      expect(nft.uri).to.equal("ipfs://question");
+      // Metaplex caps names at 32 bytes; the longest side and stake fit
+      expect(`YES ${"18446744073709551615"}`.length).to.be.at.most(32);
+    });
+
+    it("allows only one bet per position", () => {
+      const nfts = new Map<string, Nft>();
+      placeNftBet(nfts, "alice", "NO", 1_000);
+      expect(() => placeNftBet(nfts, "alice", "NO", 1_000)).to.throw("PositionNftAlreadyMinted");
+    });
+
+    it("burns the NFT on settle", () => {
+      const nfts = new Map<string, Nft>();
+      placeNftBet(nfts, "alice", "YES", 1_000);
+      settleNft(nfts, "alice", "alice");
+      expect(nfts.get("alice")!.supply).to.equal(0);
+      expect(() => settleNft(nfts, "alice", "alice")).to.throw("PositionNftMismatch");
+    });
+
+    it("derives one NFT mint per position", () => {
+      const market = Keypair.generate().publicKey;
+      const [alicePosition] = PublicKey.findProgramAddressSync(
+        [Buffer.from("position"), market.toBuffer(), creator.publicKey.toBuffer()],
+        program.programId,
+      );
+      const [bobPosition] = PublicKey.findProgramAddressSync(
+        [Buffer.from("position"), market.toBuffer(), oracle.publicKey.toBuffer()],
+        program.programId,
+      );
+      const [aliceNft] = PublicKey.findProgramAddressSync(
+        [Buffer.from("position_nft"), alicePosition.toBuffer()],
+        program.programId,
+      );
+      const [bobNft] = PublicKey.findProgramAddressSync(
+        [Buffer.from("position_nft"), bobPosition.toBuffer()],
+        program.programId,
+      );
+      expect(aliceNft.equals(bobNft)).to.be.false;
+    });
+  });
 });
 